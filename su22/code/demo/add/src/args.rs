@@ -0,0 +1,352 @@
+use std::fmt::{self, Display};
+
+use cli_output::{OutputFormat, ParseFormatError};
+
+use crate::error::CliError;
+
+pub const USAGE: &str = "\
+Usage: add [OPTIONS] < NUMBERS
+       add --csv|--tsv [--column COL] [--group-by COL] [OPTIONS] < TABLE
+
+Reads one number per line from stdin and prints the sum, count, min, max
+and mean. Numbers are exact decimals such as 12, -3.5 or +0.125.
+
+With --csv or --tsv the input is a table with RFC 4180 quoting, and one
+column is totalled. COL is a 1-based index or a header name. The first
+row is taken as a header when a column is named or when its value is not a
+number. --group-by prints one sum per distinct value, sorted, with tabs,
+line breaks and backslashes in the value written as \\t, \\n, \\r and \\\\.
+A blank line has no group cell, so --blank zero counts it in the overall
+statistics that json and ndjson print, but in no group.
+
+Options:
+  --blank MODE         blank lines or cells: skip (default), zero or error
+  --comment PREFIX     skip lines starting with PREFIX (default '#')
+  --no-comments        treat every non-blank line as a number
+  --on-error MODE      bad lines: abort (default) with the line number, or
+                       skip with a warning on stderr
+  --csv, --tsv         read comma- or tab-separated records
+  --column COL         the column to total (default 1)
+  --group-by COL       total separately for each value of COL
+  --header             the first row is a header
+  --no-header          the first row is data
+  --format FORMAT      plain (default), table, json or ndjson
+  -h, --help           print this help
+
+--format json prints one document when the input ends; ndjson prints one
+object per line, warnings included, as they happen. Both put decimals in
+strings and keep warnings off stderr.
+";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Blank {
+    Skip,
+    /// Count the line as a zero.
+    Zero,
+    Error,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnError {
+    Abort,
+    /// Report the line on stderr and carry on.
+    Skip,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Column {
+    /// 0-based, though written 1-based on the command line.
+    Index(usize),
+    Name(String),
+}
+
+impl Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Column::Index(index) => write!(f, "column {}", index + 1),
+            Column::Name(name) => write!(f, "column {name:?}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Header {
+    /// A header if a column is named or the first value is not a number.
+    Auto,
+    Present,
+    Absent,
+}
+
+/// How to read delimited input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Table {
+    pub delimiter: u8,
+    pub column: Column,
+    pub group_by: Option<Column>,
+    pub header: Header,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub blank: Blank,
+    /// Lines starting with this, after leading whitespace, are skipped.
+    pub comment: Option<String>,
+    pub on_error: OnError,
+    /// Set by `--csv` or `--tsv`; plain input has one number per line.
+    pub table: Option<Table>,
+    pub format: OutputFormat,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            blank: Blank::Skip,
+            comment: Some("#".to_string()),
+            on_error: OnError::Abort,
+            table: None,
+            format: OutputFormat::Plain,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Sum(Options),
+}
+
+fn invalid(name: &str, value: &str, expected: &str) -> CliError {
+    CliError::Usage(format!("invalid {name} {value:?} (expected {expected})"))
+}
+
+fn parse_format(value: &str) -> Result<OutputFormat, CliError> {
+    value
+        .parse()
+        .map_err(|error: ParseFormatError| CliError::Usage(error.to_string()))
+}
+
+fn parse_column(value: &str) -> Result<Column, CliError> {
+    if value.is_empty() {
+        return Err(CliError::Usage("column names cannot be empty".into()));
+    }
+    if !value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Ok(Column::Name(value.to_string()));
+    }
+    match value.parse::<usize>() {
+        Ok(index) if index > 0 => Ok(Column::Index(index - 1)),
+        _ => Err(invalid("column", value, "a number from 1 or a header name")),
+    }
+}
+
+/// Parses the arguments after the program name; option values come as the
+/// next argument or after `=`.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, CliError> {
+    let mut options = Options::default();
+    let mut delimiter = None;
+    let mut column = None;
+    let mut group_by = None;
+    let mut header = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError::Usage(format!("{name} needs a value")))
+        };
+        match name {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--blank" => {
+                options.blank = match value()?.as_str() {
+                    "skip" => Blank::Skip,
+                    "zero" => Blank::Zero,
+                    "error" => Blank::Error,
+                    other => return Err(invalid("blank mode", other, "skip, zero or error")),
+                }
+            }
+            "--comment" => {
+                let prefix = value()?;
+                if prefix.is_empty() {
+                    return Err(CliError::Usage("--comment needs a non-empty prefix".into()));
+                }
+                options.comment = Some(prefix);
+            }
+            "--no-comments" => options.comment = None,
+            "--csv" => delimiter = Some(b','),
+            "--tsv" => delimiter = Some(b'\t'),
+            "--column" => column = Some(parse_column(&value()?)?),
+            "--group-by" => group_by = Some(parse_column(&value()?)?),
+            "--header" => header = Some(Header::Present),
+            "--no-header" => header = Some(Header::Absent),
+            "--format" => options.format = parse_format(&value()?)?,
+            "--on-error" => {
+                options.on_error = match value()?.as_str() {
+                    "abort" => OnError::Abort,
+                    "skip" => OnError::Skip,
+                    other => return Err(invalid("error mode", other, "abort or skip")),
+                }
+            }
+            _ if name.starts_with('-') => {
+                return Err(CliError::Usage(format!("unknown option {name:?}")))
+            }
+            _ => {
+                return Err(CliError::Usage(format!(
+                    "unexpected argument {arg:?}; numbers are read from stdin"
+                )))
+            }
+        }
+    }
+    match delimiter {
+        Some(delimiter) => {
+            let column = column.unwrap_or(Column::Index(0));
+            let header = header.unwrap_or(Header::Auto);
+            let named = [Some(&column), group_by.as_ref()]
+                .into_iter()
+                .any(|column| matches!(column, Some(Column::Name(_))));
+            if named && header == Header::Absent {
+                return Err(CliError::Usage(
+                    "columns can only be named when there is a header".into(),
+                ));
+            }
+            options.table = Some(Table {
+                delimiter,
+                column,
+                group_by,
+                header,
+            });
+        }
+        None if column.is_some() || group_by.is_some() || header.is_some() => {
+            return Err(CliError::Usage(
+                "--column, --group-by and --header need --csv or --tsv".into(),
+            ))
+        }
+        None => {}
+    }
+    Ok(Command::Sum(options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, CliError> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!(parse(&[]), Ok(Command::Sum(Options::default())));
+        assert_eq!(
+            parse(&["--blank", "zero", "--comment=//", "--on-error", "skip"]),
+            Ok(Command::Sum(Options {
+                blank: Blank::Zero,
+                comment: Some("//".into()),
+                on_error: OnError::Skip,
+                table: None,
+                format: OutputFormat::Plain,
+            }))
+        );
+        assert_eq!(
+            parse(&[
+                "--comment",
+                ";",
+                "--no-comments",
+                "--blank=error",
+                "--format=json"
+            ]),
+            Ok(Command::Sum(Options {
+                blank: Blank::Error,
+                comment: None,
+                on_error: OnError::Abort,
+                table: None,
+                format: OutputFormat::Json,
+            }))
+        );
+        assert_eq!(
+            parse(&["--blank", "bogus", "-h"]),
+            Err(invalid("blank mode", "bogus", "skip, zero or error"))
+        );
+        assert_eq!(parse(&["-h", "--blank", "bogus"]), Ok(Command::Help));
+    }
+
+    fn table(args: &[&str]) -> Table {
+        match parse(args) {
+            Ok(Command::Sum(Options {
+                table: Some(table), ..
+            })) => table,
+            other => panic!("{args:?} gave {other:?}"),
+        }
+    }
+
+    #[test]
+    fn table_options() {
+        assert_eq!(
+            table(&["--csv"]),
+            Table {
+                delimiter: b',',
+                column: Column::Index(0),
+                group_by: None,
+                header: Header::Auto,
+            }
+        );
+        assert_eq!(
+            table(&["--tsv", "--column", "3", "--group-by=region", "--header"]),
+            Table {
+                delimiter: b'\t',
+                column: Column::Index(2),
+                group_by: Some(Column::Name("region".into())),
+                header: Header::Present,
+            }
+        );
+        assert_eq!(
+            table(&["--column=price", "--csv"]).column,
+            Column::Name("price".into())
+        );
+        assert_eq!(table(&["--csv", "--no-header"]).header, Header::Absent);
+        assert_eq!(Column::Index(0).to_string(), "column 1");
+        assert_eq!(Column::Name("qty".into()).to_string(), "column \"qty\"");
+    }
+
+    #[test]
+    fn usage_errors() {
+        let usage = |message: &str| Err(CliError::Usage(message.into()));
+        assert_eq!(parse(&["--on-error"]), usage("--on-error needs a value"));
+        assert_eq!(
+            parse(&["--format=yaml"]),
+            usage("invalid format \"yaml\" (expected plain, table, json or ndjson)")
+        );
+        assert_eq!(
+            parse(&["--on-error", "ignore"]),
+            usage("invalid error mode \"ignore\" (expected abort or skip)")
+        );
+        assert_eq!(
+            parse(&["--comment="]),
+            usage("--comment needs a non-empty prefix")
+        );
+        assert_eq!(parse(&["-x"]), usage("unknown option \"-x\""));
+        assert_eq!(
+            parse(&["numbers.txt"]),
+            usage("unexpected argument \"numbers.txt\"; numbers are read from stdin")
+        );
+        assert_eq!(
+            parse(&["--csv", "--column", "0"]),
+            usage("invalid column \"0\" (expected a number from 1 or a header name)")
+        );
+        assert_eq!(
+            parse(&["--csv", "--group-by="]),
+            usage("column names cannot be empty")
+        );
+        assert_eq!(
+            parse(&["--column", "2"]),
+            usage("--column, --group-by and --header need --csv or --tsv")
+        );
+        assert_eq!(
+            parse(&["--tsv", "--no-header", "--group-by", "name"]),
+            usage("columns can only be named when there is a header")
+        );
+    }
+}
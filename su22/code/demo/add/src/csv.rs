@@ -0,0 +1,246 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::BufRead;
+use std::str;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CsvError {
+    Io(String),
+    /// A quoted field was still open at the end of the input.
+    UnterminatedQuote {
+        line: u64,
+    },
+    /// A closing quote was followed by something other than a delimiter.
+    AfterQuote {
+        line: u64,
+    },
+    InvalidUtf8 {
+        line: u64,
+    },
+}
+
+impl CsvError {
+    /// The line the broken record starts on, if the input itself is at fault.
+    pub fn line(&self) -> Option<u64> {
+        match *self {
+            CsvError::Io(_) => None,
+            CsvError::UnterminatedQuote { line }
+            | CsvError::AfterQuote { line }
+            | CsvError::InvalidUtf8 { line } => Some(line),
+        }
+    }
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(message) => write!(f, "cannot read input: {message}"),
+            CsvError::UnterminatedQuote { .. } => f.write_str("quoted field is never closed"),
+            CsvError::AfterQuote { .. } => {
+                f.write_str("expected a delimiter after the closing quote")
+            }
+            CsvError::InvalidUtf8 { .. } => f.write_str("record is not valid UTF-8"),
+        }
+    }
+}
+
+impl Error for CsvError {}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// At the start of a field.
+    Start,
+    Unquoted,
+    Quoted,
+    /// Just past the closing quote of a quoted field.
+    Closed,
+}
+
+/// Streams RFC 4180 records: fields are split on `delimiter`, may be wrapped
+/// in double quotes to hold delimiters, `""` or line breaks, and records end
+/// at `\n` or `\r\n` outside quotes. A stray quote inside an unquoted field
+/// is kept as an ordinary character.
+pub struct Reader<R> {
+    input: R,
+    delimiter: u8,
+    line: u64,
+    buffer: Vec<u8>,
+    field: Vec<u8>,
+}
+
+impl<R: BufRead> Reader<R> {
+    pub fn new(input: R, delimiter: u8) -> Self {
+        Reader {
+            input,
+            delimiter,
+            line: 0,
+            buffer: Vec::new(),
+            field: Vec::new(),
+        }
+    }
+
+    fn read_line(&mut self) -> Result<bool, CsvError> {
+        self.buffer.clear();
+        let read = self
+            .input
+            .read_until(b'\n', &mut self.buffer)
+            .map_err(|error| CsvError::Io(error.to_string()))?;
+        if read > 0 {
+            self.line += 1;
+        }
+        Ok(read > 0)
+    }
+
+    /// Reads the next record into `fields` and returns the line it starts
+    /// on, or `None` at the end of the input. After an error the rest of the
+    /// offending line is dropped, so reading can carry on with the next one.
+    pub fn read_record(&mut self, fields: &mut Vec<String>) -> Result<Option<u64>, CsvError> {
+        fields.clear();
+        if !self.read_line()? {
+            return Ok(None);
+        }
+        let start = self.line;
+        let mut state = State::Start;
+        self.field.clear();
+        loop {
+            let content = self
+                .buffer
+                .strip_suffix(b"\n")
+                .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+                .unwrap_or(&self.buffer);
+            let mut bytes = content.iter().copied().peekable();
+            while let Some(byte) = bytes.next() {
+                state = match (state, byte) {
+                    (State::Quoted, b'"') if bytes.peek() == Some(&b'"') => {
+                        bytes.next();
+                        self.field.push(b'"');
+                        State::Quoted
+                    }
+                    (State::Quoted, b'"') => State::Closed,
+                    (State::Quoted, _) => {
+                        self.field.push(byte);
+                        State::Quoted
+                    }
+                    (State::Start, b'"') => State::Quoted,
+                    (State::Start | State::Unquoted | State::Closed, _)
+                        if byte == self.delimiter =>
+                    {
+                        fields.push(take_field(&mut self.field, start)?);
+                        State::Start
+                    }
+                    (State::Closed, _) => return Err(CsvError::AfterQuote { line: start }),
+                    (State::Start | State::Unquoted, _) => {
+                        self.field.push(byte);
+                        State::Unquoted
+                    }
+                };
+            }
+            if state != State::Quoted {
+                fields.push(take_field(&mut self.field, start)?);
+                return Ok(Some(start));
+            }
+            // The line break belongs to the quoted field.
+            let newline = &self.buffer[content.len()..];
+            if newline.is_empty() {
+                return Err(CsvError::UnterminatedQuote { line: start });
+            }
+            self.field.extend_from_slice(newline);
+            if !self.read_line()? {
+                return Err(CsvError::UnterminatedQuote { line: start });
+            }
+        }
+    }
+}
+
+fn take_field(field: &mut Vec<u8>, line: u64) -> Result<String, CsvError> {
+    let text = str::from_utf8(field)
+        .map(str::to_owned)
+        .map_err(|_| CsvError::InvalidUtf8 { line })?;
+    field.clear();
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(input: &str, delimiter: u8) -> Vec<Result<(u64, Vec<String>), CsvError>> {
+        let mut reader = Reader::new(input.as_bytes(), delimiter);
+        let mut fields = Vec::new();
+        let mut records = Vec::new();
+        loop {
+            match reader.read_record(&mut fields) {
+                Ok(Some(line)) => records.push(Ok((line, fields.clone()))),
+                Ok(None) => return records,
+                Err(error) => records.push(Err(error)),
+            }
+        }
+    }
+
+    fn ok(line: u64, fields: &[&str]) -> Result<(u64, Vec<String>), CsvError> {
+        Ok((line, fields.iter().map(|field| field.to_string()).collect()))
+    }
+
+    #[test]
+    fn splits_fields() {
+        assert_eq!(
+            records("a,b,c\n1,,3\r\n\n,\n", b','),
+            [
+                ok(1, &["a", "b", "c"]),
+                ok(2, &["1", "", "3"]),
+                ok(3, &[""]),
+                ok(4, &["", ""]),
+            ]
+        );
+        assert_eq!(records("x,y", b','), [ok(1, &["x", "y"])]);
+        assert_eq!(records("", b','), []);
+        assert_eq!(
+            records("a\tb,c\t 2 \n", b'\t'),
+            [ok(1, &["a", "b,c", " 2 "])]
+        );
+    }
+
+    #[test]
+    fn quoted_fields() {
+        assert_eq!(
+            records(
+                "\"a,b\",\"say \"\"hi\"\"\",\"\"\n\"two\nlines\",x\r\n\"crlf\r\nkept\"\nnext",
+                b','
+            ),
+            [
+                ok(1, &["a,b", "say \"hi\"", ""]),
+                ok(2, &["two\nlines", "x"]),
+                ok(4, &["crlf\r\nkept"]),
+                ok(6, &["next"]),
+            ]
+        );
+        // Quotes only matter at the start of a field.
+        assert_eq!(records("5\" pipe,1\n", b','), [ok(1, &["5\" pipe", "1"])]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            records("\"ab\"c,1\n2\n", b','),
+            [Err(CsvError::AfterQuote { line: 1 }), ok(2, &["2"])]
+        );
+        assert_eq!(
+            records("1\n\"open,\n2\n", b','),
+            [ok(1, &["1"]), Err(CsvError::UnterminatedQuote { line: 2 })]
+        );
+        assert_eq!(records("\"x\"", b','), [ok(1, &["x"])]);
+        let mut reader = Reader::new(&b"\xff,1\nok\n"[..], b',');
+        let mut fields = Vec::new();
+        assert_eq!(
+            reader.read_record(&mut fields),
+            Err(CsvError::InvalidUtf8 { line: 1 })
+        );
+        assert_eq!(reader.read_record(&mut fields), Ok(Some(2)));
+        assert_eq!(fields, ["ok"]);
+        assert_eq!(CsvError::AfterQuote { line: 7 }.line(), Some(7));
+        assert_eq!(
+            CsvError::UnterminatedQuote { line: 2 }.to_string(),
+            "quoted field is never closed"
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CliError {
+    Usage(String),
+    /// A line that could not be used, with its 1-based number.
+    Line {
+        line: u64,
+        message: String,
+    },
+    Io(String),
+}
+
+impl CliError {
+    /// 2 for bad arguments or input, 1 when reading fails.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Usage(_) | CliError::Line { .. } => 2,
+            CliError::Io(_) => 1,
+        }
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) | CliError::Io(message) => f.write_str(message),
+            CliError::Line { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl Error for CliError {}
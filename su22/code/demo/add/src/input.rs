@@ -0,0 +1,183 @@
+use std::io::BufRead;
+use std::str;
+
+use adder::Decimal;
+
+use crate::args::{Blank, OnError, Options};
+use crate::error::CliError;
+use crate::stats::Stats;
+
+/// What a single line of input holds once surrounding whitespace is gone.
+#[derive(Debug, PartialEq, Eq)]
+enum Line {
+    Number(Decimal),
+    Ignored,
+}
+
+fn classify(bytes: &[u8], options: &Options) -> Result<Line, String> {
+    let text = str::from_utf8(bytes).map_err(|_| "line is not valid UTF-8".to_string())?;
+    let text = text.trim();
+    if text.is_empty() {
+        return match options.blank {
+            Blank::Skip => Ok(Line::Ignored),
+            Blank::Zero => Ok(Line::Number(Decimal::zero())),
+            Blank::Error => Err("blank line".to_string()),
+        };
+    }
+    if let Some(prefix) = &options.comment {
+        if text.starts_with(prefix.as_str()) {
+            return Ok(Line::Ignored);
+        }
+    }
+    parse_number(text).map(Line::Number)
+}
+
+/// Parses an already trimmed number, describing the failure for a warning.
+pub fn parse_number(text: &str) -> Result<Decimal, String> {
+    text.parse()
+        .map_err(|error| format!("invalid number {text:?}: {error}"))
+}
+
+/// Returns `error` under `--on-error abort`; otherwise hands it to `warn`
+/// so the caller can count the line as skipped.
+pub fn recover(
+    error: CliError,
+    on_error: OnError,
+    warn: &mut impl FnMut(&CliError),
+) -> Result<(), CliError> {
+    match on_error {
+        OnError::Abort => Err(error),
+        OnError::Skip => {
+            warn(&error);
+            Ok(())
+        }
+    }
+}
+
+/// Folds every line of `input` into a `Stats`, reusing one line buffer so
+/// memory stays flat however long the stream is. Bad lines either abort
+/// with their line number or, with `--on-error skip`, go to `warn`.
+pub fn read_numbers<R: BufRead>(
+    mut input: R,
+    options: &Options,
+    mut warn: impl FnMut(&CliError),
+) -> Result<Stats, CliError> {
+    let mut stats = Stats::new();
+    let mut buffer = Vec::new();
+    let mut line = 0;
+    loop {
+        buffer.clear();
+        let read = input
+            .read_until(b'\n', &mut buffer)
+            .map_err(|error| CliError::Io(format!("cannot read input: {error}")))?;
+        if read == 0 {
+            return Ok(stats);
+        }
+        line += 1;
+        match classify(&buffer, options) {
+            Ok(Line::Number(value)) => stats.push(value),
+            Ok(Line::Ignored) => {}
+            Err(message) => {
+                recover(
+                    CliError::Line { line, message },
+                    options.on_error,
+                    &mut warn,
+                )?;
+                stats.skip();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(input: &[u8], options: &Options) -> (Result<Stats, CliError>, Vec<String>) {
+        let mut warnings = Vec::new();
+        let result = read_numbers(input, options, |error| warnings.push(error.to_string()));
+        (result, warnings)
+    }
+
+    fn summary(input: &[u8], options: &Options) -> String {
+        run(input, options).0.unwrap().to_string()
+    }
+
+    #[test]
+    fn classifies_lines() {
+        let options = Options::default();
+        let dec = |src: &str| Ok(Line::Number(src.parse().unwrap()));
+        assert_eq!(classify(b"  -3.50\r\n", &options), dec("-3.50"));
+        assert_eq!(classify(b"+7", &options), dec("7"));
+        assert_eq!(classify(b"\t\n", &options), Ok(Line::Ignored));
+        assert_eq!(classify(b"  # total", &options), Ok(Line::Ignored));
+        assert_eq!(
+            classify(b"1,000", &options),
+            Err("invalid number \"1,000\": invalid digit ',' at index 1".into())
+        );
+        assert_eq!(
+            classify(b"\xff1", &options),
+            Err("line is not valid UTF-8".into())
+        );
+
+        let strict = Options {
+            blank: Blank::Error,
+            comment: None,
+            ..Options::default()
+        };
+        assert_eq!(classify(b"", &strict), Err("blank line".into()));
+        assert!(classify(b"# 5", &strict).is_err());
+    }
+
+    #[test]
+    fn sums_a_stream() {
+        let input = b"# prices\n1.25\n\n  3\n-0.5\n10";
+        assert_eq!(
+            summary(input, &Options::default()),
+            "sum: 13.75\ncount: 4\nmin: -0.5\nmax: 10\nmean: 3.4375"
+        );
+        assert_eq!(summary(b"", &Options::default()), "sum: 0\ncount: 0");
+
+        let zeros = Options {
+            blank: Blank::Zero,
+            comment: Some("//".into()),
+            ..Options::default()
+        };
+        assert_eq!(
+            summary(b"4\n\n// skip me\n2\n", &zeros),
+            "sum: 6\ncount: 3\nmin: 0\nmax: 4\nmean: 2"
+        );
+    }
+
+    #[test]
+    fn aborts_or_skips_bad_lines() {
+        let input = b"1\n2\nthree\n4\n\n";
+        let (result, warnings) = run(input, &Options::default());
+        assert_eq!(
+            result,
+            Err(CliError::Line {
+                line: 3,
+                message: "invalid number \"three\": invalid digit 't' at index 0".into(),
+            })
+        );
+        assert!(warnings.is_empty());
+
+        let options = Options {
+            blank: Blank::Error,
+            on_error: OnError::Skip,
+            ..Options::default()
+        };
+        let (result, warnings) = run(input, &options);
+        assert_eq!(
+            result.unwrap().to_string(),
+            "sum: 7\ncount: 3\nmin: 1\nmax: 4\nmean: 2.3333333333333333333333333333\nskipped: 2"
+        );
+        assert_eq!(
+            warnings,
+            [
+                "line 3: invalid number \"three\": invalid digit 't' at index 0",
+                "line 5: blank line",
+            ]
+        );
+    }
+}
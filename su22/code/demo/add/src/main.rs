@@ -1,3 +1,46 @@
-fn main() {
-    println!("Hello, world!");
+mod args;
+mod csv;
+mod error;
+mod input;
+mod report;
+mod stats;
+mod table;
+
+use std::env;
+use std::io;
+use std::process::ExitCode;
+
+use args::{parse_args, Command, USAGE};
+use error::CliError;
+use report::Reporter;
+use stats::Summary;
+
+fn run() -> Result<(), CliError> {
+    match parse_args(env::args().skip(1))? {
+        Command::Help => print!("{USAGE}"),
+        Command::Sum(options) => {
+            let stdin = io::stdin().lock();
+            let mut reporter = Reporter::new(options.format);
+            let warn = |warning: &CliError| reporter.warn(warning);
+            let result = match &options.table {
+                Some(table) => table::read_table(stdin, &options, table, warn),
+                None => input::read_numbers(stdin, &options, warn).map(Summary::Total),
+            };
+            reporter.finish(result)?;
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("add: {error}");
+            if let CliError::Usage(_) = error {
+                eprintln!("Try 'add --help' for more information.");
+            }
+            ExitCode::from(error.exit_code())
+        }
+    }
 }
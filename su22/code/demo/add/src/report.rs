@@ -0,0 +1,253 @@
+use cli_output::{
+    json_document, json_or_null, json_string, ndjson_record, text_table, Diagnostic, OutputFormat,
+    Severity,
+};
+
+use crate::error::CliError;
+use crate::stats::{escape_key, Stats, Summary};
+
+/// The JSON members every set of statistics has. Decimals are strings so
+/// that no digits are lost; `min`, `max` and `mean` are null when nothing
+/// was counted.
+fn stats_members(stats: &Stats) -> String {
+    let decimal = |value: &dyn ToString| json_string(&value.to_string());
+    format!(
+        "\"sum\":{},\"count\":{},\"min\":{},\"max\":{},\"mean\":{}",
+        decimal(stats.sum()),
+        stats.count(),
+        json_or_null(stats.min().map(|min| decimal(min))),
+        json_or_null(stats.max().map(|max| decimal(max))),
+        json_or_null(stats.mean().map(|mean| decimal(&mean))),
+    )
+}
+
+/// The position is the line number, and is null for failures that are not
+/// about one line, such as a read error.
+fn diagnostic(severity: Severity, error: &CliError) -> Diagnostic {
+    let (position, message) = match error {
+        CliError::Line { line, message } => (Some(*line), message.clone()),
+        other => (None, other.to_string()),
+    };
+    Diagnostic {
+        severity,
+        position,
+        input: None,
+        message,
+    }
+}
+
+/// The overall statistics, shared by the JSON document and the ndjson
+/// result.
+fn result_members(total: &Stats) -> String {
+    format!(
+        "\"stats\":{{{}}},\"skipped\":{}",
+        stats_members(total),
+        total.skipped()
+    )
+}
+
+fn table(summary: &Summary) -> String {
+    let cells = |stats: &Stats| {
+        let missing = || "-".to_string();
+        vec![
+            stats.sum().to_string(),
+            stats.count().to_string(),
+            stats.min().map_or_else(missing, ToString::to_string),
+            stats.max().map_or_else(missing, ToString::to_string),
+            stats.mean().map_or_else(missing, |mean| mean.to_string()),
+        ]
+    };
+    match summary {
+        Summary::Total(stats) => {
+            let mut row = cells(stats);
+            row.push(stats.skipped().to_string());
+            let headers = ["sum", "count", "min", "max", "mean", "skipped"];
+            text_table(&headers, &[row], 0)
+        }
+        Summary::Groups { groups, total } => {
+            let rows: Vec<Vec<String>> = groups
+                .iter()
+                .map(|(key, stats)| [vec![escape_key(key)], cells(stats)].concat())
+                .collect();
+            let headers = ["group", "sum", "count", "min", "max", "mean"];
+            // Skipped lines belong to no group, so they get a line of their
+            // own, like the skipped column of the ungrouped table.
+            format!(
+                "{}skipped: {}\n",
+                text_table(&headers, &rows, 1),
+                total.skipped()
+            )
+        }
+    }
+}
+
+/// Writes warnings as they turn up and the summary once the input is done,
+/// in the format chosen with `--format`.
+pub struct Reporter {
+    format: OutputFormat,
+    /// Diagnostics held back for the JSON document.
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Reporter {
+    pub fn new(format: OutputFormat) -> Self {
+        Reporter {
+            format,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn warn(&mut self, warning: &CliError) {
+        match self.format {
+            OutputFormat::Plain | OutputFormat::Table => eprintln!("add: warning: {warning}"),
+            OutputFormat::Json => self
+                .diagnostics
+                .push(diagnostic(Severity::Warning, warning)),
+            OutputFormat::Ndjson => {
+                print!("{}", diagnostic(Severity::Warning, warning).to_ndjson())
+            }
+        }
+    }
+
+    /// Prints the outcome on stdout and hands back any error for the exit
+    /// status. Machine formats also describe the error on stdout.
+    pub fn finish(self, result: Result<Summary, CliError>) -> Result<(), CliError> {
+        print!("{}", self.render(&result));
+        result.map(drop)
+    }
+
+    fn render(mut self, result: &Result<Summary, CliError>) -> String {
+        match (self.format, result) {
+            (OutputFormat::Plain, Ok(summary)) => summary.to_string(),
+            (OutputFormat::Table, Ok(summary)) => table(summary),
+            (OutputFormat::Plain | OutputFormat::Table, Err(_)) => String::new(),
+            (OutputFormat::Json, Ok(summary)) => {
+                let groups = match summary {
+                    Summary::Total(_) => None,
+                    Summary::Groups { groups, .. } => Some(
+                        groups
+                            .iter()
+                            .map(|(key, stats)| {
+                                format!("{{\"key\":{},{}}}", json_string(key), stats_members(stats))
+                            })
+                            .collect::<Vec<_>>()
+                            .join(","),
+                    ),
+                };
+                let members = format!(
+                    "{},\"groups\":{}",
+                    result_members(summary.total()),
+                    json_or_null(groups.map(|groups| format!("[{groups}]"))),
+                );
+                json_document(true, &members, &self.diagnostics)
+            }
+            (OutputFormat::Json, Err(error)) => {
+                self.diagnostics.push(diagnostic(Severity::Error, error));
+                let members = "\"stats\":null,\"skipped\":null,\"groups\":null";
+                json_document(false, members, &self.diagnostics)
+            }
+            (OutputFormat::Ndjson, Ok(summary)) => {
+                let mut out = String::new();
+                if let Summary::Groups { groups, .. } = summary {
+                    for (key, stats) in groups {
+                        let members =
+                            format!("\"key\":{},{}", json_string(key), stats_members(stats));
+                        out.push_str(&ndjson_record("group", &members));
+                    }
+                }
+                out.push_str(&ndjson_record("result", &result_members(summary.total())));
+                out
+            }
+            (OutputFormat::Ndjson, Err(error)) => diagnostic(Severity::Error, error).to_ndjson(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn stats(values: &[&str]) -> Stats {
+        let mut stats = Stats::new();
+        for value in values {
+            stats.push(value.parse().unwrap());
+        }
+        stats
+    }
+
+    #[test]
+    fn stats_as_json() {
+        assert_eq!(
+            stats_members(&stats(&["1.5", "-2", "4"])),
+            "\"sum\":\"3.5\",\"count\":3,\"min\":\"-2\",\"max\":\"4\",\"mean\":\"1.1666666666666666666666666667\""
+        );
+        assert_eq!(
+            stats_members(&Stats::new()),
+            "\"sum\":\"0\",\"count\":0,\"min\":null,\"max\":null,\"mean\":null"
+        );
+    }
+
+    #[test]
+    fn diagnostics_as_json() {
+        let line = CliError::Line {
+            line: 4,
+            message: "invalid number \"x\"".into(),
+        };
+        assert_eq!(
+            diagnostic(Severity::Warning, &line),
+            Diagnostic {
+                severity: Severity::Warning,
+                position: Some(4),
+                input: None,
+                message: "invalid number \"x\"".into(),
+            }
+        );
+        let io = diagnostic(
+            Severity::Error,
+            &CliError::Io("cannot read input: gone".into()),
+        );
+        assert_eq!(
+            (io.position, io.message.as_str()),
+            (None, "cannot read input: gone")
+        );
+    }
+
+    #[test]
+    fn renders_each_format() {
+        let mut groups = BTreeMap::new();
+        groups.insert("a".to_string(), stats(&["1"]));
+        let summary = Ok(Summary::Groups {
+            groups,
+            total: stats(&["1"]),
+        });
+        let render = |format| Reporter::new(format).render(&summary);
+        assert_eq!(render(OutputFormat::Plain), "a\t1\n");
+        assert_eq!(
+            render(OutputFormat::Table),
+            "group  sum  count  min  max  mean\n-----  ---  -----  ---  ---  ----\na        1      1    1    1     1\nskipped: 0\n"
+        );
+        assert_eq!(
+            render(OutputFormat::Ndjson),
+            "{\"type\":\"group\",\"key\":\"a\",\"sum\":\"1\",\"count\":1,\"min\":\"1\",\"max\":\"1\",\"mean\":\"1\"}\n\
+             {\"type\":\"result\",\"stats\":{\"sum\":\"1\",\"count\":1,\"min\":\"1\",\"max\":\"1\",\"mean\":\"1\"},\"skipped\":0}\n"
+        );
+
+        let mut reporter = Reporter::new(OutputFormat::Json);
+        reporter.warn(&CliError::Line {
+            line: 2,
+            message: "blank line".into(),
+        });
+        let error = Err(CliError::Line {
+            line: 3,
+            message: "blank line".into(),
+        });
+        assert_eq!(
+            reporter.render(&error),
+            "{\"ok\":false,\"stats\":null,\"skipped\":null,\"groups\":null,\"diagnostics\":[\
+             {\"severity\":\"warning\",\"position\":2,\"input\":null,\"message\":\"blank line\"},\
+             {\"severity\":\"error\",\"position\":3,\"input\":null,\"message\":\"blank line\"}]}\n"
+        );
+        assert_eq!(Reporter::new(OutputFormat::Table).render(&error), "");
+    }
+}
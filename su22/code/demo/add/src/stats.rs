@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use adder::{add, Decimal};
+
+/// Running totals over a stream of numbers, in constant space apart from
+/// the digits of the sum itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stats {
+    sum: Decimal,
+    count: u64,
+    min: Option<Decimal>,
+    max: Option<Decimal>,
+    /// Lines that were reported and left out.
+    skipped: u64,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Stats {
+            sum: Decimal::zero(),
+            count: 0,
+            min: None,
+            max: None,
+            skipped: 0,
+        }
+    }
+
+    pub fn push(&mut self, value: Decimal) {
+        if self.min.as_ref().is_none_or(|min| value < *min) {
+            self.min = Some(value.clone());
+        }
+        if self.max.as_ref().is_none_or(|max| value > *max) {
+            self.max = Some(value.clone());
+        }
+        self.sum = add(self.sum.clone(), value);
+        self.count += 1;
+    }
+
+    pub fn skip(&mut self) {
+        self.skipped += 1;
+    }
+
+    pub fn sum(&self) -> &Decimal {
+        &self.sum
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn min(&self) -> Option<&Decimal> {
+        self.min.as_ref()
+    }
+
+    pub fn max(&self) -> Option<&Decimal> {
+        self.max.as_ref()
+    }
+
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// The mean to at least the sum's scale, or more digits when it does
+    /// not divide evenly.
+    pub fn mean(&self) -> Option<Decimal> {
+        if self.count == 0 {
+            return None;
+        }
+        Some(self.sum.checked_div(&Decimal::from(self.count)).unwrap())
+    }
+}
+
+/// One `name: value` line per statistic; min, max and mean are left out
+/// when nothing was counted, and skipped only appears when non-zero.
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "sum: {}", self.sum)?;
+        write!(f, "count: {}", self.count)?;
+        if let (Some(min), Some(max), Some(mean)) = (self.min(), self.max(), self.mean()) {
+            write!(f, "\nmin: {min}\nmax: {max}\nmean: {mean}")?;
+        }
+        if self.skipped > 0 {
+            write!(f, "\nskipped: {}", self.skipped)?;
+        }
+        Ok(())
+    }
+}
+
+/// What `add` reports: one set of totals, or one per group.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Summary {
+    Total(Stats),
+    /// Keyed by the value of the group column, so iteration is sorted.
+    Groups {
+        groups: BTreeMap<String, Stats>,
+        /// Every group together, along with the skipped lines.
+        total: Stats,
+    },
+}
+
+impl Summary {
+    pub fn total(&self) -> &Stats {
+        match self {
+            Summary::Total(total) | Summary::Groups { total, .. } => total,
+        }
+    }
+}
+
+/// `key` with backslashes, tabs and line breaks escaped as `\\`, `\t`,
+/// `\n` and `\r`, so a key cannot run into the sum or the next group.
+pub fn escape_key(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    for c in key.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Groups print as `key<TAB>sum` lines, ready for `sort` or `awk`. Every
+/// line, including the last, ends with a newline.
+impl Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Summary::Total(stats) => writeln!(f, "{stats}"),
+            Summary::Groups { groups, total } => {
+                for (key, stats) in groups {
+                    writeln!(f, "{}\t{}", escape_key(key), stats.sum)?;
+                }
+                if total.skipped > 0 {
+                    writeln!(f, "skipped: {}", total.skipped)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(src: &str) -> Decimal {
+        src.parse().unwrap()
+    }
+
+    #[test]
+    fn accumulates() {
+        let mut stats = Stats::new();
+        assert_eq!(stats.mean(), None);
+        assert_eq!(stats.to_string(), "sum: 0\ncount: 0");
+        for value in ["3", "-1.5", "10", "0.25"] {
+            stats.push(dec(value));
+        }
+        stats.skip();
+        assert_eq!(stats.sum, dec("11.75"));
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.min(), Some(&dec("-1.5")));
+        assert_eq!(stats.max(), Some(&dec("10")));
+        assert_eq!(stats.mean(), Some(dec("2.9375")));
+        assert_eq!(
+            stats.to_string(),
+            "sum: 11.75\ncount: 4\nmin: -1.5\nmax: 10\nmean: 2.9375\nskipped: 1"
+        );
+
+        let mut thirds = Stats::new();
+        for value in ["1", "1", "0"] {
+            thirds.push(dec(value));
+        }
+        assert_eq!(
+            thirds.mean().unwrap().to_string(),
+            "0.6666666666666666666666666667"
+        );
+        assert_eq!(
+            Summary::Total(thirds).to_string(),
+            "sum: 2\ncount: 3\nmin: 0\nmax: 1\nmean: 0.6666666666666666666666666667\n"
+        );
+    }
+
+    #[test]
+    fn groups() {
+        let mut groups = BTreeMap::new();
+        let mut total = Stats::new();
+        for (key, value) in [("west", "2"), ("east", "1.5"), ("west", "3"), ("", "1")] {
+            groups
+                .entry(key.to_string())
+                .or_insert_with(Stats::new)
+                .push(dec(value));
+            total.push(dec(value));
+        }
+        let mut summary = Summary::Groups { groups, total };
+        assert_eq!(summary.to_string(), "\t1\neast\t1.5\nwest\t5\n");
+        assert_eq!(summary.total().sum, dec("7.5"));
+        if let Summary::Groups { total, .. } = &mut summary {
+            total.skip();
+            total.skip();
+        }
+        assert!(summary.to_string().ends_with("west\t5\nskipped: 2\n"));
+        let empty = Summary::Groups {
+            groups: BTreeMap::new(),
+            total: Stats::new(),
+        };
+        assert_eq!(empty.to_string(), "");
+    }
+
+    #[test]
+    fn escapes_group_keys() {
+        assert_eq!(escape_key("plain key"), "plain key");
+        assert_eq!(escape_key("a\tb\nc\r\\"), "a\\tb\\nc\\r\\\\");
+        let mut groups = BTreeMap::new();
+        let mut stats = Stats::new();
+        stats.push(dec("1"));
+        groups.insert("x\t2\ny".to_string(), stats.clone());
+        let summary = Summary::Groups {
+            groups,
+            total: stats,
+        };
+        assert_eq!(summary.to_string(), "x\\t2\\ny\t1\n");
+    }
+}
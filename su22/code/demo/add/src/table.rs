@@ -0,0 +1,347 @@
+use std::collections::BTreeMap;
+use std::io::BufRead;
+
+use adder::Decimal;
+
+use crate::args::{Blank, Column, Header, Options, Table};
+use crate::csv::Reader;
+use crate::error::CliError;
+use crate::input::{parse_number, recover};
+use crate::stats::{Stats, Summary};
+
+/// Where the value and group columns sit once the header, if any, is read.
+#[derive(Debug, PartialEq, Eq)]
+struct Positions {
+    value: usize,
+    group: Option<usize>,
+}
+
+fn is_header(table: &Table, first: &[String]) -> bool {
+    match table.header {
+        Header::Present => true,
+        Header::Absent => false,
+        Header::Auto => {
+            let named = [Some(&table.column), table.group_by.as_ref()]
+                .into_iter()
+                .any(|column| matches!(column, Some(Column::Name(_))));
+            let label = match table.column {
+                Column::Index(index) => first.get(index).map(|cell| cell.trim()),
+                Column::Name(_) => None,
+            };
+            named || label.is_some_and(|text| !text.is_empty() && parse_number(text).is_err())
+        }
+    }
+}
+
+fn position(column: &Column, header: &[String], line: u64) -> Result<usize, CliError> {
+    match column {
+        Column::Index(index) => Ok(*index),
+        Column::Name(name) => header
+            .iter()
+            .position(|field| field.trim() == name)
+            .ok_or_else(|| CliError::Line {
+                line,
+                message: format!("no column named {name:?} in the header"),
+            }),
+    }
+}
+
+/// The group key and value of one record, or `None` for a skipped blank.
+fn row<'a>(
+    fields: &'a [String],
+    positions: &Positions,
+    table: &Table,
+    blank: Blank,
+) -> Result<Option<(&'a str, Decimal)>, String> {
+    let cell = |index: usize, column: &Column| {
+        fields
+            .get(index)
+            .map(|field| field.trim())
+            .ok_or_else(|| format!("missing {column}"))
+    };
+    let text = cell(positions.value, &table.column)?;
+    let value = match (text.is_empty(), blank) {
+        (false, _) => parse_number(text).map_err(|error| format!("{error} in {}", table.column))?,
+        (true, Blank::Skip) => return Ok(None),
+        (true, Blank::Zero) => Decimal::zero(),
+        (true, Blank::Error) => return Err(format!("empty {}", table.column)),
+    };
+    let key = match (positions.group, &table.group_by) {
+        (Some(index), Some(column)) => cell(index, column)?,
+        _ => "",
+    };
+    Ok(Some((key, value)))
+}
+
+/// Totals one column of delimited input, or one total per distinct value
+/// of the group column. Only the groups are kept in memory; records are
+/// read one at a time.
+pub fn read_table<R: BufRead>(
+    input: R,
+    options: &Options,
+    table: &Table,
+    mut warn: impl FnMut(&CliError),
+) -> Result<Summary, CliError> {
+    let mut reader = Reader::new(input, table.delimiter);
+    let mut fields = Vec::new();
+    let mut positions = None;
+    let mut total = Stats::new();
+    let mut groups = BTreeMap::new();
+    loop {
+        let line = match reader.read_record(&mut fields) {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(error) => match error.line() {
+                Some(line) => {
+                    let message = error.to_string();
+                    recover(
+                        CliError::Line { line, message },
+                        options.on_error,
+                        &mut warn,
+                    )?;
+                    total.skip();
+                    continue;
+                }
+                None => return Err(CliError::Io(error.to_string())),
+            },
+        };
+        if let Some(prefix) = &options.comment {
+            if fields[0].trim_start().starts_with(prefix.as_str()) {
+                continue;
+            }
+        }
+        // A blank record is a blank line, even before the header. It has no
+        // group cell, so zero counts it in the total but in no group.
+        let counted = if fields.len() == 1 && fields[0].trim().is_empty() {
+            match options.blank {
+                Blank::Skip => continue,
+                Blank::Zero => {
+                    total.push(Decimal::zero());
+                    continue;
+                }
+                Blank::Error => Err("blank line".to_string()),
+            }
+        } else {
+            let positions = match &mut positions {
+                Some(positions) => positions,
+                None => {
+                    let header = is_header(table, &fields);
+                    let names: &[String] = if header { &fields } else { &[] };
+                    let group = table.group_by.as_ref();
+                    let resolved = positions.insert(Positions {
+                        value: position(&table.column, names, line)?,
+                        group: group
+                            .map(|column| position(column, names, line))
+                            .transpose()?,
+                    });
+                    if header {
+                        continue;
+                    }
+                    resolved
+                }
+            };
+            row(&fields, positions, table, options.blank)
+        };
+        match counted {
+            Ok(None) => {}
+            Ok(Some((key, value))) => {
+                if table.group_by.is_some() {
+                    groups
+                        .entry(key.to_string())
+                        .or_insert_with(Stats::new)
+                        .push(value.clone());
+                }
+                total.push(value);
+            }
+            Err(message) => {
+                recover(
+                    CliError::Line { line, message },
+                    options.on_error,
+                    &mut warn,
+                )?;
+                total.skip();
+            }
+        }
+    }
+    Ok(match table.group_by {
+        Some(_) => Summary::Groups { groups, total },
+        None => Summary::Total(total),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::OnError;
+
+    fn options(args: &[&str]) -> Options {
+        let mut options = Options::default();
+        let mut table = Table {
+            delimiter: b',',
+            column: Column::Index(0),
+            group_by: None,
+            header: Header::Auto,
+        };
+        for arg in args {
+            match arg.split_once('=') {
+                Some(("column", value)) => table.column = column(value),
+                Some(("group", value)) => table.group_by = Some(column(value)),
+                _ if *arg == "tsv" => table.delimiter = b'\t',
+                _ if *arg == "header" => table.header = Header::Present,
+                _ if *arg == "no-header" => table.header = Header::Absent,
+                _ if *arg == "skip" => options.on_error = OnError::Skip,
+                _ if *arg == "zero" => options.blank = Blank::Zero,
+                _ if *arg == "strict" => options.blank = Blank::Error,
+                _ => panic!("unknown test option {arg}"),
+            }
+        }
+        options.table = Some(table);
+        options
+    }
+
+    fn column(value: &str) -> Column {
+        match value.parse::<usize>() {
+            Ok(index) => Column::Index(index - 1),
+            Err(_) => Column::Name(value.to_string()),
+        }
+    }
+
+    fn run(input: &str, args: &[&str]) -> (Result<String, CliError>, Vec<String>) {
+        let options = options(args);
+        let mut warnings = Vec::new();
+        let table = options.table.as_ref().unwrap();
+        let result = read_table(input.as_bytes(), &options, table, |error| {
+            warnings.push(error.to_string())
+        });
+        (result.map(|summary| summary.to_string()), warnings)
+    }
+
+    fn output(input: &str, args: &[&str]) -> String {
+        let (result, warnings) = run(input, args);
+        assert_eq!(warnings, Vec::<String>::new());
+        result.unwrap()
+    }
+
+    const SALES: &str = "\
+region,item,amount
+west,\"Widget, large\",10.50
+east,Gadget,3
+# returns
+west,Gizmo,-2
+
+east,\"Widget \"\"XL\"\"\",4.25
+";
+
+    #[test]
+    fn totals_a_column() {
+        assert_eq!(
+            output(SALES, &["column=amount"]),
+            "sum: 15.75\ncount: 4\nmin: -2\nmax: 10.50\nmean: 3.9375\n"
+        );
+        // The header is found without naming a column, because "amount"
+        // is not a number.
+        assert_eq!(
+            output(SALES, &["column=3"]),
+            output(SALES, &["column=amount"])
+        );
+        assert_eq!(
+            output("1\t2\n3\t4\n", &["tsv", "column=2"]),
+            "sum: 6\ncount: 2\nmin: 2\nmax: 4\nmean: 3\n"
+        );
+        assert_eq!(
+            output("10\n20\n", &["header"]),
+            "sum: 20\ncount: 1\nmin: 20\nmax: 20\nmean: 20\n"
+        );
+        assert_eq!(
+            output(" x \n1\n,\n2\n", &["column=x", "zero"]),
+            "sum: 3\ncount: 3\nmin: 0\nmax: 2\nmean: 1\n"
+        );
+    }
+
+    #[test]
+    fn groups_sorted() {
+        assert_eq!(
+            output(SALES, &["column=amount", "group=region"]),
+            "east\t7.25\nwest\t8.50\n"
+        );
+        assert_eq!(
+            output(SALES, &["column=3", "group=2"]),
+            "Gadget\t3\nGizmo\t-2\nWidget \"XL\"\t4.25\nWidget, large\t10.50\n"
+        );
+        assert_eq!(output("", &["group=1"]), "");
+    }
+
+    #[test]
+    fn reports_bad_records() {
+        let input = "name,qty\na,1\nb,lots\nc\n\"d,2\n";
+        assert_eq!(
+            run(input, &["column=qty"]).0,
+            Err(CliError::Line {
+                line: 3,
+                message: "invalid number \"lots\": invalid digit 'l' at index 0 in column \"qty\""
+                    .into(),
+            })
+        );
+        let (result, warnings) = run(input, &["column=qty", "group=name", "skip"]);
+        assert_eq!(result.unwrap(), "a\t1\nskipped: 3\n");
+        assert_eq!(
+            warnings,
+            [
+                "line 3: invalid number \"lots\": invalid digit 'l' at index 0 in column \"qty\"",
+                "line 4: missing column \"qty\"",
+                "line 5: quoted field is never closed",
+            ]
+        );
+        assert_eq!(
+            run(input, &["column=price", "skip"]).0,
+            Err(CliError::Line {
+                line: 1,
+                message: "no column named \"price\" in the header".into(),
+            })
+        );
+        assert_eq!(
+            run("1,x\n", &["no-header", "column=3"]).0,
+            Err(CliError::Line {
+                line: 1,
+                message: "missing column 3".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn blank_records() {
+        let input = "k,v\na,1\n\na,2\n";
+        assert_eq!(
+            output(input, &["column=v"]),
+            output("a,1\na,2\n", &["column=2"])
+        );
+        assert_eq!(
+            output(input, &["column=v", "zero"]),
+            "sum: 3\ncount: 3\nmin: 0\nmax: 2\nmean: 1\n"
+        );
+        assert_eq!(output(input, &["column=v", "group=k", "zero"]), "a\t3\n");
+        assert_eq!(output(",1\n\n", &["group=1", "column=2", "zero"]), "\t1\n");
+        assert_eq!(
+            run(input, &["column=v", "strict"]).0,
+            Err(CliError::Line {
+                line: 3,
+                message: "blank line".into(),
+            })
+        );
+        let (result, warnings) = run(input, &["column=v", "group=k", "strict", "skip"]);
+        assert_eq!(result.unwrap(), "a\t3\nskipped: 1\n");
+        assert_eq!(warnings, ["line 3: blank line"]);
+    }
+
+    #[test]
+    fn detects_headers() {
+        let table = |args: &[&str]| options(args).table.unwrap();
+        let first = ["total".to_string(), "2".to_string()];
+        assert!(is_header(&table(&[]), &first));
+        assert!(!is_header(&table(&["column=2"]), &first));
+        assert!(is_header(&table(&["column=2", "group=kind"]), &first));
+        assert!(!is_header(&table(&["no-header"]), &first));
+        assert!(is_header(&table(&["header", "column=2"]), &first));
+        assert!(!is_header(&table(&["column=3"]), &first));
+    }
+}
@@ -0,0 +1,191 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use cli_output::snapshots;
+
+/// Runs the built binary with `input` piped to stdin and returns its exit
+/// code, stdout and stderr.
+fn add(args: &[&str], input: &str) -> (i32, String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_add"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run add");
+    // Usage errors exit before reading stdin, so a broken pipe is fine.
+    let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+    let output = child.wait_with_output().unwrap();
+    (
+        output.status.code().expect("add was killed by a signal"),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn summarises_stdin() {
+    assert_eq!(
+        add(&[], "# weekly\n3\n4.5\n\n-1\n"),
+        (
+            0,
+            "sum: 6.5\ncount: 3\nmin: -1\nmax: 4.5\nmean: 2.1666666666666666666666666667\n".into(),
+            String::new()
+        )
+    );
+    assert_eq!(
+        add(&[], ""),
+        (0, "sum: 0\ncount: 0\n".into(), String::new())
+    );
+    assert_eq!(
+        add(&["--blank", "zero", "--no-comments"], "2\n\n4\n"),
+        (
+            0,
+            "sum: 6\ncount: 3\nmin: 0\nmax: 4\nmean: 2\n".into(),
+            String::new()
+        )
+    );
+}
+
+#[test]
+fn streams_large_input() {
+    let input: String = (1..=100_000).map(|n| format!("{n}\n")).collect();
+    let (code, stdout, _) = add(&[], &input);
+    assert_eq!(code, 0);
+    assert_eq!(
+        stdout,
+        "sum: 5000050000\ncount: 100000\nmin: 1\nmax: 100000\nmean: 50000.5\n"
+    );
+}
+
+#[test]
+fn reports_bad_lines() {
+    let input = "1\n2\nabc\n4\n";
+    assert_eq!(
+        add(&[], input),
+        (
+            2,
+            String::new(),
+            "add: line 3: invalid number \"abc\": invalid digit 'a' at index 0\n".into()
+        )
+    );
+    assert_eq!(
+        add(&["--on-error", "skip"], input),
+        (
+            0,
+            "sum: 7\ncount: 3\nmin: 1\nmax: 4\nmean: 2.3333333333333333333333333333\nskipped: 1\n"
+                .into(),
+            "add: warning: line 3: invalid number \"abc\": invalid digit 'a' at index 0\n".into()
+        )
+    );
+    let (code, _, stderr) = add(&["--blank=error"], "1\n\n");
+    assert_eq!((code, stderr.as_str()), (2, "add: line 2: blank line\n"));
+}
+
+const SALES: &str = "\
+region,item,amount
+west,\"Widget, large\",10.50
+east,Gadget,3
+west,Gizmo,-2
+east,\"Widget \"\"XL\"\"\",4.25
+";
+
+#[test]
+fn totals_csv_columns() {
+    let expected = "sum: 15.75\ncount: 4\nmin: -2\nmax: 10.50\nmean: 3.9375\n";
+    assert_eq!(
+        add(&["--csv", "--column", "amount"], SALES),
+        (0, expected.into(), String::new())
+    );
+    assert_eq!(add(&["--csv", "--column=3"], SALES).1, expected);
+    let tsv = SALES.replace(',', "\t");
+    assert_eq!(add(&["--tsv", "--column", "3"], &tsv).1, expected);
+    assert_eq!(
+        add(&["--csv", "--no-header", "--column", "2"], "a,1\nb,2\n").1,
+        "sum: 3\ncount: 2\nmin: 1\nmax: 2\nmean: 1.5\n"
+    );
+}
+
+#[test]
+fn groups_csv_rows() {
+    assert_eq!(
+        add(
+            &["--csv", "--column", "amount", "--group-by", "region"],
+            SALES
+        ),
+        (0, "east\t7.25\nwest\t8.50\n".into(), String::new())
+    );
+    assert_eq!(
+        add(&["--csv", "--column", "3", "--group-by", "2"], SALES).1,
+        "Gadget\t3\nGizmo\t-2\nWidget \"XL\"\t4.25\nWidget, large\t10.50\n"
+    );
+    assert_eq!(
+        add(&["--csv", "--column", "price"], SALES),
+        (
+            2,
+            String::new(),
+            "add: line 1: no column named \"price\" in the header\n".into()
+        )
+    );
+    assert_eq!(
+        add(
+            &["--csv", "--column", "2", "--group-by", "1", "--on-error", "skip"],
+            "k,v\na,1\nb,x\n\"a,2\n"
+        ),
+        (
+            0,
+            "a\t1\nskipped: 2\n".into(),
+            "add: warning: line 3: invalid number \"x\": invalid digit 'x' at index 0 in column 2\n\
+             add: warning: line 4: quoted field is never closed\n"
+                .into()
+        )
+    );
+}
+
+#[test]
+fn usage() {
+    let (code, stdout, stderr) = add(&["--help"], "");
+    assert_eq!((code, stderr.as_str()), (0, ""));
+    assert!(stdout.starts_with("Usage: add [OPTIONS]"));
+
+    let (code, stdout, stderr) = add(&["--blank", "maybe"], "1\n");
+    assert_eq!(code, 2);
+    assert_eq!(stdout, "");
+    assert_eq!(
+        stderr,
+        "add: invalid blank mode \"maybe\" (expected skip, zero or error)\n\
+         Try 'add --help' for more information.\n"
+    );
+}
+
+/// Pins every output format for a run of `add` with `args` and `input`.
+fn formats(scenario: &str, args: &[&str], input: &str) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots");
+    snapshots(&dir, scenario, |format| {
+        add(&[args, &["--format", format]].concat(), input)
+    });
+}
+
+#[test]
+fn format_snapshots() {
+    formats(
+        "lines",
+        &["--on-error", "skip"],
+        "# readings\n3\n4.5\nabc\n\n-1\n",
+    );
+    formats("empty", &[], "");
+    formats(
+        "groups",
+        &[
+            "--csv",
+            "--column",
+            "amount",
+            "--group-by",
+            "region",
+            "--on-error=skip",
+        ],
+        &format!("{SALES}\"north\t\"\"far\"\" \\\",Sled,1\nsouth,Thing,n/a\n"),
+    );
+    formats("abort", &[], "1\n2\nthree\n");
+}
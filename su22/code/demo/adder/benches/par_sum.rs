@@ -0,0 +1,50 @@
+//! Compares the sequential fold over `add` with `par_sum`.
+//!
+//! Run with `cargo bench --bench par_sum`; pass a length to override the
+//! default input size.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use adder::{add, par_sum, sum};
+
+const RUNS: u32 = 5;
+
+fn best_of<T>(mut f: impl FnMut() -> T) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, baseline: Duration, time: Duration) {
+    let speedup = baseline.as_secs_f64() / time.as_secs_f64();
+    println!(
+        "{name:<24} {:>10.3} ms {speedup:>7.2}x",
+        time.as_secs_f64() * 1e3
+    );
+}
+
+fn main() {
+    let len = std::env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse().ok())
+        .unwrap_or(50_000_000usize);
+    println!("{len} values, best of {RUNS} runs");
+
+    let counters: Vec<usize> = (0..len).map(|i| i % 1000).collect();
+    let fold = best_of(|| counters.iter().fold(0usize, |total, &n| add(total, n)));
+    report("usize fold(add)", fold, fold);
+    report("usize sum", fold, best_of(|| sum(counters.iter().copied())));
+    report("usize par_sum", fold, best_of(|| par_sum(&counters)));
+
+    let floats: Vec<f64> = counters.iter().map(|&n| n as f64 * 0.1).collect();
+    let fold = best_of(|| floats.iter().fold(0.0, |total, &x| add(total, x)));
+    report("f64 fold(add)", fold, fold);
+    report("f64 sum", fold, best_of(|| sum(floats.iter().copied())));
+    report("f64 par_sum", fold, best_of(|| par_sum(&floats)));
+}
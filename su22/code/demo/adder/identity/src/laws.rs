@@ -0,0 +1,111 @@
+//! Law checks for your own `Semigroup`, `Monoid` and `Group` impls.
+//!
+//! Each function tries every combination of the given samples and panics
+//! with the failing values, so they drop straight into a `#[test]`.
+
+use std::fmt::Debug;
+
+use crate::{Group, Monoid, Semigroup};
+
+/// `(a + b) + c == a + (b + c)` for every triple of samples.
+pub fn check_semigroup<S>(samples: &[S])
+where
+    S: Semigroup + Clone + PartialEq + Debug,
+{
+    for a in samples {
+        for b in samples {
+            for c in samples {
+                let left = a.clone().combine(b.clone()).combine(c.clone());
+                let right = a.clone().combine(b.clone().combine(c.clone()));
+                assert_eq!(
+                    left, right,
+                    "combine is not associative for {a:?}, {b:?}, {c:?}"
+                );
+            }
+        }
+    }
+}
+
+/// The semigroup law plus `empty() + a == a == a + empty()`.
+pub fn check_monoid<M>(samples: &[M])
+where
+    M: Monoid + Clone + PartialEq + Debug,
+{
+    check_semigroup(samples);
+    for a in samples {
+        assert_eq!(
+            M::empty().combine(a.clone()),
+            *a,
+            "empty() is not a left identity for {a:?}"
+        );
+        assert_eq!(
+            a.clone().combine(M::empty()),
+            *a,
+            "empty() is not a right identity for {a:?}"
+        );
+    }
+}
+
+/// The monoid laws plus `a + inverse(a) == empty() == inverse(a) + a`.
+pub fn check_group<G>(samples: &[G])
+where
+    G: Group + Clone + PartialEq + Debug,
+{
+    check_monoid(samples);
+    for a in samples {
+        let inverse = a.clone().inverse();
+        assert_eq!(
+            a.clone().combine(inverse.clone()),
+            G::empty(),
+            "inverse() is not a right inverse for {a:?}"
+        );
+        assert_eq!(
+            inverse.combine(a.clone()),
+            G::empty(),
+            "inverse() is not a left inverse for {a:?}"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Subtraction is not associative, so it must fail the checks.
+    #[derive(Clone, Debug, PartialEq)]
+    struct Minus(i32);
+
+    impl Semigroup for Minus {
+        fn combine(self, other: Self) -> Self {
+            Minus(self.0 - other.0)
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "combine is not associative for Minus(1), Minus(1), Minus(1)")]
+    fn catches_non_associative() {
+        check_semigroup(&[Minus(1), Minus(2)]);
+    }
+
+    /// Max over i32 with a wrong identity.
+    #[derive(Clone, Debug, PartialEq)]
+    struct Max(i32);
+
+    impl Semigroup for Max {
+        fn combine(self, other: Self) -> Self {
+            Max(self.0.max(other.0))
+        }
+    }
+
+    impl Monoid for Max {
+        fn empty() -> Self {
+            Max(0)
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "empty() is not a left identity for Max(-1)")]
+    fn catches_wrong_identity() {
+        check_monoid(&[Max(3), Max(-1)]);
+    }
+}
@@ -1,7 +1,114 @@
+pub mod laws;
+mod num;
+
+pub use num::{Product, Sum};
+
+/// A type with an associative way to combine two values.
+pub trait Semigroup {
+    fn combine(self, other: Self) -> Self;
+}
+
+/// A semigroup with an identity element: combining with `empty()` is a no-op.
+pub trait Monoid: Semigroup {
+    fn empty() -> Self;
+}
+
+/// A monoid where every value has an inverse that combines to `empty()`.
+pub trait Group: Monoid {
+    fn inverse(self) -> Self;
+}
+
+/// Combines all items left to right, starting from `empty()`.
+pub fn fold_all<M: Monoid, I: IntoIterator<Item = M>>(items: I) -> M {
+    items.into_iter().fold(M::empty(), M::combine)
+}
+
 pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
 
+impl Semigroup for String {
+    fn combine(mut self, other: Self) -> Self {
+        self.push_str(&other);
+        self
+    }
+}
+
+impl Monoid for String {
+    fn empty() -> Self {
+        String::new()
+    }
+}
+
+impl<T> Semigroup for Vec<T> {
+    fn combine(mut self, mut other: Self) -> Self {
+        self.append(&mut other);
+        self
+    }
+}
+
+impl<T> Monoid for Vec<T> {
+    fn empty() -> Self {
+        Vec::new()
+    }
+}
+
+/// `None` is the identity; two `Some`s combine their contents.
+impl<T: Semigroup> Semigroup for Option<T> {
+    fn combine(self, other: Self) -> Self {
+        match (self, other) {
+            (Some(a), Some(b)) => Some(a.combine(b)),
+            (a, None) => a,
+            (None, b) => b,
+        }
+    }
+}
+
+impl<T: Semigroup> Monoid for Option<T> {
+    fn empty() -> Self {
+        None
+    }
+}
+
+impl Semigroup for () {
+    fn combine(self, _: Self) -> Self {}
+}
+
+impl Monoid for () {
+    fn empty() -> Self {}
+}
+
+impl Group for () {
+    fn inverse(self) -> Self {}
+}
+
+/// Tuples combine component-wise.
+macro_rules! impl_tuple {
+    ($($name:ident $index:tt),+) => {
+        impl<$($name: Semigroup),+> Semigroup for ($($name,)+) {
+            fn combine(self, other: Self) -> Self {
+                ($(self.$index.combine(other.$index),)+)
+            }
+        }
+
+        impl<$($name: Monoid),+> Monoid for ($($name,)+) {
+            fn empty() -> Self {
+                ($($name::empty(),)+)
+            }
+        }
+
+        impl<$($name: Group),+> Group for ($($name,)+) {
+            fn inverse(self) -> Self {
+                ($(self.$index.inverse(),)+)
+            }
+        }
+    };
+}
+
+impl_tuple!(A 0, B 1);
+impl_tuple!(A 0, B 1, C 2);
+impl_tuple!(A 0, B 1, C 2, D 3);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -11,4 +118,44 @@ mod tests {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn strings_and_vecs() {
+        laws::check_monoid(&["", "a", "bc"].map(String::from));
+        laws::check_monoid(&[vec![], vec![1], vec![2, 3]]);
+        assert_eq!(fold_all(["ab", "", "c"].map(String::from)), "abc");
+        assert_eq!(fold_all([vec![1], vec![], vec![2, 3]]), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn options() {
+        laws::check_monoid(&[None, Some(Sum(1)), Some(Sum(-4))]);
+        assert_eq!(
+            fold_all([None, Some(Sum(2)), None, Some(Sum(3))]),
+            Some(Sum(5))
+        );
+        assert_eq!(fold_all(Vec::<Option<String>>::new()), None);
+    }
+
+    #[test]
+    fn tuples() {
+        let samples = [
+            (Sum(1i32), Product(2i32)),
+            (Sum(-3), Product(5)),
+            (Sum(0), Product(1)),
+        ];
+        laws::check_monoid(&samples);
+        laws::check_group(&[(Sum(1i8), Sum(2.5f64)), (Sum(-128), Sum(-1.0))]);
+        laws::check_group(&[(), ()]);
+
+        let (count, total, longest) = fold_all(
+            ["a", "bcd", "ef"]
+                .iter()
+                .map(|s| (Sum(1usize), Sum(s.len()), String::from(*s))),
+        );
+        assert_eq!(
+            (count, total, longest),
+            (Sum(3), Sum(6), "abcdef".to_string())
+        );
+    }
 }
@@ -0,0 +1,120 @@
+use crate::{Group, Monoid, Semigroup};
+
+/// A number combined by addition; the identity is 0.
+///
+/// Integers add with wrapping so the laws hold exactly for every value.
+/// Float addition is only approximately associative.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sum<T>(pub T);
+
+/// A number combined by multiplication; the identity is 1.
+///
+/// Integers multiply with wrapping, like `Sum`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Product<T>(pub T);
+
+macro_rules! impl_int {
+    ($($t:ty)*) => {
+        $(
+            impl Semigroup for Sum<$t> {
+                fn combine(self, other: Self) -> Self {
+                    Sum(self.0.wrapping_add(other.0))
+                }
+            }
+
+            impl Monoid for Sum<$t> {
+                fn empty() -> Self {
+                    Sum(0)
+                }
+            }
+
+            impl Group for Sum<$t> {
+                fn inverse(self) -> Self {
+                    Sum(self.0.wrapping_neg())
+                }
+            }
+
+            impl Semigroup for Product<$t> {
+                fn combine(self, other: Self) -> Self {
+                    Product(self.0.wrapping_mul(other.0))
+                }
+            }
+
+            impl Monoid for Product<$t> {
+                fn empty() -> Self {
+                    Product(1)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_float {
+    ($($t:ty)*) => {
+        $(
+            impl Semigroup for Sum<$t> {
+                fn combine(self, other: Self) -> Self {
+                    Sum(self.0 + other.0)
+                }
+            }
+
+            impl Monoid for Sum<$t> {
+                fn empty() -> Self {
+                    Sum(0.0)
+                }
+            }
+
+            impl Group for Sum<$t> {
+                fn inverse(self) -> Self {
+                    Sum(-self.0)
+                }
+            }
+
+            impl Semigroup for Product<$t> {
+                fn combine(self, other: Self) -> Self {
+                    Product(self.0 * other.0)
+                }
+            }
+
+            impl Monoid for Product<$t> {
+                fn empty() -> Self {
+                    Product(1.0)
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+impl_float!(f32 f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fold_all, laws};
+
+    #[test]
+    fn integer_laws() {
+        let samples = [0, 1, 2, 127, 128, 200, 255].map(Sum::<u8>);
+        laws::check_group(&samples);
+        laws::check_monoid(&samples.map(|Sum(x)| Product(x)));
+        laws::check_group(&[i32::MIN, -1, 0, 1, i32::MAX].map(Sum));
+        laws::check_monoid(&[i64::MIN, -3, 0, 1, 7].map(Product));
+    }
+
+    #[test]
+    fn float_laws_on_exact_values() {
+        // Small dyadic values add and multiply without rounding.
+        let samples = [-2.0, -0.5, 0.0, 0.25, 1.0, 3.0];
+        laws::check_group(&samples.map(Sum::<f64>));
+        laws::check_monoid(&samples.map(|x| Product(x as f32)));
+    }
+
+    #[test]
+    fn folding() {
+        assert_eq!(fold_all((1..=10).map(Sum::<u32>)), Sum(55));
+        assert_eq!(fold_all((1..=5).map(Product::<u64>)), Product(120));
+        assert_eq!(fold_all(Vec::<Product<f64>>::new()), Product(1.0));
+        assert_eq!(Sum(u8::MAX).combine(Sum(1)), Sum(0));
+    }
+}
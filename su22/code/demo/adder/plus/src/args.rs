@@ -0,0 +1,220 @@
+use adder::OverflowPolicy;
+use cli_output::{OutputFormat, ParseFormatError};
+
+use crate::error::CliError;
+
+pub const USAGE: &str = "\
+Usage: plus [OPTIONS] NUMBER...
+       plus [--mode MODE]
+
+Adds the numbers and prints the sum. Without numbers, starts an interactive
+calculator; type :help there for its commands.
+
+Options:
+  -m, --mode MODE   integer overflow: checked (default), wrapping or saturating
+  -t, --type TYPE   number type: auto (default), int, decimal or big
+  -f, --format FMT  output: plain (default), table, json or ndjson
+  -h, --help        print this help
+
+With --type auto the numbers are decimals if any has a '.', and 64-bit
+integers otherwise. --mode only affects integers.
+
+json and ndjson give the sum as a string, with its type, the mode and the
+count of numbers; errors become diagnostics on stdout as well as stderr.
+";
+
+/// How the arguments are parsed and added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Auto,
+    /// `i64`, following the overflow mode.
+    Int,
+    Decimal,
+    Big,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub mode: OverflowPolicy,
+    pub kind: Kind,
+    pub numbers: Vec<String>,
+    pub format: OutputFormat,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Sum(Options),
+    Repl(OverflowPolicy),
+}
+
+/// An argument is a number rather than an option if it starts with a
+/// digit, or with a sign or point followed by one.
+fn looks_numeric(arg: &str) -> bool {
+    let unsigned = arg.strip_prefix(['-', '+']).unwrap_or(arg);
+    let unsigned = unsigned.strip_prefix('.').unwrap_or(unsigned);
+    unsigned.starts_with(|c: char| c.is_ascii_digit())
+}
+
+pub fn parse_mode(value: &str) -> Result<OverflowPolicy, CliError> {
+    match value {
+        "checked" => Ok(OverflowPolicy::Checked),
+        "wrapping" => Ok(OverflowPolicy::Wrapping),
+        "saturating" => Ok(OverflowPolicy::Saturating),
+        _ => Err(CliError::Usage(format!(
+            "invalid mode {value:?} (expected checked, wrapping or saturating)"
+        ))),
+    }
+}
+
+pub fn mode_name(mode: OverflowPolicy) -> &'static str {
+    match mode {
+        OverflowPolicy::Checked => "checked",
+        OverflowPolicy::Wrapping => "wrapping",
+        OverflowPolicy::Saturating => "saturating",
+        OverflowPolicy::Panicking => "panicking",
+    }
+}
+
+pub fn kind_name(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Auto => "auto",
+        Kind::Int => "int",
+        Kind::Decimal => "decimal",
+        Kind::Big => "big",
+    }
+}
+
+fn parse_format(value: &str) -> Result<OutputFormat, CliError> {
+    value
+        .parse()
+        .map_err(|error: ParseFormatError| CliError::Usage(error.to_string()))
+}
+
+fn parse_kind(value: &str) -> Result<Kind, CliError> {
+    match value {
+        "auto" => Ok(Kind::Auto),
+        "int" => Ok(Kind::Int),
+        "decimal" => Ok(Kind::Decimal),
+        "big" => Ok(Kind::Big),
+        _ => Err(CliError::Usage(format!(
+            "invalid type {value:?} (expected auto, int, decimal or big)"
+        ))),
+    }
+}
+
+/// Parses the arguments after the program name. Options may appear anywhere
+/// and take their value as the next argument or after `=`; `--` ends them.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, CliError> {
+    let mut options = Options {
+        mode: OverflowPolicy::Checked,
+        kind: Kind::Auto,
+        numbers: Vec::new(),
+        format: OutputFormat::Plain,
+    };
+    let mut args = args.into_iter();
+    let mut only_numbers = false;
+    while let Some(arg) = args.next() {
+        if only_numbers || !arg.starts_with('-') || looks_numeric(&arg) {
+            options.numbers.push(arg);
+            continue;
+        }
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError::Usage(format!("{name} needs a value")))
+        };
+        match name {
+            "--" => only_numbers = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "-m" | "--mode" => options.mode = parse_mode(&value()?)?,
+            "-t" | "--type" => options.kind = parse_kind(&value()?)?,
+            "-f" | "--format" => options.format = parse_format(&value()?)?,
+            _ => return Err(CliError::Usage(format!("unknown option {name:?}"))),
+        }
+    }
+    if options.numbers.is_empty() {
+        if options.format != OutputFormat::Plain {
+            return Err(CliError::Usage(
+                "--format needs numbers; the calculator only prints plain text".into(),
+            ));
+        }
+        return Ok(Command::Repl(options.mode));
+    }
+    Ok(Command::Sum(options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, CliError> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn numbers(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn options_and_numbers() {
+        assert_eq!(
+            parse(&["2", "-3", "--mode=wrapping", "-.5", "-t", "big"]),
+            Ok(Command::Sum(Options {
+                mode: OverflowPolicy::Wrapping,
+                kind: Kind::Big,
+                numbers: numbers(&["2", "-3", "-.5"]),
+                format: OutputFormat::Plain,
+            }))
+        );
+        assert_eq!(
+            parse(&["-m", "saturating", "-f", "ndjson", "--", "--5", "-x"]),
+            Ok(Command::Sum(Options {
+                mode: OverflowPolicy::Saturating,
+                kind: Kind::Auto,
+                numbers: numbers(&["--5", "-x"]),
+                format: OutputFormat::Ndjson,
+            }))
+        );
+        assert_eq!(parse(&["1", "--help", "--bogus"]), Ok(Command::Help));
+        assert_eq!(parse(&[]), Ok(Command::Repl(OverflowPolicy::Checked)));
+        assert_eq!(
+            parse(&["--mode", "saturating"]),
+            Ok(Command::Repl(OverflowPolicy::Saturating))
+        );
+    }
+
+    #[test]
+    fn usage_errors() {
+        let usage = |message: &str| Err(CliError::Usage(message.into()));
+        assert_eq!(parse(&["--mode"]), usage("--mode needs a value"));
+        assert_eq!(
+            parse(&["-f", "yaml", "1"]),
+            usage("invalid format \"yaml\" (expected plain, table, json or ndjson)")
+        );
+        assert_eq!(
+            parse(&["--mode", "panicking", "1"]),
+            usage("invalid mode \"panicking\" (expected checked, wrapping or saturating)")
+        );
+        assert_eq!(
+            parse(&["--type=float", "1"]),
+            usage("invalid type \"float\" (expected auto, int, decimal or big)")
+        );
+        assert_eq!(parse(&["-x", "1"]), usage("unknown option \"-x\""));
+        // Only long options take an inline value.
+        assert_eq!(parse(&["-t=big", "1"]), usage("unknown option \"-t=big\""));
+        assert_eq!(
+            parse(&["--format=json"]),
+            usage("--format needs numbers; the calculator only prints plain text")
+        );
+        assert_eq!(
+            parse(&["--format=plain"]),
+            Ok(Command::Repl(OverflowPolicy::Checked))
+        );
+    }
+}
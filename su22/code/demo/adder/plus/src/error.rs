@@ -0,0 +1,45 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use adder::AddError;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CliError {
+    Usage(String),
+    /// `position` counts the numbers from 1, skipping options.
+    InvalidNumber {
+        arg: String,
+        position: usize,
+        reason: String,
+    },
+    Overflow(AddError),
+    Io(String),
+}
+
+impl CliError {
+    /// 2 for anything wrong with the input, 1 when valid input overflows or
+    /// the terminal fails.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Usage(_) | CliError::InvalidNumber { .. } => 2,
+            CliError::Overflow(_) | CliError::Io(_) => 1,
+        }
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => f.write_str(message),
+            CliError::InvalidNumber {
+                arg,
+                position,
+                reason,
+            } => write!(f, "invalid number {arg:?} (number {position}): {reason}"),
+            CliError::Overflow(error) => write!(f, "{error}; try --mode or --type big"),
+            CliError::Io(message) => f.write_str(message),
+        }
+    }
+}
+
+impl Error for CliError {}
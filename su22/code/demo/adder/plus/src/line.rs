@@ -0,0 +1,628 @@
+//! A small line editor on plain std: raw terminal mode through `termios`,
+//! emacs-style keys, and history kept in a dotfile. When stdin is not a
+//! terminal it falls back to reading whole lines.
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::path::PathBuf;
+
+/// Entries kept in memory and, after trimming, in the history file.
+pub const HISTORY_LIMIT: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    /// A control key, as its lowercase letter: `Ctrl('a')` is Ctrl-A.
+    Ctrl(char),
+    Unknown,
+}
+
+/// Reads one key press, decoding UTF-8 and the usual VT100 escape
+/// sequences; `None` at end of input.
+pub fn read_key(input: &mut impl Read) -> io::Result<Option<Key>> {
+    let Some(byte) = read_byte(input)? else {
+        return Ok(None);
+    };
+    Ok(Some(match byte {
+        b'\r' | b'\n' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        0x1b => read_escape(input)?,
+        0x01..=0x1a => Key::Ctrl((b'a' + byte - 1) as char),
+        0x20..=0x7e => Key::Char(byte as char),
+        0xc0..=0xf7 => {
+            let len = match byte {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                _ => 4,
+            };
+            let mut bytes = vec![byte];
+            for _ in 1..len {
+                match read_byte(input)? {
+                    Some(next) => bytes.push(next),
+                    None => break,
+                }
+            }
+            match std::str::from_utf8(&bytes) {
+                Ok(text) => Key::Char(text.chars().next().unwrap()),
+                Err(_) => Key::Unknown,
+            }
+        }
+        _ => Key::Unknown,
+    }))
+}
+
+fn read_byte(input: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut byte = [0];
+    loop {
+        match input.read(&mut byte) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(byte[0])),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Decodes what follows an ESC: `[A`-style and `O`-style cursor keys and
+/// `[3~`-style editing keys. Anything else is consumed up to its final byte.
+fn read_escape(input: &mut impl Read) -> io::Result<Key> {
+    let introducer = read_byte(input)?;
+    if !matches!(introducer, Some(b'[' | b'O')) {
+        return Ok(Key::Unknown);
+    }
+    let mut params = Vec::new();
+    loop {
+        let Some(byte) = read_byte(input)? else {
+            return Ok(Key::Unknown);
+        };
+        if (0x40..=0x7e).contains(&byte) {
+            return Ok(match (params.as_slice(), byte) {
+                ([], b'A') => Key::Up,
+                ([], b'B') => Key::Down,
+                ([], b'C') => Key::Right,
+                ([], b'D') => Key::Left,
+                ([], b'H') => Key::Home,
+                ([], b'F') => Key::End,
+                (b"1" | b"7", b'~') => Key::Home,
+                (b"4" | b"8", b'~') => Key::End,
+                (b"3", b'~') => Key::Delete,
+                _ => Key::Unknown,
+            });
+        }
+        params.push(byte);
+    }
+}
+
+/// What the caller should do after a key press.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    Continue,
+    Submit(String),
+    /// Ctrl-C: drop the line and prompt again.
+    Cancel,
+    /// Ctrl-D on an empty line.
+    Eof,
+}
+
+/// The line being edited, with a cursor and a position in the history.
+#[derive(Debug, Default)]
+pub struct Editor {
+    buffer: Vec<char>,
+    cursor: usize,
+    /// The history entry shown, and the line typed before moving to it.
+    browsing: Option<(usize, Vec<char>)>,
+}
+
+impl Editor {
+    pub fn new() -> Self {
+        Editor::default()
+    }
+
+    pub fn line(&self) -> String {
+        self.buffer.iter().collect()
+    }
+
+    pub fn handle(&mut self, key: Key, history: &[String]) -> Event {
+        match key {
+            Key::Enter => {
+                let line = self.line();
+                *self = Editor::new();
+                return Event::Submit(line);
+            }
+            Key::Ctrl('c') => {
+                *self = Editor::new();
+                return Event::Cancel;
+            }
+            Key::Ctrl('d') if self.buffer.is_empty() => return Event::Eof,
+            Key::Char(c) => {
+                self.buffer.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            Key::Backspace | Key::Ctrl('h') if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.buffer.remove(self.cursor);
+            }
+            Key::Delete | Key::Ctrl('d') if self.cursor < self.buffer.len() => {
+                self.buffer.remove(self.cursor);
+            }
+            Key::Left | Key::Ctrl('b') => self.cursor = self.cursor.saturating_sub(1),
+            Key::Right | Key::Ctrl('f') => self.cursor = (self.cursor + 1).min(self.buffer.len()),
+            Key::Home | Key::Ctrl('a') => self.cursor = 0,
+            Key::End | Key::Ctrl('e') => self.cursor = self.buffer.len(),
+            Key::Ctrl('u') => {
+                self.buffer.drain(..self.cursor);
+                self.cursor = 0;
+            }
+            Key::Ctrl('k') => self.buffer.truncate(self.cursor),
+            Key::Ctrl('w') => {
+                let mut start = self.cursor;
+                while start > 0 && self.buffer[start - 1] == ' ' {
+                    start -= 1;
+                }
+                while start > 0 && self.buffer[start - 1] != ' ' {
+                    start -= 1;
+                }
+                self.buffer.drain(start..self.cursor);
+                self.cursor = start;
+            }
+            Key::Up | Key::Ctrl('p') => self.browse_back(history),
+            Key::Down | Key::Ctrl('n') => self.browse_forward(history),
+            _ => {}
+        }
+        Event::Continue
+    }
+
+    fn browse_back(&mut self, history: &[String]) {
+        let index = match &self.browsing {
+            None if history.is_empty() => return,
+            None => {
+                self.browsing = Some((history.len(), self.buffer.clone()));
+                history.len() - 1
+            }
+            Some((0, _)) => return,
+            Some((index, _)) => index - 1,
+        };
+        self.show(index, history[index].chars().collect());
+    }
+
+    fn browse_forward(&mut self, history: &[String]) {
+        let Some((index, draft)) = &self.browsing else {
+            return;
+        };
+        if index + 1 < history.len() {
+            let index = index + 1;
+            self.show(index, history[index].chars().collect());
+        } else {
+            self.buffer = draft.clone();
+            self.cursor = self.buffer.len();
+            self.browsing = None;
+        }
+    }
+
+    fn show(&mut self, index: usize, line: Vec<char>) {
+        if let Some((shown, _)) = &mut self.browsing {
+            *shown = index;
+        }
+        self.buffer = line;
+        self.cursor = self.buffer.len();
+    }
+
+    /// Redraws the prompt and line in place and puts the cursor back,
+    /// assuming each character takes one column.
+    pub fn render(&self, prompt: &str) -> String {
+        let mut out = format!("\r{prompt}{}\x1b[K", self.line());
+        let back = self.buffer.len() - self.cursor;
+        if back > 0 {
+            out.push_str(&format!("\x1b[{back}D"));
+        }
+        out
+    }
+}
+
+/// Entered lines, oldest first, appended to a file as they are added.
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl History {
+    /// `$PLUS_HISTORY`, or `~/.plus_history`.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os("PLUS_HISTORY") {
+            return Some(PathBuf::from(path));
+        }
+        env::var_os("HOME").map(|home| PathBuf::from(home).join(".plus_history"))
+    }
+
+    /// Loads the last `HISTORY_LIMIT` lines of `path`, rewriting the file
+    /// when it has grown well past that. A missing or unreadable file just
+    /// starts an empty history.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let text = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        let mut entries: Vec<String> = text.lines().map(str::to_string).collect();
+        if entries.len() > HISTORY_LIMIT {
+            let excess = entries.len() - HISTORY_LIMIT;
+            entries.drain(..excess);
+            if excess >= HISTORY_LIMIT {
+                if let Some(path) = &path {
+                    // Trimming is best-effort; the appends still work.
+                    let _ = fs::write(path, entries.join("\n") + "\n");
+                }
+            }
+        }
+        History { entries, path }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Records a line unless it is blank or repeats the previous one.
+    pub fn push(&mut self, line: &str) -> io::Result<()> {
+        if line.trim().is_empty() || self.entries.last().map(String::as_str) == Some(line) {
+            return Ok(());
+        }
+        self.entries.push(line.to_string());
+        if self.entries.len() > HISTORY_LIMIT {
+            self.entries.remove(0);
+        }
+        if let Some(path) = &self.path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads lines with editing when attached to a terminal, and plainly
+/// otherwise.
+pub struct LineReader {
+    history: History,
+    interactive: bool,
+    /// Set once a history write has failed and been reported.
+    history_failed: bool,
+}
+
+impl LineReader {
+    pub fn new(history: History) -> Self {
+        LineReader {
+            history,
+            interactive: io::stdin().is_terminal() && io::stdout().is_terminal(),
+            history_failed: false,
+        }
+    }
+
+    pub fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+
+    /// The next line without its newline, or `None` at end of input. Only
+    /// interactive lines go into the history.
+    pub fn read_line(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        if self.interactive {
+            if let Some(raw) = raw::RawMode::enable() {
+                let line = self.read_edited(prompt);
+                drop(raw);
+                if let Ok(Some(line)) = &line {
+                    self.remember(line);
+                }
+                return line;
+            }
+            print!("{prompt}");
+            io::stdout().flush()?;
+        }
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.strip_suffix('\n').unwrap_or(&line);
+        Ok(Some(line.strip_suffix('\r').unwrap_or(line).to_string()))
+    }
+
+    /// Adds `line` to the history. Saving it is best-effort: the first
+    /// failure is reported on stderr and the session carries on.
+    fn remember(&mut self, line: &str) {
+        if let Err(error) = self.history.push(line) {
+            if !self.history_failed {
+                eprintln!("warning: history not saved: {error}");
+                self.history_failed = true;
+            }
+        }
+    }
+
+    fn read_edited(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        let mut stdin = io::stdin().lock();
+        let mut stdout = io::stdout().lock();
+        let mut editor = Editor::new();
+        write!(stdout, "{}", editor.render(prompt))?;
+        stdout.flush()?;
+        loop {
+            let Some(key) = read_key(&mut stdin)? else {
+                write!(stdout, "\r\n")?;
+                return Ok(None);
+            };
+            match editor.handle(key, self.history.entries()) {
+                Event::Continue => write!(stdout, "{}", editor.render(prompt))?,
+                Event::Submit(line) => {
+                    write!(stdout, "\r\n")?;
+                    return Ok(Some(line));
+                }
+                Event::Cancel => write!(stdout, "^C\r\n{}", editor.render(prompt))?,
+                Event::Eof => {
+                    write!(stdout, "\r\n")?;
+                    return Ok(None);
+                }
+            }
+            stdout.flush()?;
+        }
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+))]
+mod raw {
+    //! Just enough of `termios` to turn off line buffering and echo. The
+    //! struct below is the generic Linux layout; powerpc, mips, sparc and
+    //! alpha lay `termios` out differently and use the fallback instead.
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Termios {
+        c_iflag: u32,
+        c_oflag: u32,
+        c_cflag: u32,
+        c_lflag: u32,
+        c_line: u8,
+        c_cc: [u8; 32],
+        c_ispeed: u32,
+        c_ospeed: u32,
+    }
+
+    extern "C" {
+        fn tcgetattr(fd: i32, termios: *mut Termios) -> i32;
+        fn tcsetattr(fd: i32, action: i32, termios: *const Termios) -> i32;
+    }
+
+    const STDIN: i32 = 0;
+    const TCSADRAIN: i32 = 1;
+    const ISIG: u32 = 0o1;
+    const ICANON: u32 = 0o2;
+    const ECHO: u32 = 0o10;
+    const ICRNL: u32 = 0o400;
+    const IXON: u32 = 0o2000;
+    const VTIME: usize = 5;
+    const VMIN: usize = 6;
+
+    /// Raw mode on stdin until dropped.
+    pub struct RawMode {
+        saved: Termios,
+    }
+
+    impl RawMode {
+        pub fn enable() -> Option<Self> {
+            let mut saved = Termios {
+                c_iflag: 0,
+                c_oflag: 0,
+                c_cflag: 0,
+                c_lflag: 0,
+                c_line: 0,
+                c_cc: [0; 32],
+                c_ispeed: 0,
+                c_ospeed: 0,
+            };
+            // SAFETY: both calls get a pointer to a live, correctly laid
+            // out `termios` and only touch stdin's terminal settings.
+            unsafe {
+                if tcgetattr(STDIN, &mut saved) != 0 {
+                    return None;
+                }
+                let mut raw = saved;
+                raw.c_lflag &= !(ECHO | ICANON | ISIG);
+                raw.c_iflag &= !(ICRNL | IXON);
+                raw.c_cc[VMIN] = 1;
+                raw.c_cc[VTIME] = 0;
+                if tcsetattr(STDIN, TCSADRAIN, &raw) != 0 {
+                    return None;
+                }
+            }
+            Some(RawMode { saved })
+        }
+    }
+
+    impl Drop for RawMode {
+        fn drop(&mut self) {
+            // SAFETY: restores the settings read in `enable`.
+            unsafe {
+                tcsetattr(STDIN, TCSADRAIN, &self.saved);
+            }
+        }
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+)))]
+mod raw {
+    /// Raw mode is only implemented for Linux on the architectures above;
+    /// elsewhere lines are read with the terminal's own editing.
+    pub struct RawMode;
+
+    impl RawMode {
+        pub fn enable() -> Option<Self> {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(bytes: &[u8]) -> Vec<Key> {
+        let mut input = bytes;
+        let mut keys = Vec::new();
+        while let Some(key) = read_key(&mut input).unwrap() {
+            keys.push(key);
+        }
+        keys
+    }
+
+    fn type_keys(editor: &mut Editor, keys: &[Key], history: &[String]) -> Vec<Event> {
+        keys.iter()
+            .map(|&key| editor.handle(key, history))
+            .filter(|event| *event != Event::Continue)
+            .collect()
+    }
+
+    fn chars(text: &str) -> Vec<Key> {
+        text.chars().map(Key::Char).collect()
+    }
+
+    #[test]
+    fn decodes_keys() {
+        assert_eq!(
+            keys(b"a\x1b[A\x1b[B\x1b[C\x1b[D\x1bOH\x1b[4~\x1b[3~\x7f\r\x01\x1b[1;5C"),
+            [
+                Key::Char('a'),
+                Key::Up,
+                Key::Down,
+                Key::Right,
+                Key::Left,
+                Key::Home,
+                Key::End,
+                Key::Delete,
+                Key::Backspace,
+                Key::Enter,
+                Key::Ctrl('a'),
+                Key::Unknown,
+            ]
+        );
+        assert_eq!(keys("é€".as_bytes()), [Key::Char('é'), Key::Char('€')]);
+        assert_eq!(keys(b"\xff\x1b"), [Key::Unknown, Key::Unknown]);
+    }
+
+    #[test]
+    fn edits_the_line() {
+        let mut editor = Editor::new();
+        type_keys(&mut editor, &chars("1 + 3"), &[]);
+        type_keys(
+            &mut editor,
+            &[Key::Left, Key::Backspace, Key::Backspace, Key::Char('*')],
+            &[],
+        );
+        assert_eq!((editor.line(), editor.cursor), ("1 *3".to_string(), 3));
+        assert_eq!(editor.render("> "), "\r> 1 *3\x1b[K\x1b[1D");
+        type_keys(&mut editor, &[Key::Home, Key::Delete, Key::Char('2')], &[]);
+        assert_eq!(editor.line(), "2 *3");
+        type_keys(&mut editor, &[Key::Ctrl('k')], &[]);
+        assert_eq!(editor.line(), "2");
+        type_keys(&mut editor, &chars(" + max(4, 5)"), &[]);
+        type_keys(&mut editor, &[Key::Ctrl('w'), Key::Ctrl('w')], &[]);
+        assert_eq!(editor.line(), "2 + ");
+        type_keys(&mut editor, &[Key::Left, Key::Ctrl('u')], &[]);
+        assert_eq!((editor.line(), editor.cursor), (" ".to_string(), 0));
+        let events = type_keys(&mut editor, &[Key::End, Key::Char('7'), Key::Enter], &[]);
+        assert_eq!(events, [Event::Submit(" 7".into())]);
+        assert_eq!(editor.line(), "");
+
+        let events = type_keys(
+            &mut editor,
+            &[Key::Char('x'), Key::Ctrl('c'), Key::Ctrl('d')],
+            &[],
+        );
+        assert_eq!(events, [Event::Cancel, Event::Eof]);
+    }
+
+    #[test]
+    fn browses_history() {
+        let history = ["1 + 1".to_string(), "let x = 2".to_string()];
+        let mut editor = Editor::new();
+        type_keys(&mut editor, &chars("dra"), &history);
+        type_keys(&mut editor, &[Key::Up], &history);
+        assert_eq!(editor.line(), "let x = 2");
+        type_keys(&mut editor, &[Key::Up, Key::Up], &history);
+        assert_eq!(editor.line(), "1 + 1");
+        type_keys(&mut editor, &[Key::Down], &history);
+        assert_eq!(editor.line(), "let x = 2");
+        type_keys(&mut editor, &[Key::Down, Key::Down], &history);
+        assert_eq!((editor.line(), editor.cursor), ("dra".to_string(), 3));
+        type_keys(
+            &mut editor,
+            &[Key::Up, Key::Backspace, Key::Char('3')],
+            &history,
+        );
+        let events = type_keys(&mut editor, &[Key::Enter], &history);
+        assert_eq!(events, [Event::Submit("let x = 3".into())]);
+        type_keys(&mut editor, &[Key::Up], &[]);
+        assert_eq!(editor.line(), "");
+    }
+
+    #[test]
+    fn history_persists() {
+        let path = env::temp_dir().join(format!("plus-history-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut history = History::load(Some(path.clone()));
+        for line in ["1 + 1", "1 + 1", "  ", "let x = 2"] {
+            history.push(line).unwrap();
+        }
+        assert_eq!(history.entries(), ["1 + 1", "let x = 2"]);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 + 1\nlet x = 2\n");
+        assert_eq!(
+            History::load(Some(path.clone())).entries(),
+            history.entries()
+        );
+
+        let lines: Vec<String> = (0..2 * HISTORY_LIMIT + 5).map(|i| i.to_string()).collect();
+        fs::write(&path, lines.join("\n")).unwrap();
+        let history = History::load(Some(path.clone()));
+        assert_eq!(history.entries(), &lines[HISTORY_LIMIT + 5..]);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap().lines().count(),
+            HISTORY_LIMIT
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn history_write_failures_are_not_fatal() {
+        // A directory cannot be opened for appending.
+        let mut reader = LineReader {
+            history: History::load(Some(env::temp_dir())),
+            interactive: false,
+            history_failed: false,
+        };
+        reader.remember("1 + 1");
+        assert!(reader.history_failed);
+        reader.remember("2 * 3");
+        assert_eq!(reader.history.entries(), ["1 + 1", "2 * 3"]);
+    }
+}
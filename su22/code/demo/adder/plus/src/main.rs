@@ -1,3 +1,38 @@
-fn main() {
-    println!("Hello, world!");
+mod args;
+mod error;
+mod line;
+mod repl;
+mod report;
+mod total;
+
+use std::env;
+use std::process::ExitCode;
+
+use args::{parse_args, Command, USAGE};
+use error::CliError;
+
+fn run() -> Result<(), CliError> {
+    match parse_args(env::args().skip(1))? {
+        Command::Help => print!("{USAGE}"),
+        Command::Sum(options) => {
+            let result = total::total(&options);
+            print!("{}", report::render(options.format, &options, &result));
+            result?;
+        }
+        Command::Repl(mode) => repl::run(mode).map_err(|error| CliError::Io(error.to_string()))?,
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("plus: {error}");
+            if let CliError::Usage(_) = error {
+                eprintln!("Try 'plus --help' for more information.");
+            }
+            ExitCode::from(error.exit_code())
+        }
+    }
 }
@@ -0,0 +1,383 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use std::io;
+
+use adder::{add_with, BinOp, Expr, Func, OverflowPolicy};
+
+use crate::args::{mode_name, parse_mode};
+use crate::line::{History, LineReader};
+
+const PROMPT: &str = "plus> ";
+
+/// Every integer up to 2^53 is exact as a float.
+const MAX_EXACT: f64 = 9_007_199_254_740_992.0;
+
+const HELP: &str = "\
+Enter an expression to evaluate it, e.g. (2 + 3) * 4 - 10 / 5.
+  let NAME = EXPR   bind a variable
+  ans               the previous result
+  :vars             list variables
+  :mode [MODE]      show or set integer overflow: checked, wrapping, saturating
+  :help             show this help
+  :quit             leave (or Ctrl-D)
+Operators: + - * / ^ and parentheses. Functions: min, max, abs, sqrt, sin,
+cos, exp, ln. Whole-number results stay exact 64-bit integers, following the
+overflow mode; anything else is a float. Literals above 2^53 are floats too,
+so build larger integers with arithmetic, e.g. 2^62.";
+
+/// A result: an exact integer while every step allows it, otherwise a float.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+}
+
+impl Value {
+    fn as_f64(self) -> f64 {
+        match self {
+            Value::Int(value) => value as f64,
+            Value::Float(value) => value,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(value) => value.fmt(f),
+            Value::Float(value) => value.fmt(f),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Print(String),
+    Nothing,
+    Quit,
+}
+
+/// The variables and settings of one REPL session.
+pub struct Session {
+    vars: BTreeMap<String, Value>,
+    mode: OverflowPolicy,
+}
+
+impl Session {
+    pub fn new(mode: OverflowPolicy) -> Self {
+        Session {
+            vars: BTreeMap::new(),
+            mode,
+        }
+    }
+
+    /// Runs one input line; an error is a message ready to print.
+    pub fn execute(&mut self, line: &str) -> Result<Outcome, String> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(Outcome::Nothing);
+        }
+        if let Some(command) = line.strip_prefix(':') {
+            return self.command(command);
+        }
+        if let Some(binding) = line
+            .strip_prefix("let")
+            .filter(|rest| rest.starts_with(' '))
+        {
+            let (name, src) = binding
+                .split_once('=')
+                .ok_or("expected `let NAME = EXPR`")?;
+            let name = name.trim();
+            check_name(name)?;
+            let value = self.evaluate(src.trim())?;
+            self.vars.insert(name.to_string(), value);
+            return Ok(Outcome::Print(format!("{name} = {value}")));
+        }
+        let value = self.evaluate(line)?;
+        self.vars.insert("ans".to_string(), value);
+        Ok(Outcome::Print(value.to_string()))
+    }
+
+    fn command(&mut self, command: &str) -> Result<Outcome, String> {
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or("");
+        let args: Vec<&str> = words.collect();
+        Ok(match (name, args.as_slice()) {
+            ("help", []) => Outcome::Print(HELP.to_string()),
+            ("quit" | "q", []) => Outcome::Quit,
+            ("vars", []) if self.vars.is_empty() => Outcome::Print("no variables".to_string()),
+            ("vars", []) => Outcome::Print(
+                self.vars
+                    .iter()
+                    .map(|(name, value)| format!("{name} = {value}"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            ("mode", []) => Outcome::Print(format!("mode: {}", mode_name(self.mode))),
+            ("mode", [mode]) => {
+                self.mode = parse_mode(mode).map_err(|error| error.to_string())?;
+                Outcome::Print(format!("mode: {}", mode_name(self.mode)))
+            }
+            ("help" | "quit" | "q" | "vars" | "mode", _) => {
+                return Err(format!("too many arguments to :{name}"))
+            }
+            _ => return Err(format!("unknown command :{name} (try :help)")),
+        })
+    }
+
+    fn evaluate(&self, src: &str) -> Result<Value, String> {
+        let expr = Expr::parse(src).map_err(|error| format!("{error}\n{}", error.excerpt(src)))?;
+        if let Some(result) = self.eval_int(&expr) {
+            return result.map(Value::Int);
+        }
+        let vars: HashMap<String, f64> = self
+            .vars
+            .iter()
+            .map(|(name, value)| (name.clone(), value.as_f64()))
+            .collect();
+        expr.eval(&vars)
+            .map(Value::Float)
+            .map_err(|error| error.to_string())
+    }
+
+    /// Evaluates in `i64` if every step has an exact integer result, or
+    /// returns `None` to fall back to floats.
+    fn eval_int(&self, expr: &Expr) -> Option<Result<i64, String>> {
+        let value = match expr {
+            // Literals are read as floats, so only those up to 2^53 are
+            // known to be the integer that was typed.
+            Expr::Number(value) if value.fract() == 0.0 && value.abs() <= MAX_EXACT => {
+                *value as i64
+            }
+            Expr::Number(_) => return None,
+            Expr::Var(name) => match self.vars.get(name)? {
+                Value::Int(value) => *value,
+                Value::Float(_) => return None,
+            },
+            Expr::Neg(operand) => {
+                let a = match self.eval_int(operand)? {
+                    Ok(a) => a,
+                    error => return Some(error),
+                };
+                let checked = (a.checked_neg(), a.wrapping_neg(), a.saturating_neg());
+                return Some(self.overflow(checked, || format!("-({a})")));
+            }
+            Expr::Binary(op, a, b) => {
+                let (a, b) = match (self.eval_int(a)?, self.eval_int(b)?) {
+                    (Ok(a), Ok(b)) => (a, b),
+                    (Err(error), _) | (_, Err(error)) => return Some(Err(error)),
+                };
+                return self.binary(*op, a, b);
+            }
+            Expr::Call(func @ (Func::Min | Func::Max | Func::Abs), args) => {
+                let mut values = Vec::with_capacity(args.len());
+                for arg in args {
+                    match self.eval_int(arg)? {
+                        Ok(value) => values.push(value),
+                        error => return Some(error),
+                    }
+                }
+                match func {
+                    Func::Min => *values.iter().min().unwrap(),
+                    Func::Max => *values.iter().max().unwrap(),
+                    _ => {
+                        let a = values[0];
+                        let checked = (a.checked_abs(), a.wrapping_abs(), a.saturating_abs());
+                        return Some(self.overflow(checked, || format!("abs({a})")));
+                    }
+                }
+            }
+            Expr::Call(..) => return None,
+        };
+        Some(Ok(value))
+    }
+
+    fn binary(&self, op: BinOp, a: i64, b: i64) -> Option<Result<i64, String>> {
+        let results = match op {
+            BinOp::Add => {
+                return Some(add_with(a, b, self.mode).map_err(|error| error.to_string()))
+            }
+            BinOp::Sub => (a.checked_sub(b), a.wrapping_sub(b), a.saturating_sub(b)),
+            BinOp::Mul => (a.checked_mul(b), a.wrapping_mul(b), a.saturating_mul(b)),
+            // Only exact quotients stay integers; `7 / 2` is 3.5.
+            BinOp::Div if b != 0 && a.checked_rem(b) == Some(0) => {
+                (a.checked_div(b), a.wrapping_div(b), a.saturating_div(b))
+            }
+            BinOp::Div => return None,
+            BinOp::Pow => {
+                let exp = u32::try_from(b).ok()?;
+                (
+                    a.checked_pow(exp),
+                    a.wrapping_pow(exp),
+                    a.saturating_pow(exp),
+                )
+            }
+        };
+        Some(self.overflow(results, || format!("{a} {} {b}", op.symbol())))
+    }
+
+    /// Picks the checked, wrapping or saturating result by the mode.
+    fn overflow(
+        &self,
+        (checked, wrapping, saturating): (Option<i64>, i64, i64),
+        describe: impl FnOnce() -> String,
+    ) -> Result<i64, String> {
+        match (checked, self.mode) {
+            (Some(value), _) => Ok(value),
+            (None, OverflowPolicy::Wrapping) => Ok(wrapping),
+            (None, OverflowPolicy::Saturating) => Ok(saturating),
+            (None, _) => Err(format!("{} overflows i64", describe())),
+        }
+    }
+}
+
+fn check_name(name: &str) -> Result<(), String> {
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!("invalid variable name {name:?}"));
+    }
+    if name == "ans" || name == "let" || Func::from_name(name).is_some() {
+        return Err(format!("`{name}` is reserved"));
+    }
+    Ok(())
+}
+
+/// Runs the interactive loop until `:quit` or end of input.
+pub fn run(mode: OverflowPolicy) -> io::Result<()> {
+    let mut reader = LineReader::new(History::load(History::default_path()));
+    let mut session = Session::new(mode);
+    if reader.is_interactive() {
+        println!("plus: type :help for commands, Ctrl-D to quit");
+    }
+    while let Some(line) = reader.read_line(PROMPT)? {
+        match session.execute(&line) {
+            Ok(Outcome::Print(text)) => println!("{text}"),
+            Ok(Outcome::Nothing) => {}
+            Ok(Outcome::Quit) => break,
+            Err(message) => eprintln!("error: {message}"),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(session: &mut Session, line: &str) -> Result<String, String> {
+        match session.execute(line)? {
+            Outcome::Print(text) => Ok(text),
+            outcome => panic!("{line}: {outcome:?}"),
+        }
+    }
+
+    #[test]
+    fn evaluates_and_binds() {
+        let mut session = Session::new(OverflowPolicy::Checked);
+        assert_eq!(run(&mut session, "(2 + 3) * 4 - 10 / 5"), Ok("18".into()));
+        assert_eq!(run(&mut session, "ans * 2"), Ok("36".into()));
+        assert_eq!(run(&mut session, "let x = 7 / 2"), Ok("x = 3.5".into()));
+        assert_eq!(run(&mut session, "let rate=0.25"), Ok("rate = 0.25".into()));
+        assert_eq!(run(&mut session, "x * 2 + ans"), Ok("43".into()));
+        assert_eq!(
+            run(&mut session, "max(1, -4, 2^3) - abs(-9)"),
+            Ok("-1".into())
+        );
+        assert_eq!(run(&mut session, "sqrt(16)"), Ok("4".into()));
+        assert_eq!(
+            run(&mut session, ":vars"),
+            Ok("ans = 4\nrate = 0.25\nx = 3.5".into())
+        );
+        assert_eq!(session.execute("   "), Ok(Outcome::Nothing));
+        assert_eq!(session.execute(":quit"), Ok(Outcome::Quit));
+        assert_eq!(
+            run(&mut Session::new(OverflowPolicy::Checked), ":vars"),
+            Ok("no variables".into())
+        );
+    }
+
+    #[test]
+    fn integers_follow_the_mode() {
+        let mut session = Session::new(OverflowPolicy::Checked);
+        let max = i64::MAX;
+        assert_eq!(
+            run(&mut session, "let big = 2^62 - 1 + 2^62"),
+            Ok(format!("big = {max}"))
+        );
+        assert_eq!(
+            run(&mut session, "big + 1"),
+            Err(format!("{max} + 1 overflows i64"))
+        );
+        // Too large to be typed exactly, so it is a float.
+        assert_eq!(
+            run(&mut session, &format!("{max} + 1")),
+            Ok("9223372036854776000".into())
+        );
+        assert_eq!(
+            run(&mut session, "2 ^ 63"),
+            Err("2 ^ 63 overflows i64".into())
+        );
+        assert_eq!(
+            run(&mut session, "2 ^ 62"),
+            Ok("4611686018427387904".into())
+        );
+        assert_eq!(run(&mut session, "2 ^ -1"), Ok("0.5".into()));
+        assert_eq!(run(&mut session, ":mode"), Ok("mode: checked".into()));
+        assert_eq!(
+            run(&mut session, ":mode wrapping"),
+            Ok("mode: wrapping".into())
+        );
+        assert_eq!(run(&mut session, "big + 1"), Ok(i64::MIN.to_string()));
+        assert_eq!(
+            run(&mut session, ":mode saturating"),
+            Ok("mode: saturating".into())
+        );
+        assert_eq!(run(&mut session, "big * 3"), Ok(max.to_string()));
+        assert_eq!(run(&mut session, "-2^63 - 1"), Ok(i64::MIN.to_string()));
+        assert_eq!(
+            run(&mut session, ":mode fast"),
+            Err("invalid mode \"fast\" (expected checked, wrapping or saturating)".into())
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut session = Session::new(OverflowPolicy::Checked);
+        assert_eq!(
+            run(&mut session, "1 + * 2"),
+            Err("expected expression at offset 4\n1 + * 2\n    ^".into())
+        );
+        assert_eq!(
+            run(&mut session, "y + 1"),
+            Err("unknown variable `y`".into())
+        );
+        assert_eq!(
+            run(&mut session, "let 2x = 1"),
+            Err("invalid variable name \"2x\"".into())
+        );
+        assert_eq!(
+            run(&mut session, "let ans = 1"),
+            Err("`ans` is reserved".into())
+        );
+        assert_eq!(
+            run(&mut session, "let sqrt = 1"),
+            Err("`sqrt` is reserved".into())
+        );
+        assert_eq!(
+            run(&mut session, "let x"),
+            Err("expected `let NAME = EXPR`".into())
+        );
+        assert_eq!(
+            run(&mut session, ":bogus"),
+            Err("unknown command :bogus (try :help)".into())
+        );
+        assert_eq!(
+            run(&mut session, ":vars now"),
+            Err("too many arguments to :vars".into())
+        );
+        // A failed line leaves the state alone.
+        assert_eq!(run(&mut session, ":vars"), Ok("no variables".into()));
+        assert!(run(&mut session, ":help").unwrap().contains(":mode [MODE]"));
+    }
+}
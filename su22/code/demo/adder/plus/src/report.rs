@@ -0,0 +1,142 @@
+use cli_output::{
+    json_document, json_or_null, json_string, ndjson_record, text_table, Diagnostic, OutputFormat,
+    Severity,
+};
+
+use crate::args::{kind_name, mode_name, Options};
+use crate::error::CliError;
+use crate::total::{resolve_kind, Total};
+
+/// How the sum was worked out: the resolved type, the overflow mode and
+/// how many numbers went in.
+fn context_members(options: &Options) -> String {
+    format!(
+        "\"kind\":{},\"mode\":{},\"count\":{}",
+        json_string(kind_name(resolve_kind(options.kind, &options.numbers))),
+        json_string(mode_name(options.mode)),
+        options.numbers.len()
+    )
+}
+
+/// Only an invalid number points at one input; an overflow is about the
+/// whole sum.
+fn diagnostic(error: &CliError) -> Diagnostic {
+    let (position, input, message) = match error {
+        CliError::InvalidNumber {
+            arg,
+            position,
+            reason,
+        } => (Some(*position as u64), Some(arg.clone()), reason.clone()),
+        other => (None, None, other.to_string()),
+    };
+    Diagnostic {
+        severity: Severity::Error,
+        position,
+        input,
+        message,
+    }
+}
+
+/// The stdout for a sum in `format`. Plain and table print nothing on an
+/// error, which goes to stderr; the machine formats describe it as well.
+pub fn render(format: OutputFormat, options: &Options, result: &Result<Total, CliError>) -> String {
+    match (format, result) {
+        (OutputFormat::Plain, Ok(total)) => format!("{total}\n"),
+        (OutputFormat::Table, Ok(total)) => {
+            let kind = kind_name(resolve_kind(options.kind, &options.numbers));
+            let row = vec![
+                kind.to_string(),
+                mode_name(options.mode).to_string(),
+                options.numbers.len().to_string(),
+                total.to_string(),
+            ];
+            text_table(&["kind", "mode", "count", "total"], &[row], 2)
+        }
+        (OutputFormat::Plain | OutputFormat::Table, Err(_)) => String::new(),
+        (OutputFormat::Json, result) => {
+            let (value, diagnostics) = match result {
+                Ok(total) => (Some(json_string(&total.to_string())), vec![]),
+                Err(error) => (None, vec![diagnostic(error)]),
+            };
+            let members = format!(
+                "\"result\":{},{}",
+                json_or_null(value),
+                context_members(options)
+            );
+            json_document(result.is_ok(), &members, &diagnostics)
+        }
+        (OutputFormat::Ndjson, Ok(total)) => {
+            let members = format!(
+                "\"result\":{},{}",
+                json_string(&total.to_string()),
+                context_members(options)
+            );
+            ndjson_record("result", &members)
+        }
+        (OutputFormat::Ndjson, Err(error)) => diagnostic(error).to_ndjson(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::Kind;
+    use adder::{AddError, OverflowPolicy};
+
+    fn options(numbers: &[&str]) -> Options {
+        Options {
+            mode: OverflowPolicy::Checked,
+            kind: Kind::Auto,
+            numbers: numbers.iter().map(|number| number.to_string()).collect(),
+            format: OutputFormat::Plain,
+        }
+    }
+
+    #[test]
+    fn renders_results() {
+        let options = options(&["0.5", "2"]);
+        let result = Ok(Total::Decimal("2.5".parse().unwrap()));
+        assert_eq!(render(OutputFormat::Plain, &options, &result), "2.5\n");
+        assert_eq!(
+            render(OutputFormat::Table, &options, &result),
+            "kind     mode     count  total\n-------  -------  -----  -----\ndecimal  checked      2    2.5\n"
+        );
+        assert_eq!(
+            render(OutputFormat::Json, &options, &result),
+            "{\"ok\":true,\"result\":\"2.5\",\"kind\":\"decimal\",\"mode\":\"checked\",\"count\":2,\"diagnostics\":[]}\n"
+        );
+        assert_eq!(
+            render(OutputFormat::Ndjson, &options, &result),
+            "{\"type\":\"result\",\"result\":\"2.5\",\"kind\":\"decimal\",\"mode\":\"checked\",\"count\":2}\n"
+        );
+    }
+
+    #[test]
+    fn renders_errors() {
+        let options = options(&["1", "x"]);
+        let invalid = Err(CliError::InvalidNumber {
+            arg: "x".into(),
+            position: 2,
+            reason: "invalid digit found in string".into(),
+        });
+        assert_eq!(render(OutputFormat::Plain, &options, &invalid), "");
+        assert_eq!(render(OutputFormat::Table, &options, &invalid), "");
+        assert_eq!(
+            render(OutputFormat::Json, &options, &invalid),
+            "{\"ok\":false,\"result\":null,\"kind\":\"int\",\"mode\":\"checked\",\"count\":2,\"diagnostics\":[\
+             {\"severity\":\"error\",\"position\":2,\"input\":\"x\",\"message\":\"invalid digit found in string\"}]}\n"
+        );
+        let overflow = CliError::Overflow(AddError::Overflow {
+            ty: "i64",
+            left: "1".into(),
+            right: "2".into(),
+        });
+        assert_eq!(
+            render(OutputFormat::Ndjson, &options, &Err(overflow.clone())),
+            format!(
+                "{{\"type\":\"diagnostic\",\"severity\":\"error\",\"position\":null,\"input\":null,\"message\":{}}}\n",
+                json_string(&overflow.to_string())
+            )
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use std::fmt::{self, Display};
+use std::num::IntErrorKind;
+use std::str::FromStr;
+
+use adder::{add, add_with, BigInt, Decimal};
+
+use crate::args::{Kind, Options};
+use crate::error::CliError;
+
+/// A sum in the type the numbers were parsed as.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Total {
+    Int(i64),
+    Decimal(Decimal),
+    Big(BigInt),
+}
+
+impl Display for Total {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Total::Int(value) => value.fmt(f),
+            Total::Decimal(value) => value.fmt(f),
+            Total::Big(value) => value.fmt(f),
+        }
+    }
+}
+
+/// Resolves `Kind::Auto` from the numbers themselves.
+pub fn resolve_kind(kind: Kind, numbers: &[String]) -> Kind {
+    match kind {
+        Kind::Auto if numbers.iter().any(|number| number.contains('.')) => Kind::Decimal,
+        Kind::Auto => Kind::Int,
+        kind => kind,
+    }
+}
+
+fn parse_all<T: FromStr>(
+    numbers: &[String],
+    reason: impl Fn(T::Err) -> String,
+) -> Result<Vec<T>, CliError> {
+    numbers
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| {
+            arg.parse().map_err(|error| CliError::InvalidNumber {
+                arg: arg.clone(),
+                position: i + 1,
+                reason: reason(error),
+            })
+        })
+        .collect()
+}
+
+pub fn total(options: &Options) -> Result<Total, CliError> {
+    let numbers = &options.numbers;
+    Ok(match resolve_kind(options.kind, numbers) {
+        Kind::Auto | Kind::Int => {
+            let values = parse_all::<i64>(numbers, |error| match error.kind() {
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                    "does not fit in a 64-bit integer; try --type big".to_string()
+                }
+                _ => error.to_string(),
+            })?;
+            let sum = values
+                .into_iter()
+                .try_fold(0, |sum, value| add_with(sum, value, options.mode))
+                .map_err(CliError::Overflow)?;
+            Total::Int(sum)
+        }
+        Kind::Decimal => {
+            let values = parse_all::<Decimal>(numbers, |error| error.to_string())?;
+            Total::Decimal(values.into_iter().fold(Decimal::zero(), add))
+        }
+        Kind::Big => {
+            let values = parse_all::<BigInt>(numbers, |error| error.to_string())?;
+            Total::Big(values.into_iter().fold(BigInt::zero(), add))
+        }
+    })
+}
@@ -0,0 +1,178 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use cli_output::snapshots;
+
+/// Runs the built binary and returns its exit code, stdout and stderr.
+fn plus(args: &[&str]) -> (i32, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_plus"))
+        .args(args)
+        .output()
+        .expect("failed to run plus");
+    (
+        output.status.code().expect("plus was killed by a signal"),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+/// Runs the REPL with `input` piped to stdin.
+fn repl(args: &[&str], input: &str) -> (i32, String, String) {
+    let history = std::env::temp_dir().join(format!("plus-cli-history-{}", std::process::id()));
+    let mut child = Command::new(env!("CARGO_BIN_EXE_plus"))
+        .args(args)
+        .env("PLUS_HISTORY", &history)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run plus");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    // Piped sessions are not interactive and leave no history behind.
+    assert!(!history.exists());
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+fn sum(args: &[&str]) -> String {
+    let (code, stdout, stderr) = plus(args);
+    assert_eq!((code, stderr.as_str()), (0, ""), "plus {args:?}");
+    stdout
+}
+
+#[test]
+fn adds_integers() {
+    assert_eq!(sum(&["2", "3", "4"]), "9\n");
+    assert_eq!(sum(&["-5", "+2"]), "-3\n");
+    assert_eq!(sum(&["42"]), "42\n");
+    assert_eq!(sum(&["--", "-1", "-1"]), "-2\n");
+}
+
+#[test]
+fn adds_decimals_and_big_numbers() {
+    assert_eq!(sum(&["0.1", "0.2"]), "0.3\n");
+    assert_eq!(sum(&["1.50", "2", "-0.125"]), "3.375\n");
+    assert_eq!(sum(&["--type", "decimal", "1", "2"]), "3\n");
+    assert_eq!(
+        sum(&["--type=big", "9223372036854775807", "9223372036854775807"]),
+        "18446744073709551614\n"
+    );
+    assert_eq!(
+        sum(&["-t", "big", "-100000000000000000000000", "1"]),
+        "-99999999999999999999999\n"
+    );
+}
+
+#[test]
+fn overflow_modes() {
+    let max = i64::MAX.to_string();
+    let (code, stdout, stderr) = plus(&[&max, "1"]);
+    assert_eq!(code, 1);
+    assert_eq!(stdout, "");
+    assert_eq!(
+        stderr,
+        "plus: 9223372036854775807 + 1 overflows i64; try --mode or --type big\n"
+    );
+    assert_eq!(sum(&["--mode", "checked", "1", "2"]), "3\n");
+    assert_eq!(
+        sum(&["--mode", "wrapping", &max, "1"]),
+        format!("{}\n", i64::MIN)
+    );
+    assert_eq!(
+        sum(&["-m", "saturating", &max, "1", "-1"]),
+        format!("{}\n", max.parse::<i64>().unwrap() - 1)
+    );
+}
+
+#[test]
+fn bad_input_exits_with_two() {
+    for (args, message) in [
+        (
+            &["1", "abc"][..],
+            "plus: invalid number \"abc\" (number 2): invalid digit found in string\n",
+        ),
+        (
+            &["99999999999999999999"],
+            "plus: invalid number \"99999999999999999999\" (number 1): \
+             does not fit in a 64-bit integer; try --type big\n",
+        ),
+        (
+            &["1.5", "1e3"],
+            "plus: invalid number \"1e3\" (number 2): invalid digit 'e' at index 1\n",
+        ),
+        (
+            &["--mode", "fast", "1"],
+            "plus: invalid mode \"fast\" (expected checked, wrapping or saturating)\n\
+             Try 'plus --help' for more information.\n",
+        ),
+        (
+            &["--verbose", "1"],
+            "plus: unknown option \"--verbose\"\nTry 'plus --help' for more information.\n",
+        ),
+    ] {
+        let (code, stdout, stderr) = plus(args);
+        assert_eq!(
+            (code, stdout.as_str(), stderr.as_str()),
+            (2, "", message),
+            "{args:?}"
+        );
+    }
+}
+
+#[test]
+fn help() {
+    let (code, stdout, stderr) = plus(&["--help"]);
+    assert_eq!((code, stderr.as_str()), (0, ""));
+    assert!(stdout.starts_with("Usage: plus [OPTIONS] NUMBER..."));
+    assert!(stdout.contains("--mode MODE"));
+}
+
+#[test]
+fn repl_without_numbers() {
+    let input = "(2 + 3) * 4 - 10 / 5\nlet x = ans / 4\nx * 2 +\nx * 2\n:vars\n\n:quit\n1 + 1\n";
+    let (code, stdout, stderr) = repl(&[], input);
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "18\nx = 4.5\n9\nans = 9\nx = 4.5\n");
+    assert_eq!(
+        stderr,
+        "error: expected expression at offset 7\nx * 2 +\n       ^\n"
+    );
+
+    let (code, stdout, _) = repl(&["--mode", "wrapping"], "2^62 * 2\n:mode\n");
+    assert_eq!(code, 0);
+    assert_eq!(stdout, format!("{}\nmode: wrapping\n", i64::MIN));
+}
+
+/// Pins every output format for a run of `plus` with `args`.
+fn formats(scenario: &str, args: &[&str]) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots");
+    snapshots(&dir, scenario, |format| {
+        plus(&[&["--format", format], args].concat())
+    });
+}
+
+#[test]
+fn format_snapshots() {
+    formats("int", &["2", "-3", "40"]);
+    formats("decimal", &["0.1", "0.25"]);
+    formats("big", &["-t", "big", "99999999999999999999", "1"]);
+    formats("invalid", &["1", "2\"x"]);
+    formats("overflow", &["9223372036854775807", "1"]);
+}
+
+#[test]
+fn format_needs_numbers() {
+    let (code, stdout, stderr) = plus(&["--format", "json"]);
+    assert_eq!((code, stdout.as_str()), (2, ""));
+    assert!(stderr.starts_with("plus: --format needs numbers"));
+}
@@ -0,0 +1,876 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::str::FromStr;
+
+use crate::{Int, Num};
+
+const BITS: u32 = u32::BITS;
+
+/// An arbitrary-precision unsigned integer.
+///
+/// Stored as little-endian base 2^32 digits with no trailing zero digits, so
+/// zero is the empty vector and every value has exactly one representation.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    digits: Vec<u32>,
+}
+
+/// An arbitrary-precision signed integer.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseBigIntError {
+    Empty,
+    InvalidDigit { index: usize, found: char },
+}
+
+impl Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBigIntError::Empty => write!(f, "cannot parse integer from empty string"),
+            ParseBigIntError::InvalidDigit { index, found } => {
+                write!(f, "invalid digit {found:?} at index {index}")
+            }
+        }
+    }
+}
+
+impl Error for ParseBigIntError {}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint { digits: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        BigUint { digits: vec![1] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Number of significant bits; zero has none.
+    pub fn bits(&self) -> u64 {
+        match self.digits.last() {
+            Some(top) => self.digits.len() as u64 * BITS as u64 - top.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.digits.first().is_none_or(|low| low % 2 == 0)
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = BigUint::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    /// Greatest common divisor; `gcd(0, 0)` is 0.
+    pub fn gcd(&self, other: &BigUint) -> BigUint {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = b;
+            b = r;
+        }
+        a
+    }
+
+    pub fn checked_sub(&self, rhs: &BigUint) -> Option<BigUint> {
+        if *self < *rhs {
+            return None;
+        }
+        let mut digits = self.digits.clone();
+        let mut borrow = 0u64;
+        for (i, digit) in digits.iter_mut().enumerate() {
+            let r = rhs.digits.get(i).copied().unwrap_or(0) as u64 + borrow;
+            if i >= rhs.digits.len() && borrow == 0 {
+                break;
+            }
+            let d = *digit as u64;
+            if d >= r {
+                *digit = (d - r) as u32;
+                borrow = 0;
+            } else {
+                *digit = (d + (1 << BITS) - r) as u32;
+                borrow = 1;
+            }
+        }
+        Some(BigUint::from_digits(digits))
+    }
+
+    pub fn checked_div_rem(&self, rhs: &BigUint) -> Option<(BigUint, BigUint)> {
+        if rhs.is_zero() {
+            return None;
+        }
+        if self < rhs {
+            return Some((BigUint::zero(), self.clone()));
+        }
+        if rhs.digits.len() == 1 {
+            let (quotient, remainder) = self.div_rem_small(rhs.digits[0]);
+            return Some((quotient, BigUint::from(remainder)));
+        }
+        Some(self.div_rem_knuth(rhs))
+    }
+
+    /// Divides with remainder, panicking on division by zero.
+    pub fn div_rem(&self, rhs: &BigUint) -> (BigUint, BigUint) {
+        self.checked_div_rem(rhs)
+            .expect("attempt to divide by zero")
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.digits.len() > 4 {
+            return None;
+        }
+        Some(
+            self.digits
+                .iter()
+                .rev()
+                .fold(0u128, |acc, &digit| (acc << BITS) | digit as u128),
+        )
+    }
+
+    /// Approximate `f64` value; infinite if out of range.
+    pub fn to_f64(&self) -> f64 {
+        let bits = self.bits();
+        if bits <= 64 {
+            return self.to_u128().unwrap() as f64;
+        }
+        let shift = bits - 64;
+        if shift > 1023 {
+            return f64::INFINITY;
+        }
+        let top = self.shr(shift).to_u128().unwrap() as f64;
+        top * 2f64.powi(shift as i32)
+    }
+
+    pub fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseBigIntError> {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix must be in the range 2..=36, got {radix}"
+        );
+        if src.is_empty() {
+            return Err(ParseBigIntError::Empty);
+        }
+        let mut value = BigUint::zero();
+        for (index, found) in src.char_indices() {
+            let digit = found
+                .to_digit(radix)
+                .ok_or(ParseBigIntError::InvalidDigit { index, found })?;
+            value.mul_add_small(radix, digit);
+        }
+        Ok(value)
+    }
+
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix must be in the range 2..=36, got {radix}"
+        );
+        if self.is_zero() {
+            return "0".to_string();
+        }
+        // Peel off as many digits per division as fit in a u32.
+        let mut chunk = radix;
+        let mut per_chunk = 1;
+        while let Some(next) = chunk.checked_mul(radix) {
+            chunk = next;
+            per_chunk += 1;
+        }
+        let mut out = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, mut remainder) = rest.div_rem_small(chunk);
+            rest = quotient;
+            for _ in 0..per_chunk {
+                out.push(std::char::from_digit(remainder % radix, radix).unwrap());
+                remainder /= radix;
+                if rest.is_zero() && remainder == 0 {
+                    break;
+                }
+            }
+        }
+        out.iter().rev().collect()
+    }
+
+    fn from_digits(mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        BigUint { digits }
+    }
+
+    fn mul_add_small(&mut self, factor: u32, addend: u32) {
+        let mut carry = addend as u64;
+        for digit in self.digits.iter_mut() {
+            let product = *digit as u64 * factor as u64 + carry;
+            *digit = product as u32;
+            carry = product >> BITS;
+        }
+        if carry > 0 {
+            self.digits.push(carry as u32);
+        }
+    }
+
+    fn div_rem_small(&self, divisor: u32) -> (BigUint, u32) {
+        let mut quotient = vec![0; self.digits.len()];
+        let mut remainder = 0u64;
+        for (i, &digit) in self.digits.iter().enumerate().rev() {
+            let current = (remainder << BITS) | digit as u64;
+            quotient[i] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        (BigUint::from_digits(quotient), remainder as u32)
+    }
+
+    pub(crate) fn shl(&self, shift: u64) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let whole = (shift / BITS as u64) as usize;
+        let part = (shift % BITS as u64) as u32;
+        let mut digits = vec![0; whole];
+        if part == 0 {
+            digits.extend_from_slice(&self.digits);
+        } else {
+            let mut carry = 0;
+            for &digit in &self.digits {
+                digits.push((digit << part) | carry);
+                carry = digit >> (BITS - part);
+            }
+            digits.push(carry);
+        }
+        BigUint::from_digits(digits)
+    }
+
+    pub(crate) fn shr(&self, shift: u64) -> BigUint {
+        let whole = (shift / BITS as u64) as usize;
+        if whole >= self.digits.len() {
+            return BigUint::zero();
+        }
+        let part = (shift % BITS as u64) as u32;
+        let rest = &self.digits[whole..];
+        if part == 0 {
+            return BigUint::from_digits(rest.to_vec());
+        }
+        let digits = (0..rest.len())
+            .map(|i| {
+                let high = rest.get(i + 1).map_or(0, |&d| d << (BITS - part));
+                (rest[i] >> part) | high
+            })
+            .collect();
+        BigUint::from_digits(digits)
+    }
+
+    /// Knuth's Algorithm D (TAOCP vol. 2, 4.3.1) for divisors of two or more digits.
+    fn div_rem_knuth(&self, rhs: &BigUint) -> (BigUint, BigUint) {
+        let shift = rhs.digits.last().unwrap().leading_zeros() as u64;
+        let v = rhs.shl(shift).digits;
+        let mut u = self.shl(shift).digits;
+        u.push(0);
+        let n = v.len();
+        let m = u.len() - n - 1;
+        let base = 1u64 << BITS;
+        let mut quotient = vec![0; m + 1];
+
+        for j in (0..=m).rev() {
+            let top = ((u[j + n] as u64) << BITS) | u[j + n - 1] as u64;
+            let mut qhat = top / v[n - 1] as u64;
+            let mut rhat = top % v[n - 1] as u64;
+            while qhat >= base || qhat * v[n - 2] as u64 > ((rhat << BITS) | u[j + n - 2] as u64) {
+                qhat -= 1;
+                rhat += v[n - 1] as u64;
+                if rhat >= base {
+                    break;
+                }
+            }
+
+            let mut borrow = 0i64;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let product = qhat * v[i] as u64 + carry;
+                carry = product >> BITS;
+                let t = u[i + j] as i64 - borrow - (product & 0xffff_ffff) as i64;
+                u[i + j] = t as u32;
+                borrow = (t < 0) as i64;
+            }
+            let t = u[j + n] as i64 - borrow - carry as i64;
+            u[j + n] = t as u32;
+
+            if t < 0 {
+                // qhat was one too large: add the divisor back.
+                qhat -= 1;
+                let mut carry = 0u64;
+                for i in 0..n {
+                    let sum = u[i + j] as u64 + v[i] as u64 + carry;
+                    u[i + j] = sum as u32;
+                    carry = sum >> BITS;
+                }
+                u[j + n] = u[j + n].wrapping_add(carry as u32);
+            }
+            quotient[j] = qhat as u32;
+        }
+
+        u.truncate(n);
+        let remainder = BigUint::from_digits(u).shr(shift);
+        (BigUint::from_digits(quotient), remainder)
+    }
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        BigInt::default()
+    }
+
+    pub fn one() -> Self {
+        BigInt::from(1u8)
+    }
+
+    pub fn from_parts(negative: bool, magnitude: BigUint) -> Self {
+        BigInt {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// -1, 0 or 1 according to the sign of the value.
+    pub fn signum(&self) -> i32 {
+        if self.negative {
+            -1
+        } else if self.is_zero() {
+            0
+        } else {
+            1
+        }
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    pub fn into_magnitude(self) -> BigUint {
+        self.magnitude
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::from(self.magnitude.clone())
+    }
+
+    pub fn pow(&self, exp: u32) -> Self {
+        BigInt::from_parts(self.negative && exp % 2 == 1, self.magnitude.pow(exp))
+    }
+
+    /// Truncating division, matching the primitive integers: the quotient
+    /// rounds toward zero and the remainder takes the sign of `self`.
+    pub fn checked_div_rem(&self, rhs: &BigInt) -> Option<(BigInt, BigInt)> {
+        let (quotient, remainder) = self.magnitude.checked_div_rem(&rhs.magnitude)?;
+        Some((
+            BigInt::from_parts(self.negative != rhs.negative, quotient),
+            BigInt::from_parts(self.negative, remainder),
+        ))
+    }
+
+    pub fn div_rem(&self, rhs: &BigInt) -> (BigInt, BigInt) {
+        self.checked_div_rem(rhs)
+            .expect("attempt to divide by zero")
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = self.magnitude.to_u128()?;
+        if self.negative {
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self.magnitude.to_f64();
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    pub fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseBigIntError> {
+        let (negative, digits) = match src.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, src.strip_prefix('+').unwrap_or(src)),
+        };
+        let offset = src.len() - digits.len();
+        let magnitude = BigUint::from_str_radix(digits, radix).map_err(|error| match error {
+            ParseBigIntError::InvalidDigit { index, found } => ParseBigIntError::InvalidDigit {
+                index: index + offset,
+                found,
+            },
+            error => error,
+        })?;
+        Ok(BigInt::from_parts(negative, magnitude))
+    }
+
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        let digits = self.magnitude.to_str_radix(radix);
+        if self.negative {
+            format!("-{digits}")
+        } else {
+            digits
+        }
+    }
+}
+
+fn add_magnitudes(left: &BigUint, right: &BigUint) -> BigUint {
+    let (long, short) = if left.digits.len() >= right.digits.len() {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    let mut digits = Vec::with_capacity(long.digits.len() + 1);
+    let mut carry = 0u64;
+    for (i, &digit) in long.digits.iter().enumerate() {
+        let sum = digit as u64 + short.digits.get(i).copied().unwrap_or(0) as u64 + carry;
+        digits.push(sum as u32);
+        carry = sum >> BITS;
+    }
+    if carry > 0 {
+        digits.push(carry as u32);
+    }
+    BigUint { digits }
+}
+
+fn mul_magnitudes(left: &BigUint, right: &BigUint) -> BigUint {
+    if left.is_zero() || right.is_zero() {
+        return BigUint::zero();
+    }
+    let mut digits = vec![0u32; left.digits.len() + right.digits.len()];
+    for (i, &a) in left.digits.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &b) in right.digits.iter().enumerate() {
+            let t = a as u64 * b as u64 + digits[i + j] as u64 + carry;
+            digits[i + j] = t as u32;
+            carry = t >> BITS;
+        }
+        digits[i + right.digits.len()] = carry as u32;
+    }
+    BigUint::from_digits(digits)
+}
+
+/// Adds two signed values given as sign and magnitude.
+fn add_signed(a_negative: bool, a: &BigUint, b_negative: bool, b: &BigUint) -> BigInt {
+    if a_negative == b_negative {
+        return BigInt::from_parts(a_negative, add_magnitudes(a, b));
+    }
+    match a.cmp(b) {
+        Ordering::Less => BigInt::from_parts(b_negative, b.checked_sub(a).unwrap()),
+        _ => BigInt::from_parts(a_negative, a.checked_sub(b).unwrap()),
+    }
+}
+
+forward_binop!(BigUint, Add, add, |a, b| add_magnitudes(a, b));
+forward_binop!(BigUint, Sub, sub, |a, b| a
+    .checked_sub(b)
+    .expect("attempt to subtract with overflow"));
+forward_binop!(BigUint, Mul, mul, |a, b| mul_magnitudes(a, b));
+forward_binop!(BigUint, Div, div, |a, b| a.div_rem(b).0);
+forward_binop!(BigUint, Rem, rem, |a, b| a.div_rem(b).1);
+
+forward_binop!(BigInt, Add, add, |a, b| add_signed(
+    a.negative,
+    &a.magnitude,
+    b.negative,
+    &b.magnitude
+));
+forward_binop!(BigInt, Sub, sub, |a, b| add_signed(
+    a.negative,
+    &a.magnitude,
+    !b.negative,
+    &b.magnitude
+));
+forward_binop!(BigInt, Mul, mul, |a, b| BigInt::from_parts(
+    a.negative != b.negative,
+    mul_magnitudes(&a.magnitude, &b.magnitude)
+));
+forward_binop!(BigInt, Div, div, |a, b| a.div_rem(b).0);
+forward_binop!(BigInt, Rem, rem, |a, b| a.div_rem(b).1);
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+macro_rules! from_unsigned {
+    ($($t:ty)*) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(value: $t) -> Self {
+                    let mut value = value as u128;
+                    let mut digits = Vec::new();
+                    while value > 0 {
+                        digits.push(value as u32);
+                        value >>= BITS;
+                    }
+                    BigUint { digits }
+                }
+            }
+
+            impl From<$t> for BigInt {
+                fn from(value: $t) -> Self {
+                    BigInt::from(BigUint::from(value))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! from_signed {
+    ($($t:ty)*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(value: $t) -> Self {
+                    BigInt::from_parts(value < 0, BigUint::from(value.unsigned_abs()))
+                }
+            }
+        )*
+    };
+}
+
+from_unsigned!(u8 u16 u32 u64 u128 usize);
+from_signed!(i8 i16 i32 i64 i128 isize);
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        BigInt::from_parts(false, magnitude)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        BigUint::from_str_radix(src, 10)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        BigInt::from_str_radix(src, 10)
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_str_radix(10))
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_str_radix(10))
+    }
+}
+
+macro_rules! impl_num {
+    ($($t:ident)*) => {
+        $(
+            impl Num for $t {
+                fn zero() -> Self {
+                    $t::zero()
+                }
+
+                fn one() -> Self {
+                    $t::one()
+                }
+
+                fn add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+
+                fn sub(self, rhs: Self) -> Self {
+                    self - rhs
+                }
+
+                fn mul(self, rhs: Self) -> Self {
+                    self * rhs
+                }
+
+                fn div(self, rhs: Self) -> Self {
+                    self / rhs
+                }
+            }
+
+            /// Big integers never overflow, so every policy is plain addition.
+            impl Int for $t {
+                const NAME: &'static str = stringify!($t);
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    Some(self + rhs)
+                }
+
+                fn wrapping_add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+
+                fn saturating_add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+            }
+        )*
+    };
+}
+
+impl_num!(BigUint BigInt);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+    use crate::{add, checked_add};
+
+    fn big(value: u128) -> BigUint {
+        BigUint::from(value)
+    }
+
+    #[test]
+    fn plugs_into_add() {
+        let total = add(big(u128::MAX), big(1));
+        assert_eq!(total.to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(add(BigInt::from(-5), BigInt::from(3)), BigInt::from(-2));
+        assert_eq!(
+            checked_add(big(u128::MAX), big(u128::MAX)),
+            Ok(big(u128::MAX) * big(2))
+        );
+    }
+
+    #[test]
+    fn matches_u128() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..2000 {
+            let (a, b) = (rng.u128(), rng.u128());
+            if let Some(sum) = a.checked_add(b) {
+                assert_eq!(big(a) + big(b), big(sum));
+            }
+            if let Some(product) = a.checked_mul(b) {
+                assert_eq!(big(a) * big(b), big(product));
+            }
+            assert_eq!(big(a).checked_sub(&big(b)), a.checked_sub(b).map(big));
+            assert_eq!(big(a).cmp(&big(b)), a.cmp(&b));
+            if b != 0 {
+                assert_eq!(
+                    big(a).div_rem(&big(b)),
+                    (big(a / b), big(a % b)),
+                    "{a} / {b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn division_identity() {
+        let mut rng = Rng(42);
+        for _ in 0..500 {
+            let a = big(rng.u128()) * big(rng.u128()) * big(rng.u128());
+            let b = big(rng.u128()) * big(rng.u128()) + big(1);
+            let (q, r) = a.div_rem(&b);
+            assert!(r < b);
+            assert_eq!(&q * &b + &r, a);
+        }
+    }
+
+    #[test]
+    fn division_edge_digits() {
+        // Digits near 0, 2^31 and 2^32 stress the qhat estimate and its corrections.
+        let edges = [
+            0,
+            1,
+            0x7fff_ffff,
+            0x8000_0000,
+            0x8000_0001,
+            0xffff_fffe,
+            0xffff_ffff,
+        ];
+        let mut rng = Rng(3);
+        let mut pick = |len: u64| {
+            let digits = (0..1 + rng.next() % len)
+                .map(|_| edges[(rng.next() % edges.len() as u64) as usize])
+                .collect();
+            BigUint::from_digits(digits)
+        };
+        for _ in 0..5000 {
+            let a = pick(8);
+            let b = pick(4);
+            if b.is_zero() {
+                continue;
+            }
+            let (q, r) = a.div_rem(&b);
+            assert!(r < b);
+            assert_eq!(&q * &b + &r, a);
+        }
+    }
+
+    #[test]
+    fn signed_arithmetic() {
+        let cases: [(i64, i64); 8] = [
+            (7, 3),
+            (-7, 3),
+            (7, -3),
+            (-7, -3),
+            (0, 5),
+            (5, 5),
+            (-5, 5),
+            (i64::MIN, -1),
+        ];
+        for (a, b) in cases {
+            let (x, y) = (BigInt::from(a), BigInt::from(b));
+            let (a, b) = (a as i128, b as i128);
+            assert_eq!((&x + &y).to_i128(), Some(a + b));
+            assert_eq!((&x - &y).to_i128(), Some(a - b));
+            assert_eq!((&x * &y).to_i128(), Some(a * b));
+            assert_eq!((&x / &y).to_i128(), Some(a / b));
+            assert_eq!((&x % &y).to_i128(), Some(a % b));
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+        }
+        assert!(!(BigInt::from(3) - BigInt::from(3)).is_negative());
+        assert_eq!(-BigInt::zero(), BigInt::zero());
+    }
+
+    #[test]
+    fn radix_round_trip() {
+        let mut rng = Rng(7);
+        for radix in 2..=36 {
+            for _ in 0..20 {
+                let value = big(rng.u128()) * big(rng.u128());
+                let text = value.to_str_radix(radix);
+                assert_eq!(BigUint::from_str_radix(&text, radix), Ok(value));
+            }
+        }
+        assert_eq!(big(255).to_str_radix(16), "ff");
+        assert_eq!(big(0).to_str_radix(2), "0");
+        assert_eq!(BigInt::from(-35).to_str_radix(36), "-z");
+        assert_eq!(BigInt::from_str_radix("-Z", 36), Ok(BigInt::from(-35)));
+        assert_eq!(BigInt::from_str_radix("+101", 2), Ok(BigInt::from(5)));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigIntError::Empty));
+        assert_eq!("-".parse::<BigInt>(), Err(ParseBigIntError::Empty));
+        assert_eq!(
+            "-12a".parse::<BigInt>(),
+            Err(ParseBigIntError::InvalidDigit {
+                index: 3,
+                found: 'a'
+            })
+        );
+        assert_eq!(
+            "-1".parse::<BigUint>(),
+            Err(ParseBigIntError::InvalidDigit {
+                index: 0,
+                found: '-'
+            })
+        );
+    }
+
+    #[test]
+    fn formatting() {
+        let value: BigInt = "-123456789012345678901234567890".parse().unwrap();
+        assert_eq!(value.to_string(), "-123456789012345678901234567890");
+        assert_eq!(format!("{:>6}", BigInt::from(-42)), "   -42");
+        assert_eq!(format!("{:+}", BigUint::from(7u8)), "+7");
+    }
+
+    #[test]
+    fn powers_and_floats() {
+        assert_eq!(big(10).pow(30).to_string(), format!("1{}", "0".repeat(30)));
+        assert_eq!(BigInt::from(-2).pow(3), BigInt::from(-8));
+        assert_eq!(big(1).shl(200).to_f64(), 2f64.powi(200));
+        assert_eq!(big(1).shl(200).bits(), 201);
+        assert_eq!(BigInt::from(-3).to_f64(), -3.0);
+    }
+
+    #[test]
+    fn gcd() {
+        assert_eq!(big(12).gcd(&big(18)), big(6));
+        assert_eq!(big(0).gcd(&big(5)), big(5));
+        assert_eq!(big(0).gcd(&big(0)), big(0));
+        let p = big(1_000_000_007);
+        assert_eq!((&p * big(6)).gcd(&(&p * big(35))), p);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to subtract with overflow")]
+    fn unsigned_underflow() {
+        let _ = big(1) - big(2);
+    }
+}
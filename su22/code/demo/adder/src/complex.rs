@@ -0,0 +1,372 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use crate::{add, Float, Num};
+
+/// A complex number `re + im·i`.
+///
+/// Addition, subtraction and multiplication work for any `Num`; division
+/// and the transcendental functions need a `Float`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseComplexError {
+    Empty,
+    InvalidPart(String),
+}
+
+impl Display for ParseComplexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseComplexError::Empty => write!(f, "cannot parse complex number from empty string"),
+            ParseComplexError::InvalidPart(part) => write!(f, "invalid number {part:?}"),
+        }
+    }
+}
+
+impl Error for ParseComplexError {}
+
+impl<T: Num> Complex<T> {
+    pub fn new(re: T, im: T) -> Self {
+        Complex { re, im }
+    }
+
+    /// The imaginary unit.
+    pub fn i() -> Self {
+        Complex::new(T::zero(), T::one())
+    }
+
+    pub fn conj(&self) -> Self {
+        Complex::new(self.re.clone(), Num::neg(self.im.clone()))
+    }
+
+    /// `re² + im²`, the squared norm, which needs no square root.
+    pub fn norm_sqr(&self) -> T {
+        add(
+            self.re.clone().mul(self.re.clone()),
+            self.im.clone().mul(self.im.clone()),
+        )
+    }
+
+    pub fn scale(&self, factor: T) -> Self {
+        Complex::new(
+            self.re.clone().mul(factor.clone()),
+            self.im.clone().mul(factor),
+        )
+    }
+}
+
+impl<T: Float> Complex<T> {
+    /// The absolute value `|z|`, computed without intermediate overflow.
+    pub fn norm(&self) -> T {
+        self.re.hypot(self.im)
+    }
+
+    /// The argument in `(-π, π]`.
+    pub fn arg(&self) -> T {
+        self.im.atan2(self.re)
+    }
+
+    /// `(|z|, arg z)`.
+    pub fn to_polar(&self) -> (T, T) {
+        (self.norm(), self.arg())
+    }
+
+    pub fn from_polar(r: T, theta: T) -> Self {
+        Complex::new(r * theta.cos(), r * theta.sin())
+    }
+
+    pub fn exp(&self) -> Self {
+        Complex::from_polar(self.re.exp(), self.im)
+    }
+
+    /// The principal natural logarithm, with imaginary part in `(-π, π]`.
+    pub fn ln(&self) -> Self {
+        Complex::new(self.norm().ln(), self.arg())
+    }
+
+    pub fn recip(&self) -> Self {
+        Complex::new(T::one(), T::zero()) / *self
+    }
+}
+
+impl<T: Num> Add for Complex<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Complex::new(add(self.re, rhs.re), add(self.im, rhs.im))
+    }
+}
+
+impl<T: Num> Sub for Complex<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Complex::new(self.re.sub(rhs.re), self.im.sub(rhs.im))
+    }
+}
+
+impl<T: Num> Mul for Complex<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let re = self.re.clone().mul(rhs.re.clone());
+        let re = re.sub(self.im.clone().mul(rhs.im.clone()));
+        let im = add(self.re.mul(rhs.im), self.im.mul(rhs.re));
+        Complex::new(re, im)
+    }
+}
+
+/// Smith's algorithm: scaling by the larger component of the divisor avoids
+/// the overflow and underflow of the textbook `z * conj(w) / |w|²`.
+impl<T: Float> Div for Complex<T> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        let (a, b, c, d) = (self.re, self.im, rhs.re, rhs.im);
+        if c.abs() >= d.abs() {
+            let ratio = d / c;
+            let denom = c + d * ratio;
+            Complex::new((a + b * ratio) / denom, (b - a * ratio) / denom)
+        } else {
+            let ratio = c / d;
+            let denom = c * ratio + d;
+            Complex::new((a * ratio + b) / denom, (b * ratio - a) / denom)
+        }
+    }
+}
+
+impl<T: Num> Neg for Complex<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Complex::new(Num::neg(self.re), Num::neg(self.im))
+    }
+}
+
+impl<T: Num> From<T> for Complex<T> {
+    fn from(re: T) -> Self {
+        Complex::new(re, T::zero())
+    }
+}
+
+/// Formats as `a+bi` or `a-bi`, e.g. `3-4i`. A precision applies to both parts.
+impl<T: Float> Display for Complex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.im < T::zero()
+            || (self.im == T::zero() && self.im.to_f64().is_sign_negative())
+        {
+            '-'
+        } else {
+            '+'
+        };
+        let im = self.im.abs();
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}{sign}{:.*}i", precision, self.re, precision, im),
+            None => write!(f, "{}{sign}{}i", self.re, im),
+        }
+    }
+}
+
+/// Parses `a+bi`, `a-bi`, a bare real `a`, or a bare imaginary `bi`.
+/// A missing coefficient means 1, so `i` and `2-i` are accepted.
+impl<T: Float> FromStr for Complex<T> {
+    type Err = ParseComplexError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        if src.is_empty() {
+            return Err(ParseComplexError::Empty);
+        }
+        let real = |part: &str| {
+            part.parse::<T>()
+                .map_err(|_| ParseComplexError::InvalidPart(part.to_string()))
+        };
+        let imaginary = |part: &str| match part {
+            "" | "+" => Ok(T::one()),
+            "-" => Ok(-T::one()),
+            _ => real(part),
+        };
+
+        let Some(body) = src.strip_suffix('i') else {
+            return Ok(Complex::new(real(src)?, T::zero()));
+        };
+        // The split is at the last sign that is neither leading nor part of
+        // an exponent such as `1e-3`.
+        let bytes = body.as_bytes();
+        let split = (1..bytes.len())
+            .rev()
+            .find(|&i| matches!(bytes[i], b'+' | b'-') && !matches!(bytes[i - 1], b'e' | b'E'));
+        match split {
+            Some(i) => Ok(Complex::new(real(&body[..i])?, imaginary(&body[i..])?)),
+            None => Ok(Complex::new(T::zero(), imaginary(body)?)),
+        }
+    }
+}
+
+impl<T: Float> Num for Complex<T> {
+    fn zero() -> Self {
+        Complex::new(T::zero(), T::zero())
+    }
+
+    fn one() -> Self {
+        Complex::new(T::one(), T::zero())
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        self + rhs
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        self - rhs
+    }
+
+    fn mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+
+    fn div(self, rhs: Self) -> Self {
+        self / rhs
+    }
+
+    fn neg(self) -> Self {
+        -self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+
+    type C = Complex<f64>;
+
+    fn c(re: f64, im: f64) -> C {
+        Complex::new(re, im)
+    }
+
+    fn assert_close(actual: C, expected: C) {
+        let scale = expected.norm().max(1.0);
+        assert!(
+            (actual - expected).norm() <= 1e-12 * scale,
+            "{actual} is not close to {expected}"
+        );
+    }
+
+    fn random(rng: &mut Rng) -> C {
+        let mut part = || rng.range(-1_000_000, 1_000_000) as f64 / 1000.0;
+        c(part(), part())
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(c(1.0, 2.0) + c(3.0, -4.0), c(4.0, -2.0));
+        assert_eq!(c(1.0, 2.0) - c(3.0, -4.0), c(-2.0, 6.0));
+        assert_eq!(c(1.0, 2.0) * c(3.0, 4.0), c(-5.0, 10.0));
+        assert_eq!(c(-5.0, 10.0) / c(3.0, 4.0), c(1.0, 2.0));
+        assert_eq!(C::i() * C::i(), c(-1.0, 0.0));
+        assert_eq!(add(c(1.0, 1.0), c(1.0, -1.0)), c(2.0, 0.0));
+        assert_eq!(c(3.0, 4.0).norm(), 5.0);
+        assert_eq!(c(3.0, 4.0).conj(), c(3.0, -4.0));
+        // Integer components work for the ring operations.
+        assert_eq!(
+            Complex::new(2, 3) * Complex::new(4, -1),
+            Complex::new(11, 10)
+        );
+        assert_eq!(Complex::new(2, 3).norm_sqr(), 13);
+    }
+
+    #[test]
+    fn algebraic_identities() {
+        let mut rng = Rng(31);
+        for _ in 0..500 {
+            let (z, w, v) = (random(&mut rng), random(&mut rng), random(&mut rng));
+            assert_close(z * z.conj(), c(z.norm_sqr(), 0.0));
+            assert_close(z * (w + v), z * w + z * v);
+            assert_close((z * w) * v, z * (w * v));
+            if w.norm() > 1e-3 {
+                assert_close((z / w) * w, z);
+                assert_close(w * w.recip(), C::one());
+            }
+            let (r, theta) = z.to_polar();
+            assert_close(Complex::from_polar(r, theta), z);
+            if z.norm() > 1e-3 {
+                assert_close(z.ln().exp(), z);
+            }
+            // Keep exponents small enough that exp stays finite.
+            let (z, w) = (z.scale(1e-3), w.scale(1e-3));
+            assert_close(z.exp().ln(), z);
+            assert_close((z + w).exp(), z.exp() * w.exp());
+        }
+    }
+
+    #[test]
+    fn euler() {
+        let pi = std::f64::consts::PI;
+        assert_close(c(0.0, pi).exp() + C::one(), C::zero());
+        assert_close(c(-1.0, 0.0).ln(), c(0.0, pi));
+        assert_close(C::from_polar(2.0, pi / 2.0), c(0.0, 2.0));
+        assert_eq!(c(-1.0, -0.0).arg(), -pi);
+        // On the branch cut the sign of the zero picks the side, so
+        // conjugating must flip it.
+        for z in [c(-2.0, 0.0), c(-2.0, -0.0)] {
+            assert_eq!(z.conj().ln(), z.ln().conj());
+            assert_eq!(z.conj().im.is_sign_negative(), z.im.is_sign_positive());
+            assert_eq!((-z).im.is_sign_negative(), z.im.is_sign_positive());
+        }
+        assert_eq!(c(-2.0, 0.0).conj().ln().im, -pi);
+    }
+
+    #[test]
+    fn robust_division() {
+        let big = c(1e300, 1e300);
+        assert_close(big / big, C::one());
+        let tiny = c(1e-300, 1e-300);
+        assert_close(tiny / tiny, C::one());
+    }
+
+    #[test]
+    fn display_and_parse() {
+        assert_eq!(c(3.0, 4.0).to_string(), "3+4i");
+        assert_eq!(c(3.0, -4.5).to_string(), "3-4.5i");
+        assert_eq!(c(-1.0, 0.0).to_string(), "-1+0i");
+        assert_eq!(format!("{:.2}", c(1.0 / 3.0, -2.0)), "0.33-2.00i");
+
+        let cases = [
+            ("3+4i", c(3.0, 4.0)),
+            ("-3-4i", c(-3.0, -4.0)),
+            ("2.5", c(2.5, 0.0)),
+            ("-7i", c(0.0, -7.0)),
+            ("i", c(0.0, 1.0)),
+            ("-i", c(0.0, -1.0)),
+            ("2-i", c(2.0, -1.0)),
+            ("1e-3+2E+2i", c(1e-3, 200.0)),
+            ("+1-1e5i", c(1.0, -1e5)),
+        ];
+        for (src, expected) in cases {
+            assert_eq!(src.parse::<C>(), Ok(expected), "{src}");
+        }
+        let mut rng = Rng(4);
+        for _ in 0..200 {
+            let z = c(
+                f64::from_bits(rng.next() >> 2),
+                -f64::from_bits(rng.next() >> 2),
+            );
+            assert_eq!(z.to_string().parse::<C>(), Ok(z));
+        }
+        assert_eq!("".parse::<C>(), Err(ParseComplexError::Empty));
+        assert_eq!(
+            "3+xi".parse::<C>(),
+            Err(ParseComplexError::InvalidPart("+x".into()))
+        );
+        assert_eq!(
+            "3+4j".parse::<C>(),
+            Err(ParseComplexError::InvalidPart("3+4j".into()))
+        );
+    }
+}
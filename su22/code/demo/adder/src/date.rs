@@ -0,0 +1,1127 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+
+pub const MIN_YEAR: i32 = 0;
+pub const MAX_YEAR: i32 = 9999;
+
+const SECS_PER_DAY: i64 = 86_400;
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+/// A day in the proleptic Gregorian calendar, from year 0 to 9999 (the
+/// range RFC 3339 can represent).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+/// A date and time of day at a fixed UTC offset, with nanosecond precision.
+///
+/// Equality and ordering compare the instant, so `12:00Z` equals
+/// `13:00+01:00`.
+#[derive(Clone, Copy, Debug)]
+pub struct DateTime {
+    date: Date,
+    secs: u32,
+    nanos: u32,
+    offset_minutes: i16,
+}
+
+/// A signed span of time, kept as whole seconds plus `0..1e9` nanoseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration {
+    secs: i64,
+    nanos: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateError {
+    InvalidMonth(u8),
+    InvalidDay {
+        year: i32,
+        month: u8,
+        day: u8,
+    },
+    InvalidTime {
+        hour: u8,
+        minute: u8,
+        second: u8,
+    },
+    InvalidOffset(i32),
+    /// The year is outside `MIN_YEAR..=MAX_YEAR`.
+    OutOfRange,
+}
+
+impl Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateError::InvalidMonth(month) => write!(f, "month {month} is not in 1..=12"),
+            DateError::InvalidDay { year, month, day } => {
+                write!(f, "{year:04}-{month:02} has no day {day}")
+            }
+            DateError::InvalidTime {
+                hour,
+                minute,
+                second,
+            } => write!(f, "invalid time {hour:02}:{minute:02}:{second:02}"),
+            DateError::InvalidOffset(minutes) => {
+                write!(f, "invalid UTC offset of {minutes} minutes")
+            }
+            DateError::OutOfRange => write!(f, "date out of range"),
+        }
+    }
+}
+
+impl Error for DateError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseDateError {
+    /// The input did not match at byte `index`, where `expected` was due.
+    Syntax {
+        index: usize,
+        expected: &'static str,
+    },
+    Range(DateError),
+}
+
+impl Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseDateError::Syntax { index, expected } => {
+                write!(f, "expected {expected} at byte {index}")
+            }
+            ParseDateError::Range(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for ParseDateError {}
+
+impl From<DateError> for ParseDateError {
+    fn from(error: DateError) -> Self {
+        ParseDateError::Range(error)
+    }
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Panics if `month` is not in `1..=12`.
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => panic!("month {month} is not in 1..=12"),
+    }
+}
+
+impl Date {
+    pub fn new(year: i32, month: u8, day: u8) -> Result<Self, DateError> {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+            return Err(DateError::OutOfRange);
+        }
+        if !(1..=12).contains(&month) {
+            return Err(DateError::InvalidMonth(month));
+        }
+        if day == 0 || day > days_in_month(year, month) {
+            return Err(DateError::InvalidDay { year, month, day });
+        }
+        Ok(Date { year, month, day })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Days since 1970-01-01, negative before it.
+    pub fn to_days(&self) -> i64 {
+        // Howard Hinnant's days_from_civil: count from 0000-03-01 so the
+        // leap day falls at the end of each shifted year.
+        let year = self.year as i64 - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month_from_march = (self.month as i64 + 9) % 12;
+        let day_of_year = (153 * month_from_march + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// The inverse of `to_days`, or `None` outside the supported years.
+    pub fn from_days(days: i64) -> Option<Self> {
+        let days = days.checked_add(719_468)?;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.checked_sub(era.checked_mul(146_097)?)?;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_from_march = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u8;
+        let month = if month_from_march < 10 {
+            month_from_march + 3
+        } else {
+            month_from_march - 9
+        } as u8;
+        let year = era
+            .checked_mul(400)?
+            .checked_add(year_of_era + (month <= 2) as i64)?;
+        let year = i32::try_from(year).ok()?;
+        Date::new(year, month, day).ok()
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        // 1970-01-01 was a Thursday.
+        Weekday::from_monday((self.to_days() + 3).rem_euclid(7) as u8)
+    }
+
+    /// The day of the year, from 1.
+    pub fn ordinal(&self) -> u16 {
+        let before: u16 = (1..self.month)
+            .map(|m| days_in_month(self.year, m) as u16)
+            .sum();
+        before + self.day as u16
+    }
+
+    /// The ISO 8601 week-numbering year and week in `1..=53`. Weeks start
+    /// on Monday and week 1 is the one containing the year's first Thursday.
+    pub fn iso_week(&self) -> (i32, u8) {
+        let monday = self.to_days() - self.weekday().number_from_monday() as i64 + 1;
+        // The Thursday decides which year the week belongs to. Near the ends
+        // of the supported range it may fall outside; compute it unchecked.
+        let thursday = monday + 3;
+        let (year, ordinal) = match Date::from_days(thursday) {
+            Some(date) => (date.year, date.ordinal() as i64),
+            None if thursday < Date::first_of(MIN_YEAR) => {
+                (MIN_YEAR - 1, thursday - Date::first_of(MIN_YEAR - 1) + 1)
+            }
+            None => (MAX_YEAR + 1, thursday - Date::first_of(MAX_YEAR + 1) + 1),
+        };
+        (year, ((ordinal - 1) / 7 + 1) as u8)
+    }
+
+    fn first_of(year: i32) -> i64 {
+        Date {
+            year,
+            month: 1,
+            day: 1,
+        }
+        .to_days()
+    }
+
+    pub fn checked_add_days(&self, days: i64) -> Option<Self> {
+        Date::from_days(self.to_days().checked_add(days)?)
+    }
+
+    /// Moves by whole months, clamping the day to the end of a shorter
+    /// month: 2024-01-31 plus one month is 2024-02-29.
+    pub fn checked_add_months(&self, months: i32) -> Option<Self> {
+        let index = self.year as i64 * 12 + self.month as i64 - 1 + months as i64;
+        let year = i32::try_from(index.div_euclid(12)).ok()?;
+        let month = (index.rem_euclid(12) + 1) as u8;
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+            return None;
+        }
+        let day = self.day.min(days_in_month(year, month));
+        Date::new(year, month, day).ok()
+    }
+
+    /// Moves by whole years, so 2024-02-29 plus one year is 2025-02-28.
+    pub fn checked_add_years(&self, years: i32) -> Option<Self> {
+        self.checked_add_months(years.checked_mul(12)?)
+    }
+
+    pub fn add_days(&self, days: i64) -> Self {
+        self.checked_add_days(days).expect("date out of range")
+    }
+
+    pub fn add_months(&self, months: i32) -> Self {
+        self.checked_add_months(months).expect("date out of range")
+    }
+
+    pub fn add_years(&self, years: i32) -> Self {
+        self.checked_add_years(years).expect("date out of range")
+    }
+
+    /// Midnight at the start of this date in UTC.
+    pub fn and_midnight(&self) -> DateTime {
+        DateTime {
+            date: *self,
+            secs: 0,
+            nanos: 0,
+            offset_minutes: 0,
+        }
+    }
+}
+
+impl Weekday {
+    fn from_monday(index: u8) -> Self {
+        use Weekday::*;
+        [
+            Monday, Tuesday, Wednesday, Thursday, Friday, Saturday, Sunday,
+        ][index as usize]
+    }
+
+    /// Monday is 1 and Sunday is 7, as in ISO 8601.
+    pub fn number_from_monday(&self) -> u8 {
+        *self as u8 + 1
+    }
+}
+
+impl DateTime {
+    /// A UTC date and time; use `with_nanosecond` and `with_offset` for the
+    /// remaining fields. Leap seconds are not supported.
+    pub fn new(date: Date, hour: u8, minute: u8, second: u8) -> Result<Self, DateError> {
+        if hour > 23 || minute > 59 || second > 59 {
+            return Err(DateError::InvalidTime {
+                hour,
+                minute,
+                second,
+            });
+        }
+        Ok(DateTime {
+            date,
+            secs: hour as u32 * 3600 + minute as u32 * 60 + second as u32,
+            nanos: 0,
+            offset_minutes: 0,
+        })
+    }
+
+    /// Panics if `nanos` is a second or more.
+    pub fn with_nanosecond(self, nanos: u32) -> Self {
+        assert!(nanos < NANOS_PER_SEC, "nanosecond {nanos} out of range");
+        DateTime { nanos, ..self }
+    }
+
+    /// The same local date and time read at a different UTC offset.
+    pub fn with_offset(self, minutes: i32) -> Result<Self, DateError> {
+        if minutes.abs() >= 24 * 60 {
+            return Err(DateError::InvalidOffset(minutes));
+        }
+        Ok(DateTime {
+            offset_minutes: minutes as i16,
+            ..self
+        })
+    }
+
+    /// The same instant as seen at a different UTC offset.
+    pub fn to_offset(self, minutes: i32) -> Result<Self, DateError> {
+        let shifted = self.with_offset(minutes)?;
+        let delta = Duration::minutes(minutes as i64 - self.offset_minutes as i64);
+        shifted.checked_shift(delta).ok_or(DateError::OutOfRange)
+    }
+
+    /// Seconds since 1970-01-01T00:00:00Z.
+    pub fn unix_timestamp(&self) -> i64 {
+        self.local_secs() - self.offset_minutes as i64 * 60
+    }
+
+    /// The UTC date and time `secs` seconds after the Unix epoch.
+    pub fn from_unix_timestamp(secs: i64, nanos: u32) -> Option<Self> {
+        assert!(nanos < NANOS_PER_SEC, "nanosecond {nanos} out of range");
+        let date = Date::from_days(secs.div_euclid(SECS_PER_DAY))?;
+        Some(DateTime {
+            date,
+            secs: secs.rem_euclid(SECS_PER_DAY) as u32,
+            nanos,
+            offset_minutes: 0,
+        })
+    }
+
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    pub fn hour(&self) -> u8 {
+        (self.secs / 3600) as u8
+    }
+
+    pub fn minute(&self) -> u8 {
+        (self.secs / 60 % 60) as u8
+    }
+
+    pub fn second(&self) -> u8 {
+        (self.secs % 60) as u8
+    }
+
+    pub fn nanosecond(&self) -> u32 {
+        self.nanos
+    }
+
+    pub fn offset_minutes(&self) -> i32 {
+        self.offset_minutes as i32
+    }
+
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        self.checked_shift(duration)
+    }
+
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        self.checked_shift(duration.checked_neg()?)
+    }
+
+    fn local_secs(&self) -> i64 {
+        self.date.to_days() * SECS_PER_DAY + self.secs as i64
+    }
+
+    /// Moves the local wall-clock time, keeping the offset.
+    fn checked_shift(&self, duration: Duration) -> Option<Self> {
+        let local = Duration {
+            secs: self.local_secs(),
+            nanos: self.nanos,
+        }
+        .checked_add(duration)?;
+        let date = Date::from_days(local.secs.div_euclid(SECS_PER_DAY))?;
+        Some(DateTime {
+            date,
+            secs: local.secs.rem_euclid(SECS_PER_DAY) as u32,
+            nanos: local.nanos,
+            offset_minutes: self.offset_minutes,
+        })
+    }
+
+    fn instant(&self) -> (i64, u32) {
+        (self.unix_timestamp(), self.nanos)
+    }
+}
+
+impl Duration {
+    pub const ZERO: Duration = Duration { secs: 0, nanos: 0 };
+
+    /// `secs + nanos / 1e9`, with any `nanos` carried into the seconds.
+    /// Panics if the carry takes the seconds out of `i64`.
+    pub fn new(secs: i64, nanos: i64) -> Self {
+        let secs = secs
+            .checked_add(nanos.div_euclid(NANOS_PER_SEC as i64))
+            .expect("duration overflow");
+        Duration {
+            secs,
+            nanos: nanos.rem_euclid(NANOS_PER_SEC as i64) as u32,
+        }
+    }
+
+    /// Panics if the seconds leave `i64`; see `checked_days`.
+    pub fn days(days: i64) -> Self {
+        Duration::checked_days(days).expect("duration overflow")
+    }
+
+    /// Panics if the seconds leave `i64`; see `checked_hours`.
+    pub fn hours(hours: i64) -> Self {
+        Duration::checked_hours(hours).expect("duration overflow")
+    }
+
+    /// Panics if the seconds leave `i64`; see `checked_minutes`.
+    pub fn minutes(minutes: i64) -> Self {
+        Duration::checked_minutes(minutes).expect("duration overflow")
+    }
+
+    pub fn checked_days(days: i64) -> Option<Self> {
+        days.checked_mul(SECS_PER_DAY).map(Duration::seconds)
+    }
+
+    pub fn checked_hours(hours: i64) -> Option<Self> {
+        hours.checked_mul(3600).map(Duration::seconds)
+    }
+
+    pub fn checked_minutes(minutes: i64) -> Option<Self> {
+        minutes.checked_mul(60).map(Duration::seconds)
+    }
+
+    pub fn seconds(secs: i64) -> Self {
+        Duration { secs, nanos: 0 }
+    }
+
+    pub fn milliseconds(millis: i64) -> Self {
+        Duration::new(millis.div_euclid(1000), millis.rem_euclid(1000) * 1_000_000)
+    }
+
+    pub fn nanoseconds(nanos: i64) -> Self {
+        Duration::new(0, nanos)
+    }
+
+    /// Whole days, rounded towards zero.
+    pub fn whole_days(&self) -> i64 {
+        self.whole_seconds() / SECS_PER_DAY
+    }
+
+    /// Whole seconds, rounded towards zero.
+    pub fn whole_seconds(&self) -> i64 {
+        if self.secs < 0 && self.nanos > 0 {
+            self.secs + 1
+        } else {
+            self.secs
+        }
+    }
+
+    pub fn as_seconds_f64(&self) -> f64 {
+        self.secs as f64 + self.nanos as f64 / NANOS_PER_SEC as f64
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.secs < 0
+    }
+
+    pub fn checked_add(self, rhs: Duration) -> Option<Self> {
+        let nanos = self.nanos + rhs.nanos;
+        let carry = (nanos >= NANOS_PER_SEC) as i64;
+        Some(Duration {
+            secs: self.secs.checked_add(rhs.secs)?.checked_add(carry)?,
+            nanos: nanos % NANOS_PER_SEC,
+        })
+    }
+
+    pub fn checked_sub(self, rhs: Duration) -> Option<Self> {
+        let (nanos, borrow) = match self.nanos.checked_sub(rhs.nanos) {
+            Some(nanos) => (nanos, 0),
+            None => (self.nanos + NANOS_PER_SEC - rhs.nanos, 1),
+        };
+        Some(Duration {
+            secs: self.secs.checked_sub(rhs.secs)?.checked_sub(borrow)?,
+            nanos,
+        })
+    }
+
+    /// `None` only for `Duration::seconds(i64::MIN)`.
+    pub fn checked_neg(self) -> Option<Self> {
+        if self.nanos == 0 {
+            return self.secs.checked_neg().map(Duration::seconds);
+        }
+        Some(Duration {
+            // `-secs - 1`, which cannot overflow.
+            secs: !self.secs,
+            nanos: NANOS_PER_SEC - self.nanos,
+        })
+    }
+}
+
+impl Add for Duration {
+    type Output = Duration;
+
+    fn add(self, rhs: Duration) -> Duration {
+        self.checked_add(rhs)
+            .expect("overflow when adding durations")
+    }
+}
+
+impl Sub for Duration {
+    type Output = Duration;
+
+    fn sub(self, rhs: Duration) -> Duration {
+        self.checked_sub(rhs)
+            .expect("overflow when subtracting durations")
+    }
+}
+
+/// Panics for `Duration::seconds(i64::MIN)`; see `checked_neg`.
+impl Neg for Duration {
+    type Output = Duration;
+
+    fn neg(self) -> Duration {
+        self.checked_neg()
+            .expect("overflow when negating a duration")
+    }
+}
+
+/// Adds the whole days of `rhs`, rounded down; panics outside the supported
+/// years.
+impl Add<Duration> for Date {
+    type Output = Date;
+
+    fn add(self, rhs: Duration) -> Date {
+        self.add_days(rhs.secs.div_euclid(SECS_PER_DAY))
+    }
+}
+
+/// Subtracts the whole days of `rhs`, rounded up, so that it undoes `+`
+/// for whole days; panics outside the supported years.
+impl Sub<Duration> for Date {
+    type Output = Date;
+
+    fn sub(self, rhs: Duration) -> Date {
+        let days = rhs.secs.div_euclid(SECS_PER_DAY);
+        let partial = rhs.secs.rem_euclid(SECS_PER_DAY) != 0 || rhs.nanos != 0;
+        // `days` is at most `i64::MAX / 86_400` in size, so this cannot
+        // overflow the way negating `rhs` could.
+        self.add_days(-days - partial as i64)
+    }
+}
+
+/// The number of days from `rhs` to `self`.
+impl Sub for Date {
+    type Output = Duration;
+
+    fn sub(self, rhs: Date) -> Duration {
+        Duration::days(self.to_days() - rhs.to_days())
+    }
+}
+
+/// Panics outside the supported years; see `checked_add`.
+impl Add<Duration> for DateTime {
+    type Output = DateTime;
+
+    fn add(self, rhs: Duration) -> DateTime {
+        self.checked_add(rhs).expect("date out of range")
+    }
+}
+
+impl Sub<Duration> for DateTime {
+    type Output = DateTime;
+
+    fn sub(self, rhs: Duration) -> DateTime {
+        self.checked_sub(rhs).expect("date out of range")
+    }
+}
+
+/// The elapsed time from `rhs` to `self`, regardless of their offsets.
+impl Sub for DateTime {
+    type Output = Duration;
+
+    fn sub(self, rhs: DateTime) -> Duration {
+        let secs = self.unix_timestamp() - rhs.unix_timestamp();
+        Duration::new(secs, self.nanos as i64 - rhs.nanos as i64)
+    }
+}
+
+impl PartialEq for DateTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.instant() == other.instant()
+    }
+}
+
+impl Eq for DateTime {}
+
+impl Ord for DateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.instant().cmp(&other.instant())
+    }
+}
+
+impl PartialOrd for DateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for DateTime {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.instant().hash(state);
+    }
+}
+
+/// `YYYY-MM-DD`.
+impl Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// RFC 3339, e.g. `2024-02-29T13:05:00.25+01:00`. The fraction is printed
+/// without trailing zeros and UTC is written `Z`.
+impl Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}T{:02}:{:02}:{:02}",
+            self.date,
+            self.hour(),
+            self.minute(),
+            self.second()
+        )?;
+        if self.nanos > 0 {
+            let fraction = format!("{:09}", self.nanos);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        match self.offset_minutes {
+            0 => write!(f, "Z"),
+            minutes => {
+                let sign = if minutes < 0 { '-' } else { '+' };
+                let minutes = minutes.unsigned_abs();
+                write!(f, "{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+/// A cursor over the bytes of a date or time being parsed.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    index: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, expected: &'static str) -> ParseDateError {
+        ParseDateError::Syntax {
+            index: self.index,
+            expected,
+        }
+    }
+
+    fn number(&mut self, digits: usize, expected: &'static str) -> Result<u32, ParseDateError> {
+        let end = self.index + digits;
+        match self.bytes.get(self.index..end) {
+            Some(slice) if slice.iter().all(u8::is_ascii_digit) => {
+                self.index = end;
+                Ok(slice.iter().fold(0, |n, b| n * 10 + (b - b'0') as u32))
+            }
+            _ => Err(self.error(expected)),
+        }
+    }
+
+    fn eat(&mut self, accepted: &[u8]) -> Option<u8> {
+        let byte = *self.bytes.get(self.index)?;
+        if accepted.contains(&byte) {
+            self.index += 1;
+            Some(byte)
+        } else {
+            None
+        }
+    }
+
+    fn expect(&mut self, accepted: &[u8], expected: &'static str) -> Result<u8, ParseDateError> {
+        self.eat(accepted).ok_or_else(|| self.error(expected))
+    }
+
+    fn date(&mut self) -> Result<Date, ParseDateError> {
+        let year = self.number(4, "a four-digit year")?;
+        self.expect(b"-", "'-'")?;
+        let month = self.number(2, "a two-digit month")?;
+        self.expect(b"-", "'-'")?;
+        let day = self.number(2, "a two-digit day")?;
+        Ok(Date::new(year as i32, month as u8, day as u8)?)
+    }
+
+    fn finish(&self) -> Result<(), ParseDateError> {
+        if self.index == self.bytes.len() {
+            Ok(())
+        } else {
+            Err(self.error("end of input"))
+        }
+    }
+}
+
+/// Parses `YYYY-MM-DD`.
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            bytes: src.as_bytes(),
+            index: 0,
+        };
+        let date = parser.date()?;
+        parser.finish()?;
+        Ok(date)
+    }
+}
+
+/// Parses an RFC 3339 timestamp. `T` and `Z` may be lowercase and the `T`
+/// may be a space, as the RFC allows. A leap second (`:60`) is folded onto
+/// the last nanosecond of the preceding second, so `23:59:60Z` reads as
+/// `23:59:59.999999999Z` and still sorts before the next minute.
+impl FromStr for DateTime {
+    type Err = ParseDateError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            bytes: src.as_bytes(),
+            index: 0,
+        };
+        let date = parser.date()?;
+        parser.expect(b"Tt ", "'T'")?;
+        let hour = parser.number(2, "a two-digit hour")?;
+        parser.expect(b":", "':'")?;
+        let minute = parser.number(2, "two-digit minutes")?;
+        parser.expect(b":", "':'")?;
+        let mut second = parser.number(2, "two-digit seconds")?;
+        let mut nanos = 0;
+        let leap = second == 60;
+        if parser.eat(b".").is_some() {
+            let start = parser.index;
+            let mut scale = NANOS_PER_SEC;
+            while let Ok(digit) = parser.number(1, "a digit") {
+                // Digits past nanosecond precision are truncated.
+                scale /= 10;
+                nanos += digit * scale;
+            }
+            if parser.index == start {
+                return Err(parser.error("a fraction digit"));
+            }
+        }
+        let offset = match parser.expect(b"Zz+-", "'Z' or an offset")? {
+            b'Z' | b'z' => 0,
+            sign => {
+                let hours = parser.number(2, "two-digit offset hours")? as i32;
+                parser.expect(b":", "':'")?;
+                let minutes = parser.number(2, "two-digit offset minutes")? as i32;
+                if minutes > 59 {
+                    return Err(DateError::InvalidOffset(hours * 60 + minutes).into());
+                }
+                let offset = hours * 60 + minutes;
+                if sign == b'-' {
+                    -offset
+                } else {
+                    offset
+                }
+            }
+        };
+        parser.finish()?;
+        if leap {
+            second = 59;
+            nanos = NANOS_PER_SEC - 1;
+        }
+        Ok(DateTime::new(date, hour as u8, minute as u8, second as u8)?
+            .with_nanosecond(nanos)
+            .with_offset(offset)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u8, day: u8) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    fn datetime(src: &str) -> DateTime {
+        src.parse().unwrap()
+    }
+
+    /// The day after `date`, by the calendar rules alone.
+    fn naive_next(current: Date) -> Date {
+        let (y, m, d) = (current.year(), current.month(), current.day());
+        if d < days_in_month(y, m) {
+            date(y, m, d + 1)
+        } else if m < 12 {
+            date(y, m + 1, 1)
+        } else {
+            date(y + 1, 1, 1)
+        }
+    }
+
+    #[test]
+    fn gregorian_cycle() {
+        let start = date(2000, 1, 1);
+        let end = date(2400, 1, 1);
+        assert_eq!((end - start).whole_days(), 146_097);
+        assert_eq!((2000..2400).filter(|&y| is_leap_year(y)).count(), 97);
+        // 2000-01-01 was a Saturday, and the cycle is a whole number of weeks.
+        assert_eq!(start.weekday(), Weekday::Saturday);
+        assert_eq!(end.weekday(), Weekday::Saturday);
+
+        let mut current = start;
+        let mut previous_week = current.iso_week();
+        for days in start.to_days()..end.to_days() {
+            assert_eq!(current.to_days(), days);
+            assert_eq!(Date::from_days(days), Some(current));
+            assert_eq!(current.to_string().parse::<Date>(), Ok(current));
+            let next = naive_next(current);
+            assert_eq!(current.add_days(1), next);
+            assert_eq!(next - current, Duration::days(1));
+            assert_eq!(
+                next.weekday().number_from_monday(),
+                current.weekday().number_from_monday() % 7 + 1
+            );
+
+            // ISO weeks advance exactly on Mondays and week 1 holds Jan 4.
+            let week = next.iso_week();
+            if next.weekday() == Weekday::Monday {
+                let expected = if week.1 == 1 {
+                    (previous_week.0 + 1, 1)
+                } else {
+                    (previous_week.0, previous_week.1 + 1)
+                };
+                assert_eq!(week, expected, "{next}");
+            } else {
+                assert_eq!(week, previous_week, "{next}");
+            }
+            if next.month() == 1 && next.day() == 4 {
+                assert_eq!(week, (next.year(), 1));
+            }
+            previous_week = week;
+
+            // Month arithmetic clamps to the target month's length.
+            let later = current.add_months(1);
+            let (y, m) = if current.month() == 12 {
+                (current.year() + 1, 1)
+            } else {
+                (current.year(), current.month() + 1)
+            };
+            assert_eq!(later, date(y, m, current.day().min(days_in_month(y, m))));
+            assert_eq!(current.add_months(12), current.add_years(1));
+            assert_eq!(
+                current.add_months(-24).add_months(24).month(),
+                current.month()
+            );
+            current = next;
+        }
+        assert_eq!(current, end);
+    }
+
+    #[test]
+    fn calendar_rules() {
+        assert!(is_leap_year(2000) && is_leap_year(2024));
+        assert!(!is_leap_year(1900) && !is_leap_year(2023));
+        assert_eq!(date(2024, 1, 31).add_months(1), date(2024, 2, 29));
+        assert_eq!(date(2023, 1, 31).add_months(1), date(2023, 2, 28));
+        assert_eq!(date(2024, 3, 31).add_months(-1), date(2024, 2, 29));
+        assert_eq!(date(2024, 2, 29).add_years(1), date(2025, 2, 28));
+        assert_eq!(date(2024, 12, 31).ordinal(), 366);
+        assert_eq!(
+            Date::new(2023, 2, 29),
+            Err(DateError::InvalidDay {
+                year: 2023,
+                month: 2,
+                day: 29
+            })
+        );
+        assert_eq!(Date::new(2023, 13, 1), Err(DateError::InvalidMonth(13)));
+        assert_eq!(Date::new(10_000, 1, 1), Err(DateError::OutOfRange));
+        assert_eq!(date(9999, 12, 31).checked_add_days(1), None);
+        assert_eq!(date(0, 1, 1).checked_add_months(-1), None);
+        assert_eq!(Date::from_days(0), Some(date(1970, 1, 1)));
+        assert_eq!(Date::from_days(i64::MAX), None);
+        assert_eq!(Date::from_days(i64::MIN), None);
+        assert_eq!(date(1970, 1, 1).checked_add_days(i64::MAX - 100), None);
+        assert_eq!(date(1970, 1, 1).checked_add_days(i64::MIN), None);
+        let epoch = datetime("1970-01-01T00:00:00Z");
+        assert_eq!(epoch.checked_sub(Duration::seconds(i64::MIN)), None);
+        assert_eq!(epoch.checked_sub(Duration::new(i64::MIN, 1)), None);
+    }
+
+    #[test]
+    fn iso_weeks() {
+        let cases = [
+            (date(2005, 1, 1), (2004, 53)),
+            (date(2007, 12, 31), (2008, 1)),
+            (date(2008, 12, 29), (2009, 1)),
+            (date(2010, 1, 3), (2009, 53)),
+            (date(2020, 12, 31), (2020, 53)),
+            (date(2021, 1, 4), (2021, 1)),
+            (date(0, 1, 1), (-1, 52)),
+            (date(9999, 12, 31), (9999, 52)),
+        ];
+        for (date, week) in cases {
+            assert_eq!(date.iso_week(), week, "{date}");
+        }
+    }
+
+    #[test]
+    fn durations() {
+        let d = Duration::new(5, -1);
+        assert_eq!((d.whole_seconds(), d.nanos), (4, 999_999_999));
+        assert_eq!(
+            -Duration::milliseconds(1500),
+            Duration::new(-2, 500_000_000)
+        );
+        assert_eq!((-Duration::milliseconds(1500)).whole_seconds(), -1);
+        assert_eq!(Duration::hours(25).whole_days(), 1);
+        assert_eq!(Duration::hours(-25).whole_days(), -1);
+        assert_eq!(Duration::days(1) - Duration::hours(1), Duration::hours(23));
+        assert_eq!(Duration::milliseconds(250).as_seconds_f64(), 0.25);
+        assert!(Duration::nanoseconds(-1).is_negative());
+        assert_eq!(Duration::seconds(i64::MIN).checked_neg(), None);
+        assert_eq!(
+            Duration::new(i64::MIN, 1).checked_neg(),
+            Some(Duration::new(i64::MAX, 999_999_999))
+        );
+        assert_eq!(
+            Duration::seconds(-1).checked_sub(Duration::seconds(i64::MIN)),
+            Some(Duration::seconds(i64::MAX))
+        );
+        assert_eq!(
+            Duration::seconds(0).checked_sub(Duration::seconds(i64::MIN)),
+            None
+        );
+        assert_eq!(
+            Duration::new(1, 0) - Duration::new(0, 250_000_000),
+            Duration::new(0, 750_000_000)
+        );
+        assert_eq!(
+            date(2024, 3, 1) - Duration::new(86_400, 1),
+            date(2024, 2, 28)
+        );
+        assert_eq!(Duration::checked_days(i64::MAX / 1000), None);
+        assert_eq!(Duration::checked_hours(i64::MIN), None);
+        assert_eq!(Duration::checked_minutes(2), Some(Duration::seconds(120)));
+        assert_eq!(
+            Duration::milliseconds(i64::MAX),
+            Duration::new(i64::MAX / 1000, 807_000_000)
+        );
+        assert_eq!(date(2024, 3, 1) - date(2024, 2, 1), Duration::days(29));
+        assert_eq!(date(2024, 3, 1) + Duration::hours(47), date(2024, 3, 2));
+        assert_eq!(date(2024, 3, 1) - Duration::hours(1), date(2024, 2, 29));
+    }
+
+    #[test]
+    fn datetimes() {
+        let t = datetime("2024-02-28T23:30:00Z");
+        assert_eq!((t + Duration::hours(1)).to_string(), "2024-02-29T00:30:00Z");
+        assert_eq!(
+            (t - Duration::days(365)).to_string(),
+            "2023-02-28T23:30:00Z"
+        );
+        assert_eq!(
+            datetime("2024-01-01T12:00:00Z"),
+            datetime("2024-01-01T13:00:00+01:00")
+        );
+        assert!(datetime("2024-01-01T12:00:00Z") < datetime("2024-01-01T12:00:00-01:00"));
+        assert_eq!(
+            datetime("2024-01-01T00:00:00.5Z") - datetime("2023-12-31T23:00:00-01:00"),
+            Duration::milliseconds(500)
+        );
+        let shifted = t.to_offset(-8 * 60).unwrap();
+        assert_eq!(shifted.to_string(), "2024-02-28T15:30:00-08:00");
+        assert_eq!(shifted, t);
+        assert_eq!(t.unix_timestamp(), 1_709_163_000);
+        assert_eq!(DateTime::from_unix_timestamp(1_709_163_000, 0), Some(t));
+        assert_eq!(
+            DateTime::from_unix_timestamp(-1, 0).unwrap().to_string(),
+            "1969-12-31T23:59:59Z"
+        );
+        assert_eq!((t.hour(), t.minute(), t.second()), (23, 30, 0));
+        assert_eq!(
+            datetime("9999-12-31T23:59:59Z").checked_add(Duration::seconds(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn rfc3339() {
+        let cases = [
+            ("1985-04-12T23:20:50.52Z", "1985-04-12T23:20:50.52Z"),
+            ("1996-12-19T16:39:57-08:00", "1996-12-19T16:39:57-08:00"),
+            (
+                "1937-01-01t12:00:27.87+00:20",
+                "1937-01-01T12:00:27.87+00:20",
+            ),
+            ("2024-02-29 00:00:00z", "2024-02-29T00:00:00Z"),
+            ("2024-02-29T00:00:00+00:00", "2024-02-29T00:00:00Z"),
+            (
+                "2024-02-29T00:00:00.000000001Z",
+                "2024-02-29T00:00:00.000000001Z",
+            ),
+            (
+                "2024-02-29T00:00:00.1234567899Z",
+                "2024-02-29T00:00:00.123456789Z",
+            ),
+            ("1990-12-31T23:59:60Z", "1990-12-31T23:59:59.999999999Z"),
+            (
+                "1990-12-31T15:59:60.5-08:00",
+                "1990-12-31T15:59:59.999999999-08:00",
+            ),
+        ];
+        for (src, formatted) in cases {
+            let t = datetime(src);
+            assert_eq!(t.to_string(), formatted);
+            assert_eq!(datetime(formatted).offset_minutes(), t.offset_minutes());
+        }
+        let leap = datetime("1990-12-31T23:59:60Z");
+        assert!(datetime("1990-12-31T23:59:59Z") < leap);
+        assert!(leap < datetime("1991-01-01T00:00:00Z"));
+        assert_eq!(leap, datetime("1990-12-31T15:59:60-08:00"));
+        let errors = [
+            (
+                "2024-2-29T00:00:00Z",
+                ParseDateError::Syntax {
+                    index: 5,
+                    expected: "a two-digit month",
+                },
+            ),
+            (
+                "2024-02-29",
+                ParseDateError::Syntax {
+                    index: 10,
+                    expected: "'T'",
+                },
+            ),
+            (
+                "2024-02-29T00:00:00",
+                ParseDateError::Syntax {
+                    index: 19,
+                    expected: "'Z' or an offset",
+                },
+            ),
+            (
+                "2024-02-29T00:00:00.Z",
+                ParseDateError::Syntax {
+                    index: 20,
+                    expected: "a fraction digit",
+                },
+            ),
+            (
+                "2024-02-29T00:00:00Zx",
+                ParseDateError::Syntax {
+                    index: 20,
+                    expected: "end of input",
+                },
+            ),
+            (
+                "2023-02-29T00:00:00Z",
+                DateError::InvalidDay {
+                    year: 2023,
+                    month: 2,
+                    day: 29,
+                }
+                .into(),
+            ),
+            (
+                "2024-02-29T24:00:00Z",
+                DateError::InvalidTime {
+                    hour: 24,
+                    minute: 0,
+                    second: 0,
+                }
+                .into(),
+            ),
+            (
+                "2024-02-29T23:59:61Z",
+                DateError::InvalidTime {
+                    hour: 23,
+                    minute: 59,
+                    second: 61,
+                }
+                .into(),
+            ),
+            (
+                "2024-02-29T00:00:00+24:00",
+                DateError::InvalidOffset(1440).into(),
+            ),
+        ];
+        for (src, error) in errors {
+            assert_eq!(src.parse::<DateTime>(), Err(error), "{src}");
+        }
+        assert_eq!(
+            "2024-02-29T00:00:00Z".parse::<Date>(),
+            Err(ParseDateError::Syntax {
+                index: 10,
+                expected: "end of input"
+            })
+        );
+    }
+}
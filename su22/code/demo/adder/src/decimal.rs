@@ -0,0 +1,554 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use crate::{add, BigInt, BigUint, Num, Rational};
+
+/// A base-10 fixed-point number: `mantissa * 10^-scale`.
+///
+/// The scale is kept per value, so "12.340" and "12.34" compare equal but
+/// still print the way they were written.
+#[derive(Clone, Debug)]
+pub struct Decimal {
+    mantissa: BigInt,
+    scale: u32,
+}
+
+/// How to pick a result when a value falls between two representable ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Round to nearest, ties to the even neighbour (banker's rounding).
+    HalfEven,
+    /// Round to nearest, ties away from zero.
+    HalfUp,
+    TowardZero,
+    /// Toward negative infinity.
+    Floor,
+    /// Toward positive infinity.
+    Ceiling,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecimalError {
+    PrecisionLoss { value: String, scale: u32 },
+    DivisionByZero,
+}
+
+impl Display for DecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecimalError::PrecisionLoss { value, scale } => {
+                write!(f, "{value} cannot be represented with scale {scale}")
+            }
+            DecimalError::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl Error for DecimalError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseDecimalError {
+    Empty,
+    InvalidDigit { index: usize, found: char },
+}
+
+impl Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseDecimalError::Empty => write!(f, "cannot parse decimal from empty string"),
+            ParseDecimalError::InvalidDigit { index, found } => {
+                write!(f, "invalid digit {found:?} at index {index}")
+            }
+        }
+    }
+}
+
+impl Error for ParseDecimalError {}
+
+fn pow10(exp: u32) -> BigInt {
+    BigInt::from(BigUint::from(10u8).pow(exp))
+}
+
+/// `numer / denom` rounded to an integer according to `mode`.
+fn round_div(numer: &BigInt, denom: &BigInt, mode: RoundingMode) -> BigInt {
+    let (quotient, remainder) = numer.div_rem(denom);
+    if remainder.is_zero() {
+        return quotient;
+    }
+    let negative = numer.is_negative() != denom.is_negative();
+    let half = (remainder.magnitude() + remainder.magnitude()).cmp(denom.magnitude());
+    let away = match mode {
+        RoundingMode::HalfEven => {
+            half == Ordering::Greater
+                || (half == Ordering::Equal && !quotient.magnitude().is_even())
+        }
+        RoundingMode::HalfUp => half != Ordering::Less,
+        RoundingMode::TowardZero => false,
+        RoundingMode::Floor => negative,
+        RoundingMode::Ceiling => !negative,
+    };
+    match (away, negative) {
+        (false, _) => quotient,
+        (true, false) => add(quotient, BigInt::one()),
+        (true, true) => add(quotient, -BigInt::one()),
+    }
+}
+
+impl Decimal {
+    /// Fractional digits kept by the `/` operator for non-terminating results.
+    pub const DIV_SCALE: u32 = 28;
+
+    pub fn new(mantissa: impl Into<BigInt>, scale: u32) -> Self {
+        Decimal {
+            mantissa: mantissa.into(),
+            scale,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Decimal::new(0, 0)
+    }
+
+    pub fn one() -> Self {
+        Decimal::new(1, 0)
+    }
+
+    pub fn mantissa(&self) -> &BigInt {
+        &self.mantissa
+    }
+
+    /// Number of digits after the decimal point.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa.is_negative()
+    }
+
+    pub fn abs(&self) -> Self {
+        Decimal::new(self.mantissa.abs(), self.scale)
+    }
+
+    /// Changes the scale without rounding, failing if digits would be dropped.
+    pub fn rescale(&self, scale: u32) -> Result<Self, DecimalError> {
+        let rounded = self.round(scale, RoundingMode::TowardZero);
+        if rounded == *self {
+            Ok(rounded)
+        } else {
+            Err(DecimalError::PrecisionLoss {
+                value: self.to_string(),
+                scale,
+            })
+        }
+    }
+
+    pub fn round(&self, scale: u32, mode: RoundingMode) -> Self {
+        if scale >= self.scale {
+            let factor = pow10(scale - self.scale);
+            return Decimal::new(&self.mantissa * &factor, scale);
+        }
+        let divisor = pow10(self.scale - scale);
+        Decimal::new(round_div(&self.mantissa, &divisor, mode), scale)
+    }
+
+    /// Drops trailing fractional zeros, keeping at least `min_scale` digits.
+    pub fn normalize(&self, min_scale: u32) -> Self {
+        let ten = BigInt::from(10);
+        let mut result = self.clone();
+        while result.scale > min_scale {
+            let (quotient, remainder) = result.mantissa.div_rem(&ten);
+            if !remainder.is_zero() {
+                break;
+            }
+            result = Decimal::new(quotient, result.scale - 1);
+        }
+        result
+    }
+
+    /// Divides and rounds the quotient to `scale` fractional digits.
+    pub fn div_rounded(
+        &self,
+        rhs: &Decimal,
+        scale: u32,
+        mode: RoundingMode,
+    ) -> Result<Self, DecimalError> {
+        if rhs.is_zero() {
+            return Err(DecimalError::DivisionByZero);
+        }
+        // self / rhs * 10^scale == self.m * 10^(scale + rhs.s - self.s) / rhs.m
+        let exp = scale as i64 + rhs.scale as i64 - self.scale as i64;
+        let (numer, denom) = if exp >= 0 {
+            (&self.mantissa * &pow10(exp as u32), rhs.mantissa.clone())
+        } else {
+            (
+                self.mantissa.clone(),
+                &rhs.mantissa * &pow10(exp.unsigned_abs() as u32),
+            )
+        };
+        Ok(Decimal::new(round_div(&numer, &denom, mode), scale))
+    }
+
+    pub fn checked_div(&self, rhs: &Decimal) -> Result<Self, DecimalError> {
+        let min_scale = self.scale.max(rhs.scale);
+        let quotient = self.div_rounded(
+            rhs,
+            min_scale.max(Decimal::DIV_SCALE),
+            RoundingMode::HalfEven,
+        )?;
+        Ok(quotient.normalize(min_scale))
+    }
+
+    /// Both mantissas brought to the larger of the two scales.
+    fn aligned(&self, rhs: &Decimal) -> (BigInt, BigInt, u32) {
+        let scale = self.scale.max(rhs.scale);
+        let left = self.round(scale, RoundingMode::TowardZero).mantissa;
+        let right = rhs.round(scale, RoundingMode::TowardZero).mantissa;
+        (left, right, scale)
+    }
+}
+
+forward_binop!(Decimal, Add, add, |a, b| {
+    let (left, right, scale) = a.aligned(b);
+    Decimal::new(add(left, right), scale)
+});
+forward_binop!(Decimal, Sub, sub, |a, b| {
+    let (left, right, scale) = a.aligned(b);
+    Decimal::new(add(left, -right), scale)
+});
+forward_binop!(Decimal, Mul, mul, |a, b| Decimal::new(
+    &a.mantissa * &b.mantissa,
+    a.scale + b.scale
+));
+forward_binop!(Decimal, Div, div, |a, b| a
+    .checked_div(b)
+    .expect("attempt to divide by zero"));
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        Decimal::new(-self.mantissa, self.scale)
+    }
+}
+
+impl Neg for &Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        -self.clone()
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (left, right, _) = self.aligned(other);
+        left.cmp(&right)
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normal = self.normalize(0);
+        normal.mantissa.hash(state);
+        normal.scale.hash(state);
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty)*) => {
+        $(
+            impl From<$t> for Decimal {
+                fn from(value: $t) -> Self {
+                    Decimal::new(value, 0)
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize BigInt);
+
+impl From<Decimal> for Rational {
+    fn from(value: Decimal) -> Self {
+        Rational::new(value.mantissa, pow10(value.scale))
+    }
+}
+
+/// Prints every digit of the scale, e.g. `12.340`. A formatter precision
+/// (`{:.2}`) rounds half-even to that many digits first.
+impl Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match f.precision() {
+            Some(precision) => self.round(precision as u32, RoundingMode::HalfEven),
+            None => self.clone(),
+        };
+        let digits = value.mantissa.magnitude().to_string();
+        let scale = value.scale as usize;
+        let text = if scale == 0 {
+            digits
+        } else {
+            let digits = format!("{digits:0>width$}", width = scale + 1);
+            let (whole, fraction) = digits.split_at(digits.len() - scale);
+            format!("{whole}.{fraction}")
+        };
+        f.pad_integral(!value.is_negative(), "", &text)
+    }
+}
+
+/// Parses `[+-]digits[.digits]`; the scale is the number of digits after
+/// the point, so "12.340" keeps scale 3.
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let (negative, unsigned) = match src.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, src.strip_prefix('+').unwrap_or(src)),
+        };
+        let offset = src.len() - unsigned.len();
+        if unsigned.is_empty() {
+            return Err(ParseDecimalError::Empty);
+        }
+        let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let point = offset + whole.len();
+        if whole.is_empty() || (fraction.is_empty() && whole.len() < unsigned.len()) {
+            return Err(ParseDecimalError::InvalidDigit {
+                index: point,
+                found: '.',
+            });
+        }
+
+        let mut mantissa = BigInt::zero();
+        let ten = BigInt::from(10);
+        let chars = whole
+            .char_indices()
+            .map(|(i, c)| (offset + i, c))
+            .chain(fraction.char_indices().map(|(i, c)| (point + 1 + i, c)));
+        for (index, found) in chars {
+            let digit = found
+                .to_digit(10)
+                .ok_or(ParseDecimalError::InvalidDigit { index, found })?;
+            mantissa = add(&mantissa * &ten, BigInt::from(digit));
+        }
+        let mantissa = if negative { -mantissa } else { mantissa };
+        Ok(Decimal::new(mantissa, fraction.len() as u32))
+    }
+}
+
+impl Num for Decimal {
+    fn zero() -> Self {
+        Decimal::zero()
+    }
+
+    fn one() -> Self {
+        Decimal::one()
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        self + rhs
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        self - rhs
+    }
+
+    fn mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+
+    fn div(self, rhs: Self) -> Self {
+        self / rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(src: &str) -> Decimal {
+        src.parse().unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        for src in [
+            "12.340",
+            "0",
+            "0.000",
+            "-0.005",
+            "1000000000000000000000000.01",
+            "7",
+        ] {
+            assert_eq!(d(src).to_string(), src);
+        }
+        assert_eq!(d("+1.50").to_string(), "1.50");
+        assert_eq!(d("-0.00").to_string(), "0.00");
+        assert_eq!(d("12.340").scale(), 3);
+        assert_eq!(format!("{:>8}", d("-1.5")), "    -1.5");
+        assert_eq!(format!("{:.2}", d("2.675")), "2.68");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("".parse::<Decimal>(), Err(ParseDecimalError::Empty));
+        assert_eq!("-".parse::<Decimal>(), Err(ParseDecimalError::Empty));
+        assert_eq!(
+            "1.2.3".parse::<Decimal>(),
+            Err(ParseDecimalError::InvalidDigit {
+                index: 3,
+                found: '.'
+            })
+        );
+        assert_eq!(
+            "1.".parse::<Decimal>(),
+            Err(ParseDecimalError::InvalidDigit {
+                index: 1,
+                found: '.'
+            })
+        );
+        assert_eq!(
+            "-.5".parse::<Decimal>(),
+            Err(ParseDecimalError::InvalidDigit {
+                index: 1,
+                found: '.'
+            })
+        );
+        assert_eq!(
+            "1e5".parse::<Decimal>(),
+            Err(ParseDecimalError::InvalidDigit {
+                index: 1,
+                found: 'e'
+            })
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(d("0.1") + d("0.2"), d("0.3"));
+        assert_eq!(add(d("12.34"), d("0.006")).to_string(), "12.346");
+        assert_eq!((d("1.50") - d("2")).to_string(), "-0.50");
+        assert_eq!((d("1.5") * d("-0.25")).to_string(), "-0.375");
+        assert_eq!((d("1.00") / d("4")).to_string(), "0.25");
+        assert_eq!((d("10") / d("4")).to_string(), "2.5");
+        assert_eq!(
+            (d("1") / d("3")).to_string(),
+            format!("0.{}", "3".repeat(Decimal::DIV_SCALE as usize))
+        );
+        assert_eq!(
+            d("1").checked_div(&d("0.00")),
+            Err(DecimalError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn rescale() {
+        assert_eq!(d("12.34").rescale(4).unwrap().to_string(), "12.3400");
+        assert_eq!(d("12.340").rescale(2).unwrap().to_string(), "12.34");
+        assert_eq!(
+            d("12.345").rescale(2),
+            Err(DecimalError::PrecisionLoss {
+                value: "12.345".to_string(),
+                scale: 2
+            })
+        );
+        assert_eq!(
+            d("12.345").rescale(2).unwrap_err().to_string(),
+            "12.345 cannot be represented with scale 2"
+        );
+    }
+
+    #[test]
+    fn rounding_modes() {
+        use RoundingMode::*;
+        let cases = [
+            // value, half-even, half-up, toward-zero, floor, ceiling
+            ("2.5", ["2", "3", "2", "2", "3"]),
+            ("3.5", ["4", "4", "3", "3", "4"]),
+            ("-2.5", ["-2", "-3", "-2", "-3", "-2"]),
+            ("2.4", ["2", "2", "2", "2", "3"]),
+            ("2.6", ["3", "3", "2", "2", "3"]),
+            ("-2.6", ["-3", "-3", "-2", "-3", "-2"]),
+            ("-0.1", ["0", "0", "0", "-1", "0"]),
+            ("7", ["7", "7", "7", "7", "7"]),
+        ];
+        for (value, expected) in cases {
+            for (mode, expected) in [HalfEven, HalfUp, TowardZero, Floor, Ceiling]
+                .iter()
+                .zip(expected)
+            {
+                assert_eq!(d(value).round(0, *mode), d(expected), "{value} {mode:?}");
+            }
+        }
+        assert_eq!(d("1.005").round(2, HalfEven).to_string(), "1.00");
+        assert_eq!(d("1.015").round(2, HalfEven).to_string(), "1.02");
+    }
+
+    #[test]
+    fn division_rounding() {
+        let (one, two, three) = (d("1"), d("2"), d("3"));
+        let third = |mode| one.div_rounded(&three, 4, mode).unwrap().to_string();
+        assert_eq!(third(RoundingMode::HalfEven), "0.3333");
+        assert_eq!(third(RoundingMode::Ceiling), "0.3334");
+        assert_eq!(
+            two.div_rounded(&three, 4, RoundingMode::HalfUp)
+                .unwrap()
+                .to_string(),
+            "0.6667"
+        );
+        assert_eq!(
+            (-one)
+                .div_rounded(&three, 4, RoundingMode::Floor)
+                .unwrap()
+                .to_string(),
+            "-0.3334"
+        );
+        assert_eq!(
+            d("1234.5")
+                .div_rounded(&d("0.01"), 0, RoundingMode::HalfEven)
+                .unwrap()
+                .to_string(),
+            "123450"
+        );
+        assert_eq!(
+            d("5")
+                .div_rounded(&d("1000"), 2, RoundingMode::HalfEven)
+                .unwrap()
+                .to_string(),
+            "0.00"
+        );
+    }
+
+    #[test]
+    fn equality_ignores_scale() {
+        use std::collections::HashSet;
+
+        assert_eq!(d("12.340"), d("12.34"));
+        assert!(d("-1.1") < d("-1.09"));
+        let set: HashSet<Decimal> = [d("1.0"), d("1.00"), d("1")].into_iter().collect();
+        assert_eq!(set.len(), 1);
+        assert_eq!(Rational::from(d("0.25")), Rational::new(1, 4));
+    }
+}
@@ -0,0 +1,668 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::add;
+
+/// A byte range `start..end` in the source text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+/// The built-in functions a formula can call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Func {
+    Min,
+    Max,
+    Abs,
+    Sqrt,
+    Sin,
+    Cos,
+    Exp,
+    Ln,
+}
+
+/// A parsed formula.
+///
+/// Precedence from loosest to tightest is `+ -`, `* /`, unary `-`, then a
+/// right-associative `^`, so `-2^2` is `-(2^2)`. `Display` prints the tree
+/// back with only the parentheses it needs.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Var(String),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseExprError {
+    kind: ParseExprErrorKind,
+    span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseExprErrorKind {
+    UnexpectedChar(char),
+    InvalidNumber,
+    Expected(&'static str),
+    UnknownFunction(String),
+    Arity { func: Func, found: usize },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EvalError {
+    UnknownVariable(String),
+}
+
+impl ParseExprError {
+    pub fn kind(&self) -> &ParseExprErrorKind {
+        &self.kind
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// The source line with carets under the offending characters.
+    pub fn excerpt(&self, src: &str) -> String {
+        let column = src[..self.span.start].chars().count();
+        let width = src[self.span.start..self.span.end].chars().count().max(1);
+        format!("{src}\n{}{}", " ".repeat(column), "^".repeat(width))
+    }
+}
+
+impl Display for ParseExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseExprErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {c:?}")?,
+            ParseExprErrorKind::InvalidNumber => write!(f, "invalid number")?,
+            ParseExprErrorKind::Expected(what) => write!(f, "expected {what}")?,
+            ParseExprErrorKind::UnknownFunction(name) => write!(f, "unknown function `{name}`")?,
+            ParseExprErrorKind::Arity { func, found } => {
+                write!(f, "`{}` takes {}, found {found}", func.name(), func.arity())?
+            }
+        }
+        write!(f, " at offset {}", self.span.start)
+    }
+}
+
+impl Error for ParseExprError {}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::UnknownVariable(name) => write!(f, "unknown variable `{name}`"),
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+impl BinOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Pow => "^",
+        }
+    }
+
+    pub fn apply(self, a: f64, b: f64) -> f64 {
+        match self {
+            BinOp::Add => add(a, b),
+            BinOp::Sub => a - b,
+            BinOp::Mul => a * b,
+            BinOp::Div => a / b,
+            BinOp::Pow => a.powf(b),
+        }
+    }
+
+    /// Left and right binding power; `^` binds tighter on the left so it
+    /// groups to the right.
+    fn binding_power(self) -> (u8, u8) {
+        match self {
+            BinOp::Add | BinOp::Sub => (1, 2),
+            BinOp::Mul | BinOp::Div => (3, 4),
+            BinOp::Pow => (6, 5),
+        }
+    }
+
+    fn precedence(self) -> u8 {
+        match self {
+            BinOp::Add | BinOp::Sub => 1,
+            BinOp::Mul | BinOp::Div => 2,
+            BinOp::Pow => 4,
+        }
+    }
+}
+
+/// Binding power of a prefix `-`: below `^`, above `*`.
+const PREFIX_BP: u8 = 5;
+const NEG_PRECEDENCE: u8 = 3;
+const ATOM_PRECEDENCE: u8 = 5;
+
+impl Func {
+    const ALL: [Func; 8] = [
+        Func::Min,
+        Func::Max,
+        Func::Abs,
+        Func::Sqrt,
+        Func::Sin,
+        Func::Cos,
+        Func::Exp,
+        Func::Ln,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Func::Min => "min",
+            Func::Max => "max",
+            Func::Abs => "abs",
+            Func::Sqrt => "sqrt",
+            Func::Sin => "sin",
+            Func::Cos => "cos",
+            Func::Exp => "exp",
+            Func::Ln => "ln",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Func::ALL.into_iter().find(|func| func.name() == name)
+    }
+
+    fn arity(self) -> &'static str {
+        match self {
+            Func::Min | Func::Max => "at least 1 argument",
+            _ => "1 argument",
+        }
+    }
+
+    fn accepts(self, count: usize) -> bool {
+        match self {
+            Func::Min | Func::Max => count >= 1,
+            _ => count == 1,
+        }
+    }
+
+    /// Applies the function; `args` has already passed the arity check.
+    pub fn apply(self, args: &[f64]) -> f64 {
+        match self {
+            Func::Min => args.iter().copied().fold(f64::INFINITY, f64::min),
+            Func::Max => args.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Func::Abs => args[0].abs(),
+            Func::Sqrt => args[0].sqrt(),
+            Func::Sin => args[0].sin(),
+            Func::Cos => args[0].cos(),
+            Func::Exp => args[0].exp(),
+            Func::Ln => args[0].ln(),
+        }
+    }
+}
+
+impl Expr {
+    pub fn parse(src: &str) -> Result<Self, ParseExprError> {
+        let mut parser = Parser {
+            tokens: tokenize(src)?,
+            pos: 0,
+        };
+        let expr = parser.expr(0)?;
+        parser.expect(TokenKind::End, "operator or end of input")?;
+        Ok(expr)
+    }
+
+    /// Evaluates with IEEE semantics, so `1 / 0` is infinite and
+    /// `sqrt(-1)` is NaN; only an unbound variable is an error.
+    pub fn eval(&self, vars: &HashMap<String, f64>) -> Result<f64, EvalError> {
+        Ok(match self {
+            Expr::Number(value) => *value,
+            Expr::Var(name) => *vars
+                .get(name)
+                .ok_or_else(|| EvalError::UnknownVariable(name.clone()))?,
+            Expr::Neg(operand) => -operand.eval(vars)?,
+            Expr::Binary(op, a, b) => op.apply(a.eval(vars)?, b.eval(vars)?),
+            Expr::Call(func, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.eval(vars))
+                    .collect::<Result<Vec<_>, _>>()?;
+                func.apply(&args)
+            }
+        })
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Number(value) if value.is_sign_negative() => NEG_PRECEDENCE,
+            Expr::Neg(_) => NEG_PRECEDENCE,
+            Expr::Binary(op, ..) => op.precedence(),
+            Expr::Number(_) | Expr::Var(_) | Expr::Call(..) => ATOM_PRECEDENCE,
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ParseExprError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Expr::parse(src)
+    }
+}
+
+/// Writes `expr`, parenthesized when it binds looser than `needed`.
+fn write_operand(f: &mut fmt::Formatter<'_>, expr: &Expr, needed: u8) -> fmt::Result {
+    if expr.precedence() < needed {
+        write!(f, "({expr})")
+    } else {
+        write!(f, "{expr}")
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Number(value) => write!(f, "{value}"),
+            Expr::Var(name) => f.write_str(name),
+            Expr::Neg(operand) => {
+                f.write_str("-")?;
+                write_operand(f, operand, NEG_PRECEDENCE)
+            }
+            Expr::Binary(op, a, b) => {
+                let precedence = op.precedence();
+                // Equal precedence needs parentheses on the side the
+                // operator does not group towards.
+                let (left, right) = match op {
+                    BinOp::Pow => (precedence + 1, precedence),
+                    _ => (precedence, precedence + 1),
+                };
+                write_operand(f, a, left)?;
+                match op {
+                    BinOp::Pow => f.write_str("^")?,
+                    _ => write!(f, " {} ", op.symbol())?,
+                }
+                write_operand(f, b, right)
+            }
+            Expr::Call(func, args) => {
+                write!(f, "{}(", func.name())?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{arg}")?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+    Number(f64),
+    Ident(String),
+    Op(BinOp),
+    LParen,
+    RParen,
+    Comma,
+    End,
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: TokenKind,
+    span: Span,
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, ParseExprError> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let start = pos;
+        let byte = bytes[pos];
+        let kind = match byte {
+            b' ' | b'\t' | b'\r' | b'\n' => {
+                pos += 1;
+                continue;
+            }
+            b'0'..=b'9' | b'.' => {
+                pos = scan_number(bytes, pos);
+                let text = &src[start..pos];
+                let value = text.parse().map_err(|_| ParseExprError {
+                    kind: ParseExprErrorKind::InvalidNumber,
+                    span: Span::new(start, pos),
+                })?;
+                TokenKind::Number(value)
+            }
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
+                while pos < bytes.len()
+                    && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_')
+                {
+                    pos += 1;
+                }
+                TokenKind::Ident(src[start..pos].to_string())
+            }
+            _ => {
+                let kind = match byte {
+                    b'+' => TokenKind::Op(BinOp::Add),
+                    b'-' => TokenKind::Op(BinOp::Sub),
+                    b'*' => TokenKind::Op(BinOp::Mul),
+                    b'/' => TokenKind::Op(BinOp::Div),
+                    b'^' => TokenKind::Op(BinOp::Pow),
+                    b'(' => TokenKind::LParen,
+                    b')' => TokenKind::RParen,
+                    b',' => TokenKind::Comma,
+                    _ => {
+                        let c = src[start..].chars().next().unwrap();
+                        return Err(ParseExprError {
+                            kind: ParseExprErrorKind::UnexpectedChar(c),
+                            span: Span::new(start, start + c.len_utf8()),
+                        });
+                    }
+                };
+                pos += 1;
+                kind
+            }
+        };
+        tokens.push(Token {
+            kind,
+            span: Span::new(start, pos),
+        });
+    }
+    tokens.push(Token {
+        kind: TokenKind::End,
+        span: Span::new(src.len(), src.len()),
+    });
+    Ok(tokens)
+}
+
+/// Scans `digits[.digits][e[+-]digits]` and returns the end; a malformed
+/// exponent is left for `parse` to reject.
+fn scan_number(bytes: &[u8], mut pos: usize) -> usize {
+    let digits = |pos: &mut usize| {
+        while *pos < bytes.len() && bytes[*pos].is_ascii_digit() {
+            *pos += 1;
+        }
+    };
+    digits(&mut pos);
+    if pos < bytes.len() && bytes[pos] == b'.' {
+        pos += 1;
+        digits(&mut pos);
+    }
+    if pos < bytes.len() && matches!(bytes[pos], b'e' | b'E') {
+        pos += 1;
+        if pos < bytes.len() && matches!(bytes[pos], b'+' | b'-') {
+            pos += 1;
+        }
+        digits(&mut pos);
+    }
+    pos
+}
+
+/// A Pratt parser over the token list, which always ends in `End`.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if token.kind != TokenKind::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, kind: TokenKind, expected: &'static str) -> Result<Span, ParseExprError> {
+        let token = self.next();
+        if token.kind == kind {
+            Ok(token.span)
+        } else {
+            Err(ParseExprError {
+                kind: ParseExprErrorKind::Expected(expected),
+                span: token.span,
+            })
+        }
+    }
+
+    fn expr(&mut self, min_bp: u8) -> Result<Expr, ParseExprError> {
+        let mut lhs = self.prefix()?;
+        while let TokenKind::Op(op) = self.peek().kind {
+            let (left_bp, right_bp) = op.binding_power();
+            if left_bp < min_bp {
+                break;
+            }
+            self.next();
+            let rhs = self.expr(right_bp)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn prefix(&mut self) -> Result<Expr, ParseExprError> {
+        let token = self.next();
+        match token.kind {
+            TokenKind::Number(value) => Ok(Expr::Number(value)),
+            TokenKind::Op(BinOp::Sub) => Ok(Expr::Neg(Box::new(self.expr(PREFIX_BP)?))),
+            TokenKind::LParen => {
+                let inner = self.expr(0)?;
+                self.expect(TokenKind::RParen, "`)`")?;
+                Ok(inner)
+            }
+            TokenKind::Ident(name) if self.peek().kind == TokenKind::LParen => {
+                self.call(name, token.span)
+            }
+            TokenKind::Ident(name) => Ok(Expr::Var(name)),
+            _ => Err(ParseExprError {
+                kind: ParseExprErrorKind::Expected("expression"),
+                span: token.span,
+            }),
+        }
+    }
+
+    fn call(&mut self, name: String, name_span: Span) -> Result<Expr, ParseExprError> {
+        let func = Func::from_name(&name).ok_or(ParseExprError {
+            kind: ParseExprErrorKind::UnknownFunction(name),
+            span: name_span,
+        })?;
+        self.next();
+        let mut args = Vec::new();
+        if self.peek().kind != TokenKind::RParen {
+            loop {
+                args.push(self.expr(0)?);
+                if self.peek().kind != TokenKind::Comma {
+                    break;
+                }
+                self.next();
+            }
+        }
+        let close = self.expect(TokenKind::RParen, "`,` or `)`")?;
+        if !func.accepts(args.len()) {
+            return Err(ParseExprError {
+                kind: ParseExprErrorKind::Arity {
+                    func,
+                    found: args.len(),
+                },
+                span: Span::new(name_span.start, close.end),
+            });
+        }
+        Ok(Expr::Call(func, args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+
+    fn eval(src: &str) -> f64 {
+        Expr::parse(src).unwrap().eval(&HashMap::new()).unwrap()
+    }
+
+    fn error(src: &str) -> (ParseExprErrorKind, Span) {
+        let error = Expr::parse(src).unwrap_err();
+        (error.kind().clone(), error.span())
+    }
+
+    #[test]
+    fn precedence_and_grouping() {
+        assert_eq!(eval("(2 + 3) * 4 - 10 / 5"), 18.0);
+        assert_eq!(eval("2 + 3 * 4"), 14.0);
+        assert_eq!(eval("10 - 4 - 3"), 3.0);
+        assert_eq!(eval("64 / 4 / 2"), 8.0);
+        assert_eq!(eval("2 ^ 3 ^ 2"), 512.0);
+        assert_eq!(eval("-2 ^ 2"), -4.0);
+        assert_eq!(eval("(-2) ^ 2"), 4.0);
+        assert_eq!(eval("2 ^ -1"), 0.5);
+        assert_eq!(eval("--3 * -2"), -6.0);
+        assert_eq!(eval("1.5e3 + .5 + 2E-1"), 1500.7);
+        assert_eq!(eval(" ( ( 7 ) ) "), 7.0);
+    }
+
+    #[test]
+    fn variables_and_calls() {
+        let vars = HashMap::from([("x".to_string(), 3.0), ("rate_2".to_string(), 0.5)]);
+        let expr = Expr::parse("max(x, 1, -x) * rate_2 + min(4, sqrt(16)) - abs(-x)").unwrap();
+        assert_eq!(expr.eval(&vars), Ok(2.5));
+        assert_eq!(
+            Expr::parse("x + y").unwrap().eval(&vars),
+            Err(EvalError::UnknownVariable("y".into()))
+        );
+        assert_eq!(eval("ln(exp(2)) + cos(0) - sin(0)"), 3.0);
+        assert!(eval("sqrt(-1)").is_nan());
+        assert_eq!(eval("1 / 0"), f64::INFINITY);
+        // A function name without parentheses is an ordinary variable.
+        assert_eq!(Expr::parse("min").unwrap(), Expr::Var("min".into()));
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        use ParseExprErrorKind::*;
+
+        assert_eq!(error("2 + $"), (UnexpectedChar('$'), Span::new(4, 5)));
+        assert_eq!(error("2 + é"), (UnexpectedChar('é'), Span::new(4, 6)));
+        assert_eq!(error("1e+ 2"), (InvalidNumber, Span::new(0, 3)));
+        assert_eq!(error("(1 + 2"), (Expected("`)`"), Span::new(6, 6)));
+        assert_eq!(error("1 + * 2"), (Expected("expression"), Span::new(4, 5)));
+        assert_eq!(
+            error("2 3"),
+            (Expected("operator or end of input"), Span::new(2, 3))
+        );
+        assert_eq!(error(""), (Expected("expression"), Span::new(0, 0)));
+        assert_eq!(
+            error("1 + foo(2)"),
+            (UnknownFunction("foo".into()), Span::new(4, 7))
+        );
+        assert_eq!(
+            error("abs(1, 2)"),
+            (
+                Arity {
+                    func: Func::Abs,
+                    found: 2
+                },
+                Span::new(0, 9)
+            )
+        );
+        assert_eq!(
+            error("max()").0,
+            Arity {
+                func: Func::Max,
+                found: 0
+            }
+        );
+        assert_eq!(error("min(1 2)"), (Expected("`,` or `)`"), Span::new(6, 7)));
+
+        let error = Expr::parse("(1 + 2) * ").unwrap_err();
+        assert_eq!(error.to_string(), "expected expression at offset 10");
+        assert_eq!(error.excerpt("(1 + 2) * "), "(1 + 2) * \n          ^");
+        let error = Expr::parse("abs(1, 2)").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`abs` takes 1 argument, found 2 at offset 0"
+        );
+        assert_eq!(error.excerpt("abs(1, 2)"), "abs(1, 2)\n^^^^^^^^^");
+    }
+
+    #[test]
+    fn display_uses_minimal_parentheses() {
+        for (src, shown) in [
+            ("(2 + 3) * 4 - 10 / 5", "(2 + 3) * 4 - 10 / 5"),
+            ("((a + b)) + (c + d)", "a + b + (c + d)"),
+            ("a - (b - c)", "a - (b - c)"),
+            ("(a / b) / c", "a / b / c"),
+            ("(a ^ b) ^ c", "(a^b)^c"),
+            ("a ^ (b ^ c)", "a^b^c"),
+            ("-(a ^ 2)", "-a^2"),
+            ("(-a) ^ 2", "(-a)^2"),
+            ("-(a * b)", "-(a * b)"),
+            ("max(a, -(b + 1))", "max(a, -(b + 1))"),
+        ] {
+            assert_eq!(Expr::parse(src).unwrap().to_string(), shown, "{src}");
+        }
+        let folded = Expr::Binary(
+            BinOp::Pow,
+            Box::new(Expr::Number(-2.0)),
+            Box::new(Expr::Number(0.5)),
+        );
+        assert_eq!(folded.to_string(), "(-2)^0.5");
+    }
+
+    fn random_expr(rng: &mut Rng, depth: u32) -> Expr {
+        let leaf = depth == 0 || rng.range(0, 3) == 0;
+        match rng.range(0, 4) {
+            _ if leaf && rng.range(0, 1) == 0 => Expr::Number(rng.range(0, 99) as f64 / 4.0),
+            _ if leaf => Expr::Var(["x", "y", "z"][rng.range(0, 2) as usize].into()),
+            0 => Expr::Neg(Box::new(random_expr(rng, depth - 1))),
+            1 => {
+                let func = Func::ALL[rng.range(0, 7) as usize];
+                let count = if func.accepts(2) { rng.range(1, 3) } else { 1 };
+                let args = (0..count).map(|_| random_expr(rng, depth - 1)).collect();
+                Expr::Call(func, args)
+            }
+            _ => {
+                let ops = [BinOp::Add, BinOp::Sub, BinOp::Mul, BinOp::Div, BinOp::Pow];
+                Expr::Binary(
+                    ops[rng.range(0, 4) as usize],
+                    Box::new(random_expr(rng, depth - 1)),
+                    Box::new(random_expr(rng, depth - 1)),
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let mut rng = Rng(18);
+        for _ in 0..2000 {
+            let expr = random_expr(&mut rng, 5);
+            let shown = expr.to_string();
+            assert_eq!(Expr::parse(&shown), Ok(expr), "{shown}");
+        }
+    }
+}
@@ -0,0 +1,441 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::{add, Float, Num};
+
+/// A closed interval `[lo, hi]` that is guaranteed to contain the exact
+/// result of every operation that produced it.
+///
+/// Each bound is computed in round-to-nearest and then moved one unit in the
+/// last place outwards, which covers the at most half-ulp rounding error.
+/// The bounds may be infinite but never NaN, and an interval is never a
+/// single infinity: `[∞, ∞]` would make `∞ - ∞` a bound.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval<T = f64> {
+    lo: T,
+    hi: T,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntervalError {
+    /// A bound was NaN, the lower bound exceeded the upper one, or both were
+    /// the same infinity.
+    InvalidBounds,
+    /// The divisor contains zero, so the quotient is unbounded.
+    DivisionByZero,
+}
+
+impl Display for IntervalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntervalError::InvalidBounds => {
+                write!(f, "interval bounds are NaN, out of order or one infinity")
+            }
+            IntervalError::DivisionByZero => write!(f, "division by an interval containing zero"),
+        }
+    }
+}
+
+impl Error for IntervalError {}
+
+impl<T: Float> Interval<T> {
+    pub fn new(lo: T, hi: T) -> Result<Self, IntervalError> {
+        if lo < hi || (lo == hi && lo.is_finite()) {
+            Ok(Interval { lo, hi })
+        } else {
+            Err(IntervalError::InvalidBounds)
+        }
+    }
+
+    /// The degenerate interval `[value, value]`; panics if `value` is NaN or
+    /// infinite.
+    pub fn point(value: T) -> Self {
+        Interval::new(value, value).expect("interval bound is NaN or infinite")
+    }
+
+    /// `[center - radius, center + radius]`, e.g. a measurement and its
+    /// tolerance; panics if either is NaN or `radius` is negative.
+    pub fn around(center: T, radius: T) -> Self {
+        assert!(radius >= T::zero(), "radius must be non-negative");
+        Interval::new(
+            Num::sub(center, radius).next_down(),
+            add(center, radius).next_up(),
+        )
+        .expect("interval bound is NaN")
+    }
+
+    pub fn lo(&self) -> T {
+        self.lo
+    }
+
+    pub fn hi(&self) -> T {
+        self.hi
+    }
+
+    /// An upper bound on `hi - lo`.
+    pub fn width(&self) -> T {
+        Num::sub(self.hi, self.lo).next_up()
+    }
+
+    /// Zero for the whole line, where halving both bounds gives `-∞ + ∞`.
+    pub fn midpoint(&self) -> T {
+        let mid = self.lo / T::from_f64(2.0) + self.hi / T::from_f64(2.0);
+        if mid.is_nan() {
+            T::zero()
+        } else {
+            mid
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    /// Whether `other` lies entirely inside `self`.
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        self.lo <= other.lo && other.hi <= self.hi
+    }
+
+    pub fn intersects(&self, other: &Interval<T>) -> bool {
+        self.lo <= other.hi && other.lo <= self.hi
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Self> {
+        Interval::new(max(self.lo, other.lo), min(self.hi, other.hi)).ok()
+    }
+
+    /// The smallest interval containing both.
+    pub fn hull(&self, other: &Interval<T>) -> Self {
+        Interval {
+            lo: min(self.lo, other.lo),
+            hi: max(self.hi, other.hi),
+        }
+    }
+
+    pub fn checked_div(self, rhs: Interval<T>) -> Result<Self, IntervalError> {
+        if rhs.contains(T::zero()) {
+            return Err(IntervalError::DivisionByZero);
+        }
+        Ok(Interval::outward([
+            self.lo / rhs.lo,
+            self.lo / rhs.hi,
+            self.hi / rhs.lo,
+            self.hi / rhs.hi,
+        ]))
+    }
+
+    /// The rounded-outward hull of four candidate bounds. A NaN candidate
+    /// comes from `∞ / ∞` at one corner of a quotient and is skipped: the
+    /// two corners beside it already reach zero and infinity.
+    fn outward(candidates: [T; 4]) -> Self {
+        let candidates = candidates.into_iter().filter(|bound| !bound.is_nan());
+        let lo = candidates.clone().reduce(min).unwrap();
+        let hi = candidates.reduce(max).unwrap();
+        Interval {
+            lo: lo.next_down(),
+            hi: hi.next_up(),
+        }
+    }
+}
+
+fn min<T: Float>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<T: Float>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+/// `0 * ∞` is taken as zero: it only arises from a zero bound meeting an
+/// unbounded one, and every finite product there is zero.
+fn product<T: Float>(a: T, b: T) -> T {
+    let p = a * b;
+    if p.is_nan() {
+        T::zero()
+    } else {
+        p
+    }
+}
+
+impl<T: Float> Add for Interval<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Interval {
+            lo: add(self.lo, rhs.lo).next_down(),
+            hi: add(self.hi, rhs.hi).next_up(),
+        }
+    }
+}
+
+impl<T: Float> Sub for Interval<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Interval {
+            lo: (self.lo - rhs.hi).next_down(),
+            hi: (self.hi - rhs.lo).next_up(),
+        }
+    }
+}
+
+impl<T: Float> Mul for Interval<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Interval::outward([
+            product(self.lo, rhs.lo),
+            product(self.lo, rhs.hi),
+            product(self.hi, rhs.lo),
+            product(self.hi, rhs.hi),
+        ])
+    }
+}
+
+/// Panics if `rhs` contains zero; use `checked_div` to get an error instead.
+impl<T: Float> Div for Interval<T> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        match self.checked_div(rhs) {
+            Ok(quotient) => quotient,
+            Err(error) => panic!("{error}"),
+        }
+    }
+}
+
+impl<T: Float> Neg for Interval<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Interval {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+impl<T: Float> From<T> for Interval<T> {
+    fn from(value: T) -> Self {
+        Interval::point(value)
+    }
+}
+
+/// Formats as `[lo, hi]`; a precision applies to both bounds.
+impl<T: Float> Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "[{:.*}, {:.*}]", precision, self.lo, precision, self.hi),
+            None => write!(f, "[{}, {}]", self.lo, self.hi),
+        }
+    }
+}
+
+impl<T: Float> Num for Interval<T> {
+    fn zero() -> Self {
+        Interval::point(T::zero())
+    }
+
+    fn one() -> Self {
+        Interval::point(T::one())
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        self + rhs
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        self - rhs
+    }
+
+    fn mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+
+    fn div(self, rhs: Self) -> Self {
+        self / rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+    use crate::Rational;
+
+    type I = Interval<f64>;
+
+    fn i(lo: f64, hi: f64) -> I {
+        Interval::new(lo, hi).unwrap()
+    }
+
+    fn exact(x: f64) -> Rational {
+        Rational::from_f64(x).unwrap()
+    }
+
+    fn encloses(interval: I, value: &Rational) -> bool {
+        exact(interval.lo()) <= *value && *value <= exact(interval.hi())
+    }
+
+    /// A float with a random sign, up to 53 significant bits and a binary
+    /// exponent in a range that keeps exact rationals small.
+    fn random_float(rng: &mut Rng) -> f64 {
+        let mantissa = (rng.next() >> 11) as f64;
+        let exponent = rng.range(-80, 10) as i32;
+        let value = mantissa * 2f64.powi(exponent);
+        if rng.next().is_multiple_of(2) {
+            value
+        } else {
+            -value
+        }
+    }
+
+    fn random_interval(rng: &mut Rng) -> I {
+        let (a, b) = (random_float(rng), random_float(rng));
+        i(a.min(b), a.max(b))
+    }
+
+    /// A point of `interval`: one of its bounds or a value in between.
+    fn random_member(rng: &mut Rng, interval: I) -> f64 {
+        match rng.next() % 3 {
+            0 => interval.lo(),
+            1 => interval.hi(),
+            _ => interval.midpoint(),
+        }
+    }
+
+    #[test]
+    fn exact_results_are_enclosed() {
+        let mut rng = Rng(14);
+        for _ in 0..2000 {
+            let (a, b) = (random_interval(&mut rng), random_interval(&mut rng));
+            let (x, y) = (random_member(&mut rng, a), random_member(&mut rng, b));
+            let (ex, ey) = (exact(x), exact(y));
+            assert!(encloses(a + b, &(&ex + &ey)), "{x} + {y} not in {}", a + b);
+            assert!(encloses(a - b, &(&ex - &ey)), "{x} - {y} not in {}", a - b);
+            assert!(encloses(a * b, &(&ex * &ey)), "{x} * {y} not in {}", a * b);
+            match a.checked_div(b) {
+                Ok(q) => assert!(encloses(q, &(&ex / &ey)), "{x} / {y} not in {q}"),
+                Err(error) => {
+                    assert_eq!(error, IntervalError::DivisionByZero);
+                    assert!(b.contains(0.0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bounds_are_rounded_outward() {
+        // 0.1 + 0.2 is not representable, so the sum must straddle it.
+        let sum = I::point(0.1) + I::point(0.2);
+        let tenth = Rational::new(1, 10);
+        assert!(encloses(sum, &(&exact(0.1) + &exact(0.2))));
+        assert!(sum.lo() < 0.30000000000000004 && 0.30000000000000004 < sum.hi());
+        assert!(!encloses(I::point(0.1), &tenth));
+        // Summing measured values keeps a guaranteed bound.
+        let total = (0..10).fold(I::zero(), |acc, _| acc + I::around(0.1, 0.01));
+        assert!(total.contains(1.0));
+        assert!(total.width() < 0.21);
+    }
+
+    #[test]
+    fn division_by_zero() {
+        let one = I::one();
+        assert_eq!(
+            one.checked_div(i(-1.0, 1.0)),
+            Err(IntervalError::DivisionByZero)
+        );
+        assert_eq!(
+            one.checked_div(i(0.0, 1.0)),
+            Err(IntervalError::DivisionByZero)
+        );
+        assert!(one.checked_div(i(0.5, 1.0)).unwrap().contains(2.0));
+        assert_eq!(
+            IntervalError::DivisionByZero.to_string(),
+            "division by an interval containing zero"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "division by an interval containing zero")]
+    fn divide_operator_panics() {
+        let _ = I::one() / I::zero();
+    }
+
+    #[test]
+    fn set_operations() {
+        let (a, b) = (i(0.0, 2.0), i(1.0, 3.0));
+        assert_eq!(a.intersection(&b), Some(i(1.0, 2.0)));
+        assert_eq!(a.hull(&b), i(0.0, 3.0));
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&i(2.5, 3.0)));
+        assert_eq!(a.intersection(&i(2.5, 3.0)), None);
+        assert!(a.contains_interval(&i(0.5, 1.5)));
+        assert!(!a.contains_interval(&b));
+        assert!(a.contains(2.0) && !a.contains(2.1));
+        assert_eq!(-a, i(-2.0, 0.0));
+        assert_eq!(I::new(1.0, 0.0), Err(IntervalError::InvalidBounds));
+        assert_eq!(I::new(f64::NAN, 0.0), Err(IntervalError::InvalidBounds));
+    }
+
+    #[test]
+    fn unbounded() {
+        let everything = i(f64::NEG_INFINITY, f64::INFINITY);
+        let product = i(0.0, 1.0) * i(1.0, f64::INFINITY);
+        assert_eq!(product.hi(), f64::INFINITY);
+        assert!(product.contains(0.0));
+        assert!(!product.lo().is_nan());
+        assert_eq!(everything + I::one(), everything);
+        let huge = I::point(f64::MAX) + I::point(f64::MAX);
+        assert_eq!(huge.hi(), f64::INFINITY);
+        assert_eq!(huge - huge, everything);
+        assert_eq!(everything.midpoint(), 0.0);
+        assert_eq!(i(1.0, f64::INFINITY).midpoint(), f64::INFINITY);
+    }
+
+    #[test]
+    fn infinite_points_are_rejected() {
+        for bound in [f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(I::new(bound, bound), Err(IntervalError::InvalidBounds));
+        }
+        assert_eq!(
+            IntervalError::InvalidBounds.to_string(),
+            "interval bounds are NaN, out of order or one infinity"
+        );
+        let up = i(1.0, f64::INFINITY);
+        let down = -up;
+        assert_eq!(up + down, i(f64::NEG_INFINITY, f64::INFINITY));
+        assert_eq!(up - up, i(f64::NEG_INFINITY, f64::INFINITY));
+        let quotient = up.checked_div(up).unwrap();
+        assert_eq!(
+            (quotient.lo(), quotient.hi()),
+            (-0.0f64.next_up(), f64::INFINITY)
+        );
+        let quotient = up.checked_div(down).unwrap();
+        assert_eq!(quotient.lo(), f64::NEG_INFINITY);
+        assert!(quotient.contains(-1.0) && quotient.contains(0.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "interval bound is NaN or infinite")]
+    fn infinite_point_panics() {
+        let _ = I::point(f64::INFINITY);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(i(1.0, 2.5).to_string(), "[1, 2.5]");
+        assert_eq!(format!("{:.2}", i(1.0 / 3.0, 0.5)), "[0.33, 0.50]");
+        let narrow: Interval<f32> = Interval::point(1.5f32) * Interval::point(2.0);
+        assert!(narrow.contains(3.0));
+    }
+}
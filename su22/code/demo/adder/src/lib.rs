@@ -1,14 +1,107 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
+#[macro_use]
+mod macros;
+
+mod big;
+mod complex;
+mod date;
+mod decimal;
+mod expr;
+mod interval;
+mod matrix;
+mod modint;
+mod money;
+mod num;
+mod numerals;
+mod overflow;
+mod parallel;
+mod polynomial;
+mod rational;
+mod simd;
+mod sum;
+mod symbolic;
+mod units;
+
+#[cfg(test)]
+mod test_util;
+
+pub use big::{BigInt, BigUint, ParseBigIntError};
+pub use complex::{Complex, ParseComplexError};
+pub use date::{
+    days_in_month, is_leap_year, Date, DateError, DateTime, Duration, ParseDateError, Weekday,
+    MAX_YEAR, MIN_YEAR,
+};
+pub use decimal::{Decimal, DecimalError, ParseDecimalError, RoundingMode};
+pub use expr::{BinOp, EvalError, Expr, Func, ParseExprError, ParseExprErrorKind, Span};
+pub use interval::{Interval, IntervalError};
+pub use matrix::{Matrix, ShapeError, Vector};
+pub use modint::{ModInt, Montgomery};
+pub use money::{Currency, ExchangeRates, Money, MoneyError, ParseMoneyError};
+pub use num::{Float, Num};
+pub use numerals::{
+    from_roman, from_words, to_roman, to_words, ParseRomanError, ParseWordsError, RomanError,
+    MAX_ROMAN,
+};
+pub use overflow::{
+    add_with, checked_add, panicking_add, saturating_add, wrapping_add, AddError, Int,
+    OverflowPolicy,
+};
+pub use parallel::{par_sum, par_sum_with_threads, PAR_CHUNK_LEN};
+pub use polynomial::Polynomial;
+pub use rational::{ParseRationalError, Rational};
+pub use simd::{
+    add_assign_slices, add_assign_slices_with, add_slices, add_slices_with, Backend, Lane,
+};
+pub use sum::{
+    sum, sum_with, Accumulator, CheckedSum, CompensatedSum, ExactSum, SumMode, Summable,
+};
+pub use symbolic::DiffError;
+pub use units::{
+    Acceleration, Area, Dim, Dimension, DimensionError, Dimensionless, DynQuantity, DynUnit,
+    Length, Mass, ParseQuantityError, Quantity, Time, Unit, Velocity, CENTIMETRE, GRAM, HOUR,
+    KILOGRAM, KILOMETRE, KILOMETRE_PER_HOUR, METRE, METRE_PER_SECOND, MILLIMETRE, MILLISECOND,
+    MINUTE, SECOND, SQUARE_METRE,
+};
+
+pub fn add<T: Num>(left: T, right: T) -> T {
+    left.add(right)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    macro_rules! add_cases {
+        ($($t:ty),*) => {
+            $(
+                for (left, right, expected) in [(0, 0, 0), (2, 2, 4), (1, 0, 1), (0, 1, 1), (40, 2, 42), (100, 27, 127)] {
+                    assert_eq!(add(left as $t, right as $t), expected as $t, "{}", stringify!($t));
+                }
+            )*
+        };
+    }
+
     #[test]
     fn it_works() {
         let result = add(2, 2);
         assert_eq!(result, 4);
+
+        add_cases!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+    }
+
+    #[test]
+    fn signed_and_fractional() {
+        assert_eq!(add(-5i8, 3), -2);
+        assert_eq!(add(i64::MIN, i64::MAX), -1);
+        assert_eq!(add(0.5f32, 0.25), 0.75);
+        assert_eq!(add(-1.5f64, 1.5), 0.0);
+    }
+
+    #[test]
+    fn extremes() {
+        assert_eq!(add(u8::MAX - 1, 1), u8::MAX);
+        assert_eq!(add(u128::MAX, 0), u128::MAX);
+        assert_eq!(add(i128::MIN, 0), i128::MIN);
+        assert!(add(f64::INFINITY, 1.0).is_infinite());
+        assert!(add(f32::NAN, 1.0).is_nan());
     }
 }
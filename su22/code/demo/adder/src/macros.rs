@@ -0,0 +1,38 @@
+/// Implements a binary operator for every owned/borrowed operand combination
+/// in terms of a body that works on two references.
+macro_rules! forward_binop {
+    ($ty:ident, $imp:ident, $method:ident, |$a:ident, $b:ident| $body:expr) => {
+        impl $imp<&$ty> for &$ty {
+            type Output = $ty;
+
+            fn $method(self, rhs: &$ty) -> $ty {
+                let ($a, $b) = (self, rhs);
+                $body
+            }
+        }
+
+        impl $imp<$ty> for $ty {
+            type Output = $ty;
+
+            fn $method(self, rhs: $ty) -> $ty {
+                $imp::$method(&self, &rhs)
+            }
+        }
+
+        impl $imp<&$ty> for $ty {
+            type Output = $ty;
+
+            fn $method(self, rhs: &$ty) -> $ty {
+                $imp::$method(&self, rhs)
+            }
+        }
+
+        impl $imp<$ty> for &$ty {
+            type Output = $ty;
+
+            fn $method(self, rhs: $ty) -> $ty {
+                $imp::$method(self, &rhs)
+            }
+        }
+    };
+}
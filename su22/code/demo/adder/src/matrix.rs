@@ -0,0 +1,523 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+use crate::{add, Num};
+
+/// Side of the square tiles `Matrix` multiplication works through, chosen
+/// so three tiles of `f64` stay well inside a typical L1/L2 cache.
+const BLOCK: usize = 64;
+
+/// A dense row-major matrix.
+///
+/// The arithmetic operators take references and return a `Result`, so
+/// `(&a * &b)?` multiplies and propagates a `ShapeError`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+/// A dense column vector.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Vector<T> {
+    data: Vec<T>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShapeError {
+    /// The operands of `op` have incompatible `(rows, cols)` shapes.
+    Mismatch {
+        op: &'static str,
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+    /// `rows * cols` elements were needed but `len` were given.
+    DataLength {
+        rows: usize,
+        cols: usize,
+        len: usize,
+    },
+    /// Row `row` has `found` elements instead of `expected`.
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::Mismatch { op, left, right } => write!(
+                f,
+                "cannot {op} {}x{} and {}x{}",
+                left.0, left.1, right.0, right.1
+            ),
+            ShapeError::DataLength { rows, cols, len } => {
+                write!(
+                    f,
+                    "a {rows}x{cols} matrix needs {} elements, got {len}",
+                    rows * cols
+                )
+            }
+            ShapeError::Ragged {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {row} has {found} elements, expected {expected}"),
+        }
+    }
+}
+
+impl Error for ShapeError {}
+
+impl<T: Num> Matrix<T> {
+    /// Builds a matrix from row-major `data`.
+    pub fn new(rows: usize, cols: usize, data: Vec<T>) -> Result<Self, ShapeError> {
+        if rows.checked_mul(cols) != Some(data.len()) {
+            return Err(ShapeError::DataLength {
+                rows,
+                cols,
+                len: data.len(),
+            });
+        }
+        Ok(Matrix { rows, cols, data })
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, ShapeError> {
+        let cols = rows.first().map_or(0, Vec::len);
+        let count = rows.len();
+        let mut data = Vec::with_capacity(count * cols);
+        for (row, values) in rows.into_iter().enumerate() {
+            if values.len() != cols {
+                return Err(ShapeError::Ragged {
+                    row,
+                    expected: cols,
+                    found: values.len(),
+                });
+            }
+            data.extend(values);
+        }
+        Ok(Matrix {
+            rows: count,
+            cols,
+            data,
+        })
+    }
+
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let data = (0..rows * cols).map(|i| f(i / cols, i % cols)).collect();
+        Matrix { rows, cols, data }
+    }
+
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Matrix::from_fn(rows, cols, |_, _| T::zero())
+    }
+
+    pub fn identity(n: usize) -> Self {
+        Matrix::from_fn(n, n, |i, j| if i == j { T::one() } else { T::zero() })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// `(rows, cols)`.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.rows && col < self.cols {
+            self.data.get(row * self.cols + col)
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn transpose(&self) -> Self {
+        Matrix::from_fn(self.cols, self.rows, |i, j| self[(j, i)].clone())
+    }
+
+    pub fn scale(&self, factor: &T) -> Self {
+        let data = self
+            .data
+            .iter()
+            .map(|x| x.clone().mul(factor.clone()))
+            .collect();
+        Matrix { data, ..*self }
+    }
+
+    pub fn mul_vector(&self, vector: &Vector<T>) -> Result<Vector<T>, ShapeError> {
+        if self.cols != vector.len() {
+            return Err(ShapeError::Mismatch {
+                op: "multiply",
+                left: self.shape(),
+                right: (vector.len(), 1),
+            });
+        }
+        let data = (0..self.rows)
+            .map(|i| dot(self.row(i), &vector.data))
+            .collect();
+        Ok(Vector { data })
+    }
+
+    fn zip_with(
+        &self,
+        rhs: &Matrix<T>,
+        op: &'static str,
+        f: impl Fn(T, T) -> T,
+    ) -> Result<Self, ShapeError> {
+        if self.shape() != rhs.shape() {
+            return Err(ShapeError::Mismatch {
+                op,
+                left: self.shape(),
+                right: rhs.shape(),
+            });
+        }
+        let data = self
+            .data
+            .iter()
+            .zip(&rhs.data)
+            .map(|(a, b)| f(a.clone(), b.clone()))
+            .collect();
+        Ok(Matrix { data, ..*self })
+    }
+
+    /// Multiplies tile by tile so each `BLOCK`-sized piece of both operands
+    /// is reused from cache, walking rows of `rhs` in the innermost loop.
+    fn mul_blocked(&self, rhs: &Matrix<T>) -> Matrix<T> {
+        let (n, m, p) = (self.rows, self.cols, rhs.cols);
+        let mut out: Matrix<T> = Matrix::zeros(n, p);
+        for i0 in (0..n).step_by(BLOCK) {
+            for k0 in (0..m).step_by(BLOCK) {
+                for j0 in (0..p).step_by(BLOCK) {
+                    for i in i0..(i0 + BLOCK).min(n) {
+                        for k in k0..(k0 + BLOCK).min(m) {
+                            let a = &self.data[i * m + k];
+                            let (row, out_row) = (rhs.row(k), &mut out.data[i * p..(i + 1) * p]);
+                            for j in j0..(j0 + BLOCK).min(p) {
+                                let product = a.clone().mul(row[j].clone());
+                                out_row[j] = add(out_row[j].clone(), product);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn dot<T: Num>(a: &[T], b: &[T]) -> T {
+    a.iter().zip(b).fold(T::zero(), |total, (x, y)| {
+        add(total, x.clone().mul(y.clone()))
+    })
+}
+
+impl<T: Num> Vector<T> {
+    pub fn new(data: Vec<T>) -> Self {
+        Vector { data }
+    }
+
+    pub fn zeros(len: usize) -> Self {
+        Vector::new(vec![T::zero(); len])
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn dot(&self, rhs: &Vector<T>) -> Result<T, ShapeError> {
+        self.check(rhs, "dot")?;
+        Ok(dot(&self.data, &rhs.data))
+    }
+
+    pub fn scale(&self, factor: &T) -> Self {
+        Vector::new(
+            self.data
+                .iter()
+                .map(|x| x.clone().mul(factor.clone()))
+                .collect(),
+        )
+    }
+
+    fn check(&self, rhs: &Vector<T>, op: &'static str) -> Result<(), ShapeError> {
+        if self.len() == rhs.len() {
+            Ok(())
+        } else {
+            Err(ShapeError::Mismatch {
+                op,
+                left: (self.len(), 1),
+                right: (rhs.len(), 1),
+            })
+        }
+    }
+}
+
+impl<T: Num> Add for &Matrix<T> {
+    type Output = Result<Matrix<T>, ShapeError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, "add", add)
+    }
+}
+
+impl<T: Num> Sub for &Matrix<T> {
+    type Output = Result<Matrix<T>, ShapeError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, "subtract", T::sub)
+    }
+}
+
+impl<T: Num> Mul for &Matrix<T> {
+    type Output = Result<Matrix<T>, ShapeError>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.cols != rhs.rows {
+            return Err(ShapeError::Mismatch {
+                op: "multiply",
+                left: self.shape(),
+                right: rhs.shape(),
+            });
+        }
+        Ok(self.mul_blocked(rhs))
+    }
+}
+
+impl<T: Num> Mul<&Vector<T>> for &Matrix<T> {
+    type Output = Result<Vector<T>, ShapeError>;
+
+    fn mul(self, rhs: &Vector<T>) -> Self::Output {
+        self.mul_vector(rhs)
+    }
+}
+
+impl<T: Num> Add for &Vector<T> {
+    type Output = Result<Vector<T>, ShapeError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.check(rhs, "add")?;
+        let data = self.data.iter().zip(&rhs.data);
+        Ok(Vector::new(
+            data.map(|(a, b)| add(a.clone(), b.clone())).collect(),
+        ))
+    }
+}
+
+impl<T: Num> Sub for &Vector<T> {
+    type Output = Result<Vector<T>, ShapeError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.check(rhs, "subtract")?;
+        let data = self.data.iter().zip(&rhs.data);
+        Ok(Vector::new(
+            data.map(|(a, b)| a.clone().sub(b.clone())).collect(),
+        ))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "index ({row}, {col}) out of bounds for {}x{} matrix",
+            self.rows,
+            self.cols
+        );
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "index ({row}, {col}) out of bounds for {}x{} matrix",
+            self.rows,
+            self.cols
+        );
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+impl<T> Index<usize> for Vector<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.data[index]
+    }
+}
+
+impl<T> IndexMut<usize> for Vector<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.data[index]
+    }
+}
+
+impl<T> From<Vec<T>> for Vector<T> {
+    fn from(data: Vec<T>) -> Self {
+        Vector { data }
+    }
+}
+
+/// One row per line, elements separated by spaces; a precision is passed
+/// through to every element.
+impl<T: Display> Display for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.data.chunks(self.cols.max(1)).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for (j, value) in row.iter().enumerate() {
+                if j > 0 {
+                    write!(f, " ")?;
+                }
+                Display::fmt(value, f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+
+    fn m(rows: Vec<Vec<i64>>) -> Matrix<i64> {
+        Matrix::from_rows(rows).unwrap()
+    }
+
+    fn naive_mul(a: &Matrix<i64>, b: &Matrix<i64>) -> Matrix<i64> {
+        Matrix::from_fn(a.rows(), b.cols(), |i, j| {
+            (0..a.cols()).map(|k| a[(i, k)] * b[(k, j)]).sum()
+        })
+    }
+
+    fn random(rng: &mut Rng, rows: usize, cols: usize) -> Matrix<i64> {
+        Matrix::from_fn(rows, cols, |_, _| rng.range(-100, 100))
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = m(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let b = m(vec![vec![7, 8], vec![9, 10], vec![11, 12]]);
+        assert_eq!((&a * &b).unwrap(), m(vec![vec![58, 64], vec![139, 154]]));
+        assert_eq!((&a + &a).unwrap(), a.scale(&2));
+        assert_eq!((&a - &a).unwrap(), Matrix::zeros(2, 3));
+        assert_eq!(a.transpose(), m(vec![vec![1, 4], vec![2, 5], vec![3, 6]]));
+        assert_eq!((&Matrix::identity(2) * &a).unwrap(), a);
+        assert_eq!((&a * &Matrix::identity(3)).unwrap(), a);
+
+        let v = Vector::new(vec![1, 0, -1]);
+        assert_eq!((&a * &v).unwrap(), Vector::new(vec![-2, -2]));
+        assert_eq!(v.dot(&v), Ok(2));
+        assert_eq!((&v + &v).unwrap(), v.scale(&2));
+        assert_eq!((&v - &v).unwrap(), Vector::zeros(3));
+    }
+
+    #[test]
+    fn shape_errors() {
+        let a = Matrix::<f64>::zeros(2, 3);
+        let b = Matrix::<f64>::zeros(2, 2);
+        let error = ShapeError::Mismatch {
+            op: "multiply",
+            left: (2, 3),
+            right: (2, 2),
+        };
+        assert_eq!(&a * &b, Err(error.clone()));
+        assert_eq!(error.to_string(), "cannot multiply 2x3 and 2x2");
+        assert!((&a + &b).is_err());
+        assert!((&a - &b).is_err());
+        assert!(a.mul_vector(&Vector::zeros(2)).is_err());
+        assert!(Vector::<f64>::zeros(2).dot(&Vector::zeros(3)).is_err());
+        assert_eq!(
+            Matrix::new(2, 2, vec![1.0; 3]),
+            Err(ShapeError::DataLength {
+                rows: 2,
+                cols: 2,
+                len: 3
+            })
+        );
+        assert_eq!(
+            Matrix::from_rows(vec![vec![1], vec![2, 3]]),
+            Err(ShapeError::Ragged {
+                row: 1,
+                expected: 1,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn blocked_matches_naive() {
+        let mut rng = Rng(12);
+        // Sizes straddle the tile boundary so partial tiles are covered.
+        for (n, k, p) in [
+            (1, 1, 1),
+            (3, 5, 2),
+            (64, 64, 64),
+            (65, 63, 130),
+            (130, 1, 70),
+        ] {
+            let a = random(&mut rng, n, k);
+            let b = random(&mut rng, k, p);
+            assert_eq!((&a * &b).unwrap(), naive_mul(&a, &b), "{n}x{k} * {k}x{p}");
+        }
+    }
+
+    #[test]
+    fn algebra() {
+        let mut rng = Rng(13);
+        let (a, b, c) = (
+            random(&mut rng, 7, 9),
+            random(&mut rng, 9, 4),
+            random(&mut rng, 4, 5),
+        );
+        assert_eq!((&(&a * &b).unwrap() * &c), (&a * &(&b * &c).unwrap()));
+        assert_eq!(
+            (&a * &b).unwrap().transpose(),
+            (&b.transpose() * &a.transpose()).unwrap()
+        );
+        let d = random(&mut rng, 9, 4);
+        assert_eq!(
+            (&a * &(&b + &d).unwrap()),
+            (&(&a * &b).unwrap() + &(&a * &d).unwrap())
+        );
+    }
+
+    #[test]
+    fn floats_and_display() {
+        let rotation = Matrix::from_rows(vec![vec![0.0, -1.0], vec![1.0, 0.0]]).unwrap();
+        let v = Vector::new(vec![1.0, 2.0]);
+        assert_eq!(
+            rotation.mul_vector(&v).unwrap(),
+            Vector::new(vec![-2.0, 1.0])
+        );
+        assert_eq!(format!("{:.1}", rotation), "0.0 -1.0\n1.0 0.0");
+        assert_eq!(rotation.get(1, 0), Some(&1.0));
+        assert_eq!(rotation.get(2, 0), None);
+    }
+}
@@ -0,0 +1,420 @@
+use std::fmt::{self, Debug, Display};
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::Num;
+
+/// An integer modulo `M`, always stored reduced into `0..M`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModInt<const M: u64>(u64);
+
+/// A residue modulo an odd `M` in Montgomery form (`x * 2^64 mod M`).
+///
+/// Multiplying in this form replaces the 128-by-64-bit division behind
+/// `ModInt`'s `*` with two multiplications, which pays off for long chains
+/// of products such as `pow`. Convert in with `from` and back with `get`;
+/// converting with an even `M` fails to compile:
+///
+/// ```compile_fail
+/// use adder::{ModInt, Montgomery};
+///
+/// let _ = Montgomery::from(ModInt::<10>::new(3));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Montgomery<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    const VALID: () = assert!(M > 0, "modulus must be positive");
+
+    pub fn new(value: u64) -> Self {
+        let () = Self::VALID;
+        ModInt(value % M)
+    }
+
+    pub fn from_i64(value: i64) -> Self {
+        let () = Self::VALID;
+        ModInt((value as i128).rem_euclid(M as i128) as u64)
+    }
+
+    /// The representative in `0..M`.
+    pub fn get(self) -> u64 {
+        self.0
+    }
+
+    pub const fn modulus() -> u64 {
+        M
+    }
+
+    pub fn pow(self, mut exp: u64) -> Self {
+        if M % 2 == 1 {
+            return Montgomery::convert(self).pow(exp).get();
+        }
+        let (mut base, mut result) = (self, ModInt::new(1));
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// The multiplicative inverse via the extended Euclidean algorithm, or
+    /// `None` when the value shares a factor with `M`.
+    pub fn inverse(self) -> Option<Self> {
+        let (mut r0, mut r1) = (M as i128, self.0 as i128);
+        let (mut t0, mut t1) = (0i128, 1i128);
+        while r1 != 0 {
+            let q = r0 / r1;
+            (r0, r1) = (r1, r0 - q * r1);
+            (t0, t1) = (t1, t0 - q * t1);
+        }
+        if r0 != 1 {
+            return None;
+        }
+        Some(ModInt::new(t0.rem_euclid(M as i128) as u64))
+    }
+}
+
+/// Zero, with the same compile-time check on `M` as `new`.
+impl<const M: u64> Default for ModInt<M> {
+    fn default() -> Self {
+        let () = Self::VALID;
+        ModInt(0)
+    }
+}
+
+impl<const M: u64> Montgomery<M> {
+    const ODD: () = assert!(M % 2 == 1, "Montgomery form needs an odd modulus");
+
+    /// `M^-1 mod 2^64` by Newton's iteration; each step doubles the
+    /// number of correct low bits, starting from 3 (`M * M = 1 mod 8`).
+    const M_INV: u64 = {
+        let mut inv = M;
+        let mut i = 0;
+        while i < 5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(M.wrapping_mul(inv)));
+            i += 1;
+        }
+        inv
+    };
+
+    /// `2^128 mod M`, which maps a plain residue into Montgomery form.
+    const R2: u64 = {
+        let r = ((1u128 << 64) % M as u128) as u64;
+        ((r as u128 * r as u128) % M as u128) as u64
+    };
+
+    /// Montgomery reduction: `t * 2^-64 mod M` for any `t < M * 2^64`.
+    fn reduce(t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(Self::M_INV);
+        let mm = m as u128 * M as u128;
+        // The low halves of t and mm are equal, so only the high halves
+        // need subtracting.
+        let (r, borrow) = ((t >> 64) as u64).overflowing_sub((mm >> 64) as u64);
+        if borrow {
+            r.wrapping_add(M)
+        } else {
+            r
+        }
+    }
+
+    /// `from` without the odd-modulus check, so that `ModInt::pow` can
+    /// still compile for even moduli, where it never calls this.
+    fn convert(value: ModInt<M>) -> Self {
+        Montgomery(Self::reduce(value.0 as u128 * Self::R2 as u128))
+    }
+
+    pub fn get(self) -> ModInt<M> {
+        ModInt(Self::reduce(self.0 as u128))
+    }
+
+    pub fn pow(self, mut exp: u64) -> Self {
+        let (mut base, mut result) = (self, Montgomery::convert(ModInt::new(1)));
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            exp >>= 1;
+        }
+        result
+    }
+}
+
+impl<const M: u64> From<ModInt<M>> for Montgomery<M> {
+    fn from(value: ModInt<M>) -> Self {
+        let () = Self::ODD;
+        Montgomery::convert(value)
+    }
+}
+
+impl<const M: u64> Mul for Montgomery<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Montgomery(Self::reduce(self.0 as u128 * rhs.0 as u128))
+    }
+}
+
+/// Montgomery form is linear, so addition works on it directly.
+impl<const M: u64> Add for Montgomery<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Montgomery((ModInt::<M>(self.0) + ModInt(rhs.0)).0)
+    }
+}
+
+impl<const M: u64> Sub for Montgomery<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Montgomery((ModInt::<M>(self.0) - ModInt(rhs.0)).0)
+    }
+}
+
+impl<const M: u64> Debug for Montgomery<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} (Montgomery)", self.get())
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        // Written to avoid overflowing u64 when M is close to 2^64.
+        if self.0 >= M - rhs.0 {
+            ModInt(self.0 - (M - rhs.0))
+        } else {
+            ModInt(self.0 + rhs.0)
+        }
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        if self.0 >= rhs.0 {
+            ModInt(self.0 - rhs.0)
+        } else {
+            ModInt(M - (rhs.0 - self.0))
+        }
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        ModInt((self.0 as u128 * rhs.0 as u128 % M as u128) as u64)
+    }
+}
+
+/// Multiplies by the inverse of `rhs`; panics if `rhs` is not invertible.
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        match rhs.inverse() {
+            Some(inverse) => Mul::mul(self, inverse),
+            None => panic!("{rhs:?} has no inverse"),
+        }
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        ModInt(0) - self
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> Self {
+        ModInt::new(value)
+    }
+}
+
+impl<const M: u64> Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl<const M: u64> Debug for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (mod {M})", self.0)
+    }
+}
+
+impl<const M: u64> Num for ModInt<M> {
+    fn zero() -> Self {
+        ModInt::new(0)
+    }
+
+    fn one() -> Self {
+        ModInt::new(1)
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        self + rhs
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        self - rhs
+    }
+
+    fn mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+
+    fn div(self, rhs: Self) -> Self {
+        self / rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::add;
+    use crate::test_util::Rng;
+
+    fn gcd(a: u64, b: u64) -> u64 {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+
+    /// Every operation against plain integer arithmetic for every pair of
+    /// residues.
+    fn exhaustive<const M: u64>() {
+        for a in 0..M {
+            let x = ModInt::<M>::new(a);
+            for b in 0..M {
+                let y = ModInt::<M>::new(b);
+                assert_eq!((x + y).get(), (a + b) % M);
+                assert_eq!((x - y).get(), (a + M - b) % M);
+                assert_eq!((x * y).get(), a * b % M);
+                assert_eq!(add(x, y), x + y);
+                // `from` would not compile for the even moduli here.
+                if M % 2 == 1 {
+                    let product = Montgomery::convert(x) * Montgomery::convert(y);
+                    assert_eq!(product.get(), x * y);
+                    let sum = Montgomery::convert(x) + Montgomery::convert(y);
+                    assert_eq!(sum.get(), x + y);
+                }
+            }
+            match x.inverse() {
+                Some(inverse) => {
+                    assert_eq!(gcd(a, M), 1, "{x:?}");
+                    assert_eq!(x * inverse, ModInt::new(1));
+                }
+                None => assert_ne!(gcd(a, M), 1, "{x:?}"),
+            }
+            let mut power = ModInt::new(1);
+            for exp in 0..2 * M {
+                assert_eq!(x.pow(exp), power, "{x:?}^{exp}");
+                power *= x;
+            }
+        }
+    }
+
+    #[test]
+    fn small_moduli() {
+        exhaustive::<1>();
+        exhaustive::<2>();
+        exhaustive::<3>();
+        exhaustive::<7>();
+        exhaustive::<8>();
+        exhaustive::<12>();
+        exhaustive::<13>();
+        exhaustive::<16>();
+        exhaustive::<97>();
+        exhaustive::<100>();
+    }
+
+    /// Random operands against u128 arithmetic, plus Fermat's little theorem
+    /// where `M` is prime.
+    fn randomized<const M: u64>(prime: bool) {
+        let mut rng = Rng(M);
+        for _ in 0..2000 {
+            let (a, b) = (rng.next() % M, rng.next() % M);
+            let (x, y) = (ModInt::<M>::new(a), ModInt::<M>::new(b));
+            assert_eq!((x + y).get() as u128, (a as u128 + b as u128) % M as u128);
+            assert_eq!((x - y + y), x);
+            assert_eq!((x * y).get() as u128, a as u128 * b as u128 % M as u128);
+            if M % 2 == 1 {
+                assert_eq!(
+                    (Montgomery::convert(x) * Montgomery::convert(y)).get(),
+                    x * y
+                );
+            }
+            if let Some(inverse) = x.inverse() {
+                assert_eq!(x * inverse, ModInt::new(1));
+                assert_eq!(y / x * x, y);
+            }
+            if prime && a != 0 {
+                assert_eq!(x.pow(M - 1), ModInt::new(1), "{x:?}");
+            }
+            let e = rng.next() % 64;
+            assert_eq!(x.pow(e + 1), x.pow(e) * x);
+        }
+    }
+
+    #[test]
+    fn large_moduli() {
+        randomized::<998_244_353>(true);
+        randomized::<1_000_000_007>(true);
+        randomized::<{ (1 << 61) - 1 }>(true);
+        randomized::<18_446_744_073_709_551_557>(true);
+        randomized::<{ u64::MAX }>(false);
+        randomized::<{ 1 << 63 }>(false);
+    }
+
+    #[test]
+    fn signed_and_display() {
+        type M7 = ModInt<7>;
+        assert_eq!(M7::from_i64(-1).get(), 6);
+        assert_eq!(
+            M7::from_i64(i64::MIN),
+            M7::new((i64::MIN as i128).rem_euclid(7) as u64)
+        );
+        assert_eq!(-M7::new(3), M7::new(4));
+        assert_eq!(M7::new(3).to_string(), "3");
+        assert_eq!(format!("{:?}", M7::new(10)), "3 (mod 7)");
+        assert_eq!(M7::modulus(), 7);
+        assert_eq!(M7::default(), M7::new(0));
+        assert_eq!(Montgomery::from(M7::new(3)).get(), M7::new(3));
+        assert_eq!(ModInt::<4>::new(3).pow(2), ModInt::new(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "2 (mod 4) has no inverse")]
+    fn divide_by_non_unit() {
+        let _ = ModInt::<4>::new(1) / ModInt::new(2);
+    }
+}
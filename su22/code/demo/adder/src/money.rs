@@ -0,0 +1,518 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+
+use crate::{add, BigInt, Decimal, DecimalError, ParseDecimalError, RoundingMode};
+
+/// An ISO 4217 currency: a three-letter code and the number of digits in
+/// its minor unit (2 for cents, 0 for yen).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Currency {
+    code: [u8; 3],
+    minor_units: u8,
+}
+
+/// An amount of one currency.
+///
+/// Arithmetic between two `Money` values only succeeds when the currencies
+/// match; the operators take references and return a `Result`, so
+/// `(&a + &b)?` adds and propagates a `MoneyError`. To combine currencies,
+/// convert through an `ExchangeRates` table.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Money {
+    amount: Decimal,
+    currency: Currency,
+}
+
+/// Conversion rates between pairs of currencies.
+#[derive(Clone, Debug, Default)]
+pub struct ExchangeRates {
+    rates: HashMap<(Currency, Currency), Decimal>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MoneyError {
+    CurrencyMismatch {
+        left: Currency,
+        right: Currency,
+    },
+    MissingRate {
+        from: Currency,
+        to: Currency,
+    },
+    /// Allocation needs at least one ratio and a non-zero total.
+    InvalidRatios,
+    Decimal(DecimalError),
+}
+
+impl Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoneyError::CurrencyMismatch { left, right } => {
+                write!(f, "cannot combine {left} with {right}")
+            }
+            MoneyError::MissingRate { from, to } => {
+                write!(f, "no exchange rate from {from} to {to}")
+            }
+            MoneyError::InvalidRatios => write!(f, "ratios must not all be zero"),
+            MoneyError::Decimal(error) => Display::fmt(error, f),
+        }
+    }
+}
+
+impl Error for MoneyError {}
+
+impl From<DecimalError> for MoneyError {
+    fn from(error: DecimalError) -> Self {
+        MoneyError::Decimal(error)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseMoneyError {
+    MissingCurrency,
+    UnknownCurrency(String),
+    Amount(ParseDecimalError),
+}
+
+impl Display for ParseMoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseMoneyError::MissingCurrency => write!(f, "expected a currency code and an amount"),
+            ParseMoneyError::UnknownCurrency(code) => write!(f, "unknown currency {code:?}"),
+            ParseMoneyError::Amount(error) => write!(f, "invalid amount: {error}"),
+        }
+    }
+}
+
+impl Error for ParseMoneyError {}
+
+impl Currency {
+    pub const USD: Currency = Currency::known(*b"USD", 2);
+    pub const EUR: Currency = Currency::known(*b"EUR", 2);
+    pub const GBP: Currency = Currency::known(*b"GBP", 2);
+    pub const CHF: Currency = Currency::known(*b"CHF", 2);
+    pub const JPY: Currency = Currency::known(*b"JPY", 0);
+    pub const KWD: Currency = Currency::known(*b"KWD", 3);
+
+    const KNOWN: [Currency; 6] = [
+        Currency::USD,
+        Currency::EUR,
+        Currency::GBP,
+        Currency::CHF,
+        Currency::JPY,
+        Currency::KWD,
+    ];
+
+    const fn known(code: [u8; 3], minor_units: u8) -> Self {
+        Currency { code, minor_units }
+    }
+
+    /// A currency outside the built-in list; `code` must be three ASCII
+    /// uppercase letters.
+    pub fn new(code: &str, minor_units: u8) -> Option<Self> {
+        let code: [u8; 3] = code.as_bytes().try_into().ok()?;
+        if !code.iter().all(u8::is_ascii_uppercase) {
+            return None;
+        }
+        Some(Currency { code, minor_units })
+    }
+
+    pub fn code(&self) -> &str {
+        std::str::from_utf8(&self.code).unwrap()
+    }
+
+    /// Digits after the decimal point in the smallest unit, e.g. 2 for cents.
+    pub fn minor_units(&self) -> u32 {
+        self.minor_units as u32
+    }
+}
+
+/// Looks up a built-in currency by its code.
+impl FromStr for Currency {
+    type Err = ParseMoneyError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Currency::KNOWN
+            .into_iter()
+            .find(|currency| currency.code() == src)
+            .ok_or_else(|| ParseMoneyError::UnknownCurrency(src.to_string()))
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.code())
+    }
+}
+
+impl Debug for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl Money {
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        Money { amount, currency }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Money::new(Decimal::new(0, currency.minor_units()), currency)
+    }
+
+    /// An amount counted in the minor unit, e.g. `from_minor(1050, USD)` is
+    /// USD 10.50.
+    pub fn from_minor(units: impl Into<BigInt>, currency: Currency) -> Self {
+        Money::new(Decimal::new(units, currency.minor_units()), currency)
+    }
+
+    pub fn amount(&self) -> &Decimal {
+        &self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.amount.is_negative()
+    }
+
+    /// Rounds to the currency's minor unit.
+    pub fn round(&self, mode: RoundingMode) -> Self {
+        Money::new(
+            self.amount.round(self.currency.minor_units(), mode),
+            self.currency,
+        )
+    }
+
+    /// Multiplies by a factor such as a tax rate, keeping every digit;
+    /// `round` afterwards to get back to whole minor units.
+    pub fn scale(&self, factor: &Decimal) -> Self {
+        Money::new(&self.amount * factor, self.currency)
+    }
+
+    pub fn checked_add(&self, rhs: &Money) -> Result<Self, MoneyError> {
+        self.same_currency(rhs)?;
+        Ok(Money::new(
+            add(self.amount.clone(), rhs.amount.clone()),
+            self.currency,
+        ))
+    }
+
+    pub fn checked_sub(&self, rhs: &Money) -> Result<Self, MoneyError> {
+        self.checked_add(&-rhs.clone())
+    }
+
+    /// Adds `rhs` after converting it into this currency with `rates`.
+    pub fn add_converted(&self, rhs: &Money, rates: &ExchangeRates) -> Result<Self, MoneyError> {
+        self.checked_add(&rates.convert(rhs, self.currency)?)
+    }
+
+    /// Adds up `items`, all of which must be in `currency`.
+    pub fn sum<'a>(
+        currency: Currency,
+        items: impl IntoIterator<Item = &'a Money>,
+    ) -> Result<Self, MoneyError> {
+        items
+            .into_iter()
+            .try_fold(Money::zero(currency), |total, item| total.checked_add(item))
+    }
+
+    /// Splits into parts proportional to `ratios` without losing a minor
+    /// unit: each part is rounded down and the units left over go one each
+    /// to the parts with the largest remainders, earliest first on ties.
+    ///
+    /// The amount must already be a whole number of minor units.
+    pub fn allocate(&self, ratios: &[u32]) -> Result<Vec<Money>, MoneyError> {
+        let total_ratio: u64 = ratios.iter().map(|&r| r as u64).sum();
+        if total_ratio == 0 {
+            return Err(MoneyError::InvalidRatios);
+        }
+        let scaled = self.amount.rescale(self.currency.minor_units())?;
+        let units = scaled.mantissa().abs();
+        let total_ratio = BigInt::from(total_ratio);
+
+        let mut shares = Vec::with_capacity(ratios.len());
+        let mut remainders = Vec::with_capacity(ratios.len());
+        let mut allocated = BigInt::zero();
+        for &ratio in ratios {
+            let (share, remainder) = (&units * &BigInt::from(ratio)).div_rem(&total_ratio);
+            allocated = add(allocated, share.clone());
+            shares.push(share);
+            remainders.push(remainder);
+        }
+
+        let mut order: Vec<usize> = (0..ratios.len()).collect();
+        order.sort_by(|&a, &b| remainders[b].cmp(&remainders[a]));
+        let left_over = (&units - &allocated).to_i128().unwrap() as usize;
+        for &index in &order[..left_over] {
+            shares[index] = add(shares[index].clone(), BigInt::one());
+        }
+
+        let negative = self.is_negative();
+        Ok(shares
+            .into_iter()
+            .map(|share| {
+                let share = if negative { -share } else { share };
+                Money::from_minor(share, self.currency)
+            })
+            .collect())
+    }
+
+    /// Splits into `parts` equal shares, as `allocate(&[1; parts])`.
+    pub fn split(&self, parts: usize) -> Result<Vec<Money>, MoneyError> {
+        self.allocate(&vec![1; parts])
+    }
+
+    fn same_currency(&self, rhs: &Money) -> Result<(), MoneyError> {
+        if self.currency == rhs.currency {
+            Ok(())
+        } else {
+            Err(MoneyError::CurrencyMismatch {
+                left: self.currency,
+                right: rhs.currency,
+            })
+        }
+    }
+}
+
+impl ExchangeRates {
+    pub fn new() -> Self {
+        ExchangeRates::default()
+    }
+
+    /// Records that one unit of `from` is worth `rate` units of `to`.
+    pub fn insert(&mut self, from: Currency, to: Currency, rate: Decimal) {
+        self.rates.insert((from, to), rate);
+    }
+
+    /// The rate from `from` to `to`: 1 for the same currency, a recorded
+    /// rate, or the reciprocal of the reverse rate.
+    pub fn rate(&self, from: Currency, to: Currency) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::one());
+        }
+        if let Some(rate) = self.rates.get(&(from, to)) {
+            return Some(rate.clone());
+        }
+        let reverse = self.rates.get(&(to, from))?;
+        Decimal::one().checked_div(reverse).ok()
+    }
+
+    /// Converts into `to`, rounding half-even to its minor unit.
+    pub fn convert(&self, money: &Money, to: Currency) -> Result<Money, MoneyError> {
+        if money.currency == to {
+            return Ok(money.clone());
+        }
+        let rate = self
+            .rate(money.currency, to)
+            .ok_or(MoneyError::MissingRate {
+                from: money.currency,
+                to,
+            })?;
+        Ok(Money::new(&money.amount * &rate, to).round(RoundingMode::HalfEven))
+    }
+}
+
+impl Add for &Money {
+    type Output = Result<Money, MoneyError>;
+
+    fn add(self, rhs: &Money) -> Self::Output {
+        self.checked_add(rhs)
+    }
+}
+
+impl Sub for &Money {
+    type Output = Result<Money, MoneyError>;
+
+    fn sub(self, rhs: &Money) -> Self::Output {
+        self.checked_sub(rhs)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money::new(-self.amount, self.currency)
+    }
+}
+
+/// Locale-neutral: the ISO code, a space and the amount with a `.` point and
+/// no grouping, e.g. `USD -1234.50`. The amount shows at least the minor
+/// unit's digits; a precision rounds half-even instead.
+impl Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let amount = match f.precision() {
+            Some(precision) => self.amount.round(precision as u32, RoundingMode::HalfEven),
+            None if self.amount.scale() < self.currency.minor_units() => self
+                .amount
+                .round(self.currency.minor_units(), RoundingMode::HalfEven),
+            None => self.amount.clone(),
+        };
+        write!(f, "{} {amount}", self.currency)
+    }
+}
+
+/// Parses the `Display` form, `CODE amount`, for built-in currencies.
+impl FromStr for Money {
+    type Err = ParseMoneyError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let (code, amount) = src
+            .trim()
+            .split_once(' ')
+            .ok_or(ParseMoneyError::MissingCurrency)?;
+        let currency = code.parse()?;
+        let amount = amount
+            .trim_start()
+            .parse()
+            .map_err(ParseMoneyError::Amount)?;
+        Ok(Money::new(amount, currency))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+
+    fn usd(src: &str) -> Money {
+        Money::new(src.parse().unwrap(), Currency::USD)
+    }
+
+    fn dec(src: &str) -> Decimal {
+        src.parse().unwrap()
+    }
+
+    #[test]
+    fn same_currency_arithmetic() {
+        assert_eq!((&usd("10.25") + &usd("0.80")).unwrap(), usd("11.05"));
+        assert_eq!((&usd("10.25") - &usd("20")).unwrap(), usd("-9.75"));
+        let items = [usd("1.10"), usd("2.20"), usd("3.30")];
+        assert_eq!(Money::sum(Currency::USD, &items), Ok(usd("6.60")));
+        assert_eq!(
+            usd("19.99")
+                .scale(&dec("0.0825"))
+                .round(RoundingMode::HalfEven),
+            usd("1.65")
+        );
+        assert_eq!(Money::from_minor(1050, Currency::USD), usd("10.50"));
+        assert_eq!(
+            Money::from_minor(1050, Currency::JPY).to_string(),
+            "JPY 1050"
+        );
+    }
+
+    #[test]
+    fn mixing_currencies() {
+        let eur = Money::new(dec("5"), Currency::EUR);
+        let error = MoneyError::CurrencyMismatch {
+            left: Currency::USD,
+            right: Currency::EUR,
+        };
+        assert_eq!(&usd("1") + &eur, Err(error.clone()));
+        assert_eq!(error.to_string(), "cannot combine USD with EUR");
+        assert!((&usd("1") - &eur).is_err());
+        assert!(Money::sum(Currency::USD, &[usd("1"), eur.clone()]).is_err());
+
+        let mut rates = ExchangeRates::new();
+        assert_eq!(
+            usd("1").add_converted(&eur, &rates),
+            Err(MoneyError::MissingRate {
+                from: Currency::EUR,
+                to: Currency::USD
+            })
+        );
+        rates.insert(Currency::EUR, Currency::USD, dec("1.0845"));
+        assert_eq!(usd("1").add_converted(&eur, &rates), Ok(usd("6.42")));
+        // The reverse direction uses the reciprocal rate.
+        let back = rates.convert(&usd("10.845"), Currency::EUR).unwrap();
+        assert_eq!(back, Money::new(dec("10.00"), Currency::EUR));
+        let yen = Money::new(dec("1000"), Currency::JPY);
+        rates.insert(Currency::JPY, Currency::USD, dec("0.00667"));
+        assert_eq!(rates.convert(&yen, Currency::USD), Ok(usd("6.67")));
+        assert_eq!(
+            rates.convert(&usd("100"), Currency::JPY),
+            Ok(Money::from_minor(14993, Currency::JPY))
+        );
+    }
+
+    #[test]
+    fn allocation() {
+        let parts = usd("100").split(3).unwrap();
+        assert_eq!(parts, [usd("33.34"), usd("33.33"), usd("33.33")]);
+        let parts = usd("0.05").allocate(&[3, 7]).unwrap();
+        assert_eq!(parts, [usd("0.02"), usd("0.03")]);
+        // Largest remainders win the spare cents, not just the first parts.
+        let parts = usd("1").allocate(&[1, 2, 2]).unwrap();
+        assert_eq!(parts, [usd("0.20"), usd("0.40"), usd("0.40")]);
+        let parts = usd("-100").split(3).unwrap();
+        assert_eq!(parts, [usd("-33.34"), usd("-33.33"), usd("-33.33")]);
+        assert_eq!(usd("5").allocate(&[0, 1]).unwrap(), [usd("0"), usd("5")]);
+        assert_eq!(usd("5").allocate(&[]), Err(MoneyError::InvalidRatios));
+        assert_eq!(usd("5").allocate(&[0, 0]), Err(MoneyError::InvalidRatios));
+        assert!(matches!(usd("0.001").split(2), Err(MoneyError::Decimal(_))));
+
+        let mut rng = Rng(17);
+        for _ in 0..500 {
+            let total = Money::from_minor(rng.range(-1_000_000, 1_000_000), Currency::USD);
+            let ratios: Vec<u32> = (0..rng.range(1, 8))
+                .map(|_| rng.range(0, 50) as u32)
+                .collect();
+            let Ok(parts) = total.allocate(&ratios) else {
+                assert!(ratios.iter().all(|&r| r == 0));
+                continue;
+            };
+            assert_eq!(Money::sum(Currency::USD, &parts), Ok(total.clone()));
+            // Every part is within one cent of its exact share.
+            let sum: u32 = ratios.iter().sum();
+            for (part, &ratio) in parts.iter().zip(&ratios) {
+                let exact = total.amount() * &Decimal::from(ratio) / Decimal::from(sum);
+                assert!(
+                    (part.amount() - &exact).abs() < dec("0.01"),
+                    "{part} vs {exact}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn formatting_and_parsing() {
+        assert_eq!(usd("1234.5").to_string(), "USD 1234.50");
+        assert_eq!(usd("-0.125").to_string(), "USD -0.125");
+        assert_eq!(format!("{:.2}", usd("-0.125")), "USD -0.12");
+        assert_eq!(Money::zero(Currency::KWD).to_string(), "KWD 0.000");
+        assert_eq!("USD 1234.50".parse(), Ok(usd("1234.50")));
+        assert_eq!(
+            " EUR  -3 ".parse(),
+            Ok(Money::new(dec("-3"), Currency::EUR))
+        );
+        assert_eq!(
+            "1234".parse::<Money>(),
+            Err(ParseMoneyError::MissingCurrency)
+        );
+        assert_eq!(
+            "XYZ 1".parse::<Money>(),
+            Err(ParseMoneyError::UnknownCurrency("XYZ".into()))
+        );
+        assert!(matches!(
+            "USD 1,000".parse::<Money>(),
+            Err(ParseMoneyError::Amount(_))
+        ));
+
+        let custom = Currency::new("BTC", 8).unwrap();
+        assert_eq!(Money::from_minor(1, custom).to_string(), "BTC 0.00000001");
+        assert_eq!(Currency::new("usd", 2), None);
+        assert_eq!(Currency::new("USDX", 2), None);
+        assert_eq!(format!("{:?}", Currency::GBP), "GBP");
+    }
+}
@@ -0,0 +1,223 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// A number that `add` (and the rest of this crate) can work with.
+///
+/// Implemented for every primitive integer and float type. Implement it for
+/// your own types, e.g. fixed-point values, to use them with the same API.
+pub trait Num: Clone + PartialEq + Debug {
+    fn zero() -> Self;
+    fn one() -> Self;
+
+    fn add(self, rhs: Self) -> Self;
+    fn sub(self, rhs: Self) -> Self;
+    fn mul(self, rhs: Self) -> Self;
+    fn div(self, rhs: Self) -> Self;
+
+    /// `zero - self` unless overridden; floats override it to keep the sign
+    /// of zero, so that `-0.0` and `0.0` swap.
+    fn neg(self) -> Self {
+        Self::zero().sub(self)
+    }
+}
+
+macro_rules! impl_num {
+    ($zero:literal, $one:literal, $neg:item; $($t:ty)*) => {
+        $(
+            impl Num for $t {
+                fn zero() -> Self {
+                    $zero
+                }
+
+                fn one() -> Self {
+                    $one
+                }
+
+                fn add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+
+                fn sub(self, rhs: Self) -> Self {
+                    self - rhs
+                }
+
+                fn mul(self, rhs: Self) -> Self {
+                    self * rhs
+                }
+
+                fn div(self, rhs: Self) -> Self {
+                    self / rhs
+                }
+
+                $neg
+            }
+        )*
+    };
+}
+
+impl_num!(0, 1, fn neg(self) -> Self { 0 - self };
+    u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+impl_num!(0.0, 1.0, fn neg(self) -> Self { -self }; f32 f64);
+
+/// A floating-point `Num` with the usual operators and math functions.
+///
+/// Implemented for `f32` and `f64`; the methods forward to the inherent ones.
+pub trait Float:
+    Num
+    + Copy
+    + PartialOrd
+    + Display
+    + FromStr
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const EPSILON: Self;
+    const PI: Self;
+
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn is_nan(self) -> bool;
+    fn is_finite(self) -> bool;
+    /// The next representable value towards positive infinity.
+    fn next_up(self) -> Self;
+    /// The next representable value towards negative infinity.
+    fn next_down(self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($($t:ident)*) => {
+        $(
+            impl Float for $t {
+                const EPSILON: Self = $t::EPSILON;
+                const PI: Self = std::$t::consts::PI;
+
+                fn from_f64(value: f64) -> Self {
+                    value as $t
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn abs(self) -> Self {
+                    $t::abs(self)
+                }
+
+                fn sqrt(self) -> Self {
+                    $t::sqrt(self)
+                }
+
+                fn exp(self) -> Self {
+                    $t::exp(self)
+                }
+
+                fn ln(self) -> Self {
+                    $t::ln(self)
+                }
+
+                fn sin(self) -> Self {
+                    $t::sin(self)
+                }
+
+                fn cos(self) -> Self {
+                    $t::cos(self)
+                }
+
+                fn atan2(self, other: Self) -> Self {
+                    $t::atan2(self, other)
+                }
+
+                fn hypot(self, other: Self) -> Self {
+                    $t::hypot(self, other)
+                }
+
+                fn is_nan(self) -> bool {
+                    $t::is_nan(self)
+                }
+
+                fn is_finite(self) -> bool {
+                    $t::is_finite(self)
+                }
+
+                fn next_up(self) -> Self {
+                    $t::next_up(self)
+                }
+
+                fn next_down(self) -> Self {
+                    $t::next_down(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_float!(f32 f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Fixed(i64);
+
+    impl Num for Fixed {
+        fn zero() -> Self {
+            Fixed(0)
+        }
+
+        fn one() -> Self {
+            Fixed(100)
+        }
+
+        fn add(self, rhs: Self) -> Self {
+            Fixed(self.0 + rhs.0)
+        }
+
+        fn sub(self, rhs: Self) -> Self {
+            Fixed(self.0 - rhs.0)
+        }
+
+        fn mul(self, rhs: Self) -> Self {
+            Fixed(self.0 * rhs.0 / 100)
+        }
+
+        fn div(self, rhs: Self) -> Self {
+            Fixed(self.0 * 100 / rhs.0)
+        }
+    }
+
+    #[test]
+    fn identities() {
+        fn check<T: Num + Copy>(x: T) {
+            assert_eq!(x.add(T::zero()), x);
+            assert_eq!(x.sub(T::zero()), x);
+            assert_eq!(x.mul(T::one()), x);
+            assert_eq!(x.div(T::one()), x);
+        }
+
+        check(7u8);
+        check(-7i32);
+        check(2.5f64);
+        check(Fixed(1234));
+    }
+
+    #[test]
+    fn user_types() {
+        assert_eq!(Fixed(150).mul(Fixed(200)), Fixed(300));
+        assert_eq!(Fixed(300).div(Fixed(200)), Fixed(150));
+        assert_eq!(Fixed(300).sub(Fixed::one()), Fixed(200));
+    }
+}
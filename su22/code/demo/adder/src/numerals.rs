@@ -0,0 +1,512 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use crate::add;
+
+/// The largest number with a standard Roman numeral, `MMMCMXCIX`.
+pub const MAX_ROMAN: u32 = 3999;
+
+/// One, five and ten at each decimal place, thousands first; there is no
+/// five or ten thousand, so those places are never used.
+const ROMAN_PLACES: [(u32, [char; 3]); 4] = [
+    (1000, ['M', '?', '?']),
+    (100, ['C', 'D', 'M']),
+    (10, ['X', 'L', 'C']),
+    (1, ['I', 'V', 'X']),
+];
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// Scale words from largest to smallest with their values.
+const SCALES: [(&str, u64); 6] = [
+    ("quintillion", 1_000_000_000_000_000_000),
+    ("quadrillion", 1_000_000_000_000_000),
+    ("trillion", 1_000_000_000_000),
+    ("billion", 1_000_000_000),
+    ("million", 1_000_000),
+    ("thousand", 1_000),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RomanError {
+    OutOfRange(u32),
+}
+
+impl Display for RomanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RomanError::OutOfRange(value) => {
+                write!(
+                    f,
+                    "{value} has no roman numeral (expected 1 to {MAX_ROMAN})"
+                )
+            }
+        }
+    }
+}
+
+impl Error for RomanError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRomanError {
+    Empty,
+    InvalidChar {
+        index: usize,
+        found: char,
+    },
+    /// A valid letter in a position the standard form does not allow, such
+    /// as the second `I` in `IIX` or the last `M` in `MMMM`.
+    Malformed {
+        index: usize,
+    },
+}
+
+impl Display for ParseRomanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRomanError::Empty => write!(f, "cannot parse roman numeral from empty string"),
+            ParseRomanError::InvalidChar { index, found } => {
+                write!(f, "invalid roman digit {found:?} at index {index}")
+            }
+            ParseRomanError::Malformed { index } => {
+                write!(f, "malformed roman numeral at index {index}")
+            }
+        }
+    }
+}
+
+impl Error for ParseRomanError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseWordsError {
+    Empty,
+    UnknownWord {
+        index: usize,
+        word: String,
+    },
+    /// A known word in the wrong place, as in `twenty twenty` or
+    /// `thousand million`.
+    Unexpected {
+        index: usize,
+        word: String,
+    },
+    Overflow,
+}
+
+impl Display for ParseWordsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWordsError::Empty => write!(f, "cannot parse number from empty string"),
+            ParseWordsError::UnknownWord { index, word } => {
+                write!(f, "unknown number word {word:?} at index {index}")
+            }
+            ParseWordsError::Unexpected { index, word } => {
+                write!(f, "unexpected {word:?} at index {index}")
+            }
+            ParseWordsError::Overflow => write!(f, "number too large to fit in target type"),
+        }
+    }
+}
+
+impl Error for ParseWordsError {}
+
+/// The digit `digit` (0 to 9) at a place with the given one, five and ten.
+fn roman_digit(digit: u32, [one, five, ten]: [char; 3]) -> String {
+    match digit {
+        0..=3 => one.to_string().repeat(digit as usize),
+        4 => format!("{one}{five}"),
+        5..=8 => format!("{five}{}", one.to_string().repeat(digit as usize - 5)),
+        _ => format!("{one}{ten}"),
+    }
+}
+
+/// Writes `value` in standard subtractive form, e.g. 1994 is `MCMXCIV`.
+pub fn to_roman(value: u32) -> Result<String, RomanError> {
+    if !(1..=MAX_ROMAN).contains(&value) {
+        return Err(RomanError::OutOfRange(value));
+    }
+    Ok(ROMAN_PLACES
+        .iter()
+        .map(|&(place, symbols)| roman_digit(value / place % 10, symbols))
+        .collect())
+}
+
+/// Parses an uppercase numeral, accepting only the form `to_roman` writes:
+/// `IIII`, `IC` and `VX` are all rejected.
+pub fn from_roman(src: &str) -> Result<u32, ParseRomanError> {
+    if src.is_empty() {
+        return Err(ParseRomanError::Empty);
+    }
+    let mut rest = src;
+    let mut value = 0;
+    for (place, symbols) in ROMAN_PLACES {
+        // Larger digits have the longer spelling among those sharing a
+        // prefix, so the first match from the top is the right one.
+        let digits = if place == 1000 { 3 } else { 9 };
+        if let Some((digit, len)) = (1..=digits).rev().find_map(|digit| {
+            let spelled = roman_digit(digit, symbols);
+            rest.starts_with(&spelled).then_some((digit, spelled.len()))
+        }) {
+            value = add(value, digit * place);
+            rest = &rest[len..];
+        }
+    }
+    match rest.chars().next() {
+        None => Ok(value),
+        Some(found) => {
+            let index = src.len() - rest.len();
+            if "IVXLCDM".contains(found) {
+                Err(ParseRomanError::Malformed { index })
+            } else {
+                Err(ParseRomanError::InvalidChar { index, found })
+            }
+        }
+    }
+}
+
+/// Writes words for a number below a thousand, which must not be zero.
+fn group_words(value: u64, words: &mut Vec<String>) {
+    let (hundreds, rest) = (value / 100, value % 100);
+    if hundreds > 0 {
+        words.push(ONES[hundreds as usize].to_string());
+        words.push("hundred".to_string());
+    }
+    match rest {
+        0 => {}
+        1..=19 => words.push(ONES[rest as usize].to_string()),
+        _ if rest % 10 == 0 => words.push(TENS[rest as usize / 10].to_string()),
+        _ => words.push(format!(
+            "{}-{}",
+            TENS[rest as usize / 10],
+            ONES[rest as usize % 10]
+        )),
+    }
+}
+
+/// Writes `value` in US English: `one hundred twenty-three`, with
+/// hyphenated tens and no "and".
+pub fn to_words(value: u64) -> String {
+    if value == 0 {
+        return ONES[0].to_string();
+    }
+    let mut words = Vec::new();
+    let mut rest = value;
+    for (name, scale) in SCALES {
+        if rest >= scale {
+            group_words(rest / scale, &mut words);
+            words.push(name.to_string());
+            rest %= scale;
+        }
+    }
+    if rest > 0 {
+        group_words(rest, &mut words);
+    }
+    words.join(" ")
+}
+
+/// What a word contributes, once looked up.
+#[derive(Clone, Copy, PartialEq)]
+enum Word {
+    Zero,
+    Unit(u64),
+    Teen(u64),
+    Tens(u64),
+    Hundred,
+    Scale(u64),
+    And,
+}
+
+fn lookup(word: &str) -> Option<Word> {
+    if let Some(n) = ONES.iter().position(|&w| w == word) {
+        return Some(match n {
+            0 => Word::Zero,
+            1..=9 => Word::Unit(n as u64),
+            _ => Word::Teen(n as u64),
+        });
+    }
+    if let Some(n) = TENS.iter().position(|&w| !w.is_empty() && w == word) {
+        return Some(Word::Tens(n as u64 * 10));
+    }
+    if let Some(&(_, scale)) = SCALES.iter().find(|&&(name, _)| name == word) {
+        return Some(Word::Scale(scale));
+    }
+    match word {
+        "hundred" => Some(Word::Hundred),
+        "and" => Some(Word::And),
+        _ => None,
+    }
+}
+
+/// Parses English number words, ignoring case, e.g. `One Hundred and
+/// Twenty-Three`. An "and" may follow "hundred" or a scale word; hyphens
+/// may only join tens to units.
+pub fn from_words(src: &str) -> Result<u64, ParseWordsError> {
+    let mut words = Vec::new();
+    for token in src.split_whitespace() {
+        let start = token.as_ptr() as usize - src.as_ptr() as usize;
+        let lower = token.to_ascii_lowercase();
+        let mut parts = lower.splitn(2, '-');
+        let first = parts.next().unwrap();
+        let known = |part: &str, index| {
+            lookup(part).ok_or_else(|| ParseWordsError::UnknownWord {
+                index,
+                word: part.to_string(),
+            })
+        };
+        words.push((start, first.to_string(), known(first, start)?));
+        if let Some(second) = parts.next() {
+            let index = start + first.len() + 1;
+            let word = known(second, index)?;
+            if !matches!(
+                (words.last().unwrap().2, word),
+                (Word::Tens(_), Word::Unit(_))
+            ) {
+                return Err(ParseWordsError::Unexpected {
+                    index,
+                    word: second.to_string(),
+                });
+            }
+            words.push((index, second.to_string(), word));
+        }
+    }
+
+    let unexpected = |(index, word, _): &(usize, String, Word)| ParseWordsError::Unexpected {
+        index: *index,
+        word: word.clone(),
+    };
+    match words.as_slice() {
+        [] => return Err(ParseWordsError::Empty),
+        [(_, _, Word::Zero)] => return Ok(0),
+        _ => {}
+    }
+
+    let mut total: u64 = 0;
+    // The group below a thousand being built, and the smallest scale used
+    // so far, which later scales must stay under.
+    let mut group: u64 = 0;
+    let mut last_scale = u64::MAX;
+    let mut previous: Option<Word> = None;
+    for entry in &words {
+        let word = entry.2;
+        let allowed = match word {
+            Word::Zero => false,
+            // A unit may start a group, follow "hundred", or finish tens.
+            Word::Unit(_) => group.is_multiple_of(100) || matches!(previous, Some(Word::Tens(_))),
+            Word::Teen(_) | Word::Tens(_) => group.is_multiple_of(100),
+            Word::Hundred => matches!(previous, Some(Word::Unit(_))) && group < 10,
+            Word::Scale(scale) => group > 0 && scale < last_scale,
+            Word::And => matches!(previous, Some(Word::Hundred | Word::Scale(_))),
+        };
+        if !allowed {
+            return Err(unexpected(entry));
+        }
+        match word {
+            Word::Unit(n) | Word::Teen(n) | Word::Tens(n) => group = add(group, n),
+            Word::Hundred => group *= 100,
+            Word::Scale(scale) => {
+                let value = group.checked_mul(scale).ok_or(ParseWordsError::Overflow)?;
+                total = total.checked_add(value).ok_or(ParseWordsError::Overflow)?;
+                group = 0;
+                last_scale = scale;
+            }
+            Word::Zero | Word::And => {}
+        }
+        previous = Some(word);
+    }
+    if previous == Some(Word::And) {
+        return Err(unexpected(words.last().unwrap()));
+    }
+    total.checked_add(group).ok_or(ParseWordsError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+
+    #[test]
+    fn roman_round_trips_full_range() {
+        for value in 1..=MAX_ROMAN {
+            let numeral = to_roman(value).unwrap();
+            assert_eq!(from_roman(&numeral), Ok(value), "{numeral}");
+        }
+        assert_eq!(to_roman(1994).unwrap(), "MCMXCIV");
+        assert_eq!(to_roman(3999).unwrap(), "MMMCMXCIX");
+        assert_eq!(to_roman(0), Err(RomanError::OutOfRange(0)));
+        assert_eq!(to_roman(4000), Err(RomanError::OutOfRange(4000)));
+        assert_eq!(
+            RomanError::OutOfRange(0).to_string(),
+            "0 has no roman numeral (expected 1 to 3999)"
+        );
+    }
+
+    #[test]
+    fn roman_rejects_non_standard_forms() {
+        use ParseRomanError::*;
+
+        for (src, index) in [
+            ("IIII", 3),
+            ("IIX", 2),
+            ("IL", 1),
+            ("IC", 1),
+            ("XD", 1),
+            ("VX", 1),
+            ("VV", 1),
+            ("LL", 1),
+            ("DD", 1),
+            ("XCX", 2),
+            ("IXI", 2),
+            ("MMMM", 3),
+            ("CMD", 2),
+        ] {
+            assert_eq!(from_roman(src), Err(Malformed { index }), "{src}");
+        }
+        assert_eq!(from_roman(""), Err(Empty));
+        assert_eq!(
+            from_roman("XIV "),
+            Err(InvalidChar {
+                index: 3,
+                found: ' '
+            })
+        );
+        assert_eq!(
+            from_roman("xiv"),
+            Err(InvalidChar {
+                index: 0,
+                found: 'x'
+            })
+        );
+        assert_eq!(
+            Malformed { index: 2 }.to_string(),
+            "malformed roman numeral at index 2"
+        );
+    }
+
+    #[test]
+    fn words_round_trip() {
+        for value in (0..=100_000).chain((1..=100).map(|n| n * 1_000_000 - 1)) {
+            let words = to_words(value);
+            assert_eq!(from_words(&words), Ok(value), "{words}");
+        }
+        let mut rng = Rng(20);
+        for _ in 0..2000 {
+            let value = rng.next() >> (rng.next() % 64);
+            assert_eq!(from_words(&to_words(value)), Ok(value));
+        }
+        assert_eq!(
+            to_words(u64::MAX),
+            "eighteen quintillion four hundred forty-six quadrillion seven hundred \
+             forty-four trillion seventy-three billion seven hundred nine million \
+             five hundred fifty-one thousand six hundred fifteen"
+        );
+        assert_eq!(from_words(&to_words(u64::MAX)), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn words_formatting_and_lenient_input() {
+        assert_eq!(to_words(0), "zero");
+        assert_eq!(to_words(123), "one hundred twenty-three");
+        assert_eq!(to_words(1_000_010), "one million ten");
+        assert_eq!(to_words(90_000), "ninety thousand");
+        assert_eq!(from_words("One Hundred and Twenty-Three"), Ok(123));
+        assert_eq!(from_words("  twenty   five "), Ok(25));
+        assert_eq!(from_words("two thousand and one"), Ok(2001));
+        assert_eq!(
+            from_words("nineteen hundred"),
+            Err(ParseWordsError::Unexpected {
+                index: 9,
+                word: "hundred".into()
+            })
+        );
+    }
+
+    #[test]
+    fn words_errors() {
+        use ParseWordsError::*;
+
+        let unexpected = |index, word: &str| Unexpected {
+            index,
+            word: word.into(),
+        };
+        assert_eq!(from_words(""), Err(Empty));
+        assert_eq!(from_words("   "), Err(Empty));
+        assert_eq!(
+            from_words("one hundred twentyfive"),
+            Err(UnknownWord {
+                index: 12,
+                word: "twentyfive".into()
+            })
+        );
+        assert_eq!(from_words("twenty twenty"), Err(unexpected(7, "twenty")));
+        assert_eq!(from_words("five three"), Err(unexpected(5, "three")));
+        assert_eq!(from_words("twelve one"), Err(unexpected(7, "one")));
+        assert_eq!(from_words("zero one"), Err(unexpected(0, "zero")));
+        assert_eq!(from_words("one zero"), Err(unexpected(4, "zero")));
+        assert_eq!(from_words("thousand"), Err(unexpected(0, "thousand")));
+        assert_eq!(
+            from_words("one thousand million"),
+            Err(unexpected(13, "million"))
+        );
+        assert_eq!(
+            from_words("one thousand two thousand"),
+            Err(unexpected(17, "thousand"))
+        );
+        assert_eq!(
+            from_words("one hundred hundred"),
+            Err(unexpected(12, "hundred"))
+        );
+        assert_eq!(from_words("and one"), Err(unexpected(0, "and")));
+        assert_eq!(from_words("one hundred and"), Err(unexpected(12, "and")));
+        assert_eq!(from_words("five-twenty"), Err(unexpected(5, "twenty")));
+        assert_eq!(
+            from_words("twenty-"),
+            Err(UnknownWord {
+                index: 7,
+                word: "".into()
+            })
+        );
+        assert_eq!(from_words("twenty quintillion"), Err(Overflow));
+        assert_eq!(
+            unexpected(4, "zero").to_string(),
+            "unexpected \"zero\" at index 4"
+        );
+    }
+
+    #[test]
+    fn feeds_add() {
+        let sum = add(
+            from_roman("XII").unwrap(),
+            from_words("nine").unwrap() as u32,
+        );
+        assert_eq!(to_roman(sum).unwrap(), "XXI");
+        assert_eq!(
+            to_words(add(from_words("forty-two").unwrap(), 58)),
+            "one hundred"
+        );
+    }
+}
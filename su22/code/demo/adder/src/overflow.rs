@@ -0,0 +1,194 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use crate::Num;
+
+/// What to do when an integer addition does not fit in its type.
+///
+/// Every policy behaves the same in debug and release builds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// Return `AddError::Overflow`.
+    Checked,
+    /// Wrap around at the boundary of the type (two's complement).
+    Wrapping,
+    /// Clamp to the minimum or maximum value of the type.
+    Saturating,
+    /// Panic with the `AddError` message.
+    Panicking,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AddError {
+    Overflow {
+        ty: &'static str,
+        left: String,
+        right: String,
+    },
+}
+
+impl AddError {
+    fn overflow<T: Int>(left: &T, right: &T) -> Self {
+        AddError::Overflow {
+            ty: T::NAME,
+            left: left.to_string(),
+            right: right.to_string(),
+        }
+    }
+}
+
+impl Display for AddError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddError::Overflow { ty, left, right } => {
+                write!(f, "{left} + {right} overflows {ty}")
+            }
+        }
+    }
+}
+
+impl Error for AddError {}
+
+/// An integer type with explicit overflow behaviour.
+pub trait Int: Num + Display {
+    const NAME: &'static str;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn wrapping_add(self, rhs: Self) -> Self;
+    fn saturating_add(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_int {
+    ($($t:ident)*) => {
+        $(
+            impl Int for $t {
+                const NAME: &'static str = stringify!($t);
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    $t::checked_add(self, rhs)
+                }
+
+                fn wrapping_add(self, rhs: Self) -> Self {
+                    $t::wrapping_add(self, rhs)
+                }
+
+                fn saturating_add(self, rhs: Self) -> Self {
+                    $t::saturating_add(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+pub fn add_with<T: Int>(left: T, right: T, policy: OverflowPolicy) -> Result<T, AddError> {
+    match policy {
+        OverflowPolicy::Checked => checked_add(left, right),
+        OverflowPolicy::Wrapping => Ok(wrapping_add(left, right)),
+        OverflowPolicy::Saturating => Ok(saturating_add(left, right)),
+        OverflowPolicy::Panicking => Ok(panicking_add(left, right)),
+    }
+}
+
+pub fn checked_add<T: Int>(left: T, right: T) -> Result<T, AddError> {
+    match left.clone().checked_add(right.clone()) {
+        Some(sum) => Ok(sum),
+        None => Err(AddError::overflow(&left, &right)),
+    }
+}
+
+pub fn wrapping_add<T: Int>(left: T, right: T) -> T {
+    left.wrapping_add(right)
+}
+
+pub fn saturating_add<T: Int>(left: T, right: T) -> T {
+    left.saturating_add(right)
+}
+
+pub fn panicking_add<T: Int>(left: T, right: T) -> T {
+    match checked_add(left, right) {
+        Ok(sum) => sum,
+        Err(error) => panic!("{error}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! unsigned_boundaries {
+        ($($t:ident)*) => {
+            $(
+                assert_eq!(checked_add($t::MAX - 1, 1), Ok($t::MAX));
+                assert_eq!(
+                    checked_add($t::MAX, 1),
+                    Err(AddError::Overflow {
+                        ty: stringify!($t),
+                        left: $t::MAX.to_string(),
+                        right: "1".to_string(),
+                    })
+                );
+                assert_eq!(wrapping_add($t::MAX, 1), 0);
+                assert_eq!(wrapping_add($t::MAX, $t::MAX), $t::MAX - 1);
+                assert_eq!(saturating_add($t::MAX, 1), $t::MAX);
+                assert_eq!(saturating_add($t::MIN, 0), $t::MIN);
+                assert_eq!(panicking_add($t::MAX, 0), $t::MAX);
+            )*
+        };
+    }
+
+    macro_rules! signed_boundaries {
+        ($($t:ident)*) => {
+            $(
+                assert_eq!(checked_add($t::MAX - 1, 1), Ok($t::MAX));
+                assert_eq!(checked_add($t::MIN + 1, -1), Ok($t::MIN));
+                assert!(checked_add($t::MAX, 1).is_err());
+                assert!(checked_add($t::MIN, -1).is_err());
+                assert_eq!(checked_add($t::MIN, $t::MAX), Ok(-1));
+                assert_eq!(wrapping_add($t::MAX, 1), $t::MIN);
+                assert_eq!(wrapping_add($t::MIN, -1), $t::MAX);
+                assert_eq!(saturating_add($t::MAX, 1), $t::MAX);
+                assert_eq!(saturating_add($t::MIN, -1), $t::MIN);
+                assert_eq!(panicking_add($t::MIN, 0), $t::MIN);
+            )*
+        };
+    }
+
+    #[test]
+    fn unsigned() {
+        unsigned_boundaries!(u8 u16 u32 u64 u128 usize);
+    }
+
+    #[test]
+    fn signed() {
+        signed_boundaries!(i8 i16 i32 i64 i128 isize);
+    }
+
+    #[test]
+    fn policies() {
+        assert!(add_with(u8::MAX, 1, OverflowPolicy::Checked).is_err());
+        assert_eq!(add_with(u8::MAX, 1, OverflowPolicy::Wrapping), Ok(0));
+        assert_eq!(
+            add_with(u8::MAX, 1, OverflowPolicy::Saturating),
+            Ok(u8::MAX)
+        );
+        assert_eq!(
+            add_with(usize::MAX, 0, OverflowPolicy::Panicking),
+            Ok(usize::MAX)
+        );
+        assert_eq!(add_with(2i32, 2, OverflowPolicy::Checked), Ok(4));
+    }
+
+    #[test]
+    #[should_panic(expected = "255 + 1 overflows u8")]
+    fn panicking() {
+        panicking_add(u8::MAX, 1);
+    }
+
+    #[test]
+    fn error_message() {
+        let error = checked_add(i8::MIN, -1).unwrap_err();
+        assert_eq!(error.to_string(), "-128 + -1 overflows i8");
+    }
+}
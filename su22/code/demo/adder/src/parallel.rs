@@ -0,0 +1,151 @@
+use std::thread;
+
+use crate::{Accumulator, AddError, Summable};
+
+/// Values summed per chunk. The chunking, and so the reduction tree, is the
+/// same for any thread count, which is what makes float results reproducible.
+pub const PAR_CHUNK_LEN: usize = 1 << 16;
+
+/// Sums a slice on all available cores; see `par_sum_with_threads`.
+pub fn par_sum<T>(values: &[T]) -> Result<T, AddError>
+where
+    T: Summable + Sync,
+    T::Accumulator: Send,
+{
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    par_sum_with_threads(values, threads)
+}
+
+/// Sums a slice on up to `threads` scoped worker threads.
+///
+/// The slice is cut into `PAR_CHUNK_LEN` chunks, each chunk is accumulated
+/// like `sum` does, and the chunk totals are merged in a fixed balanced tree.
+/// The result is bit-identical for every thread count. Integers are checked
+/// within each chunk and at every merge rather than along the whole slice,
+/// so this and `sum` can disagree on whether a sum overflows: either may
+/// fail where the other succeeds, e.g. when an early large value is only
+/// cancelled in a later chunk. When both succeed the totals are equal.
+pub fn par_sum_with_threads<T>(values: &[T], threads: usize) -> Result<T, AddError>
+where
+    T: Summable + Sync,
+    T::Accumulator: Send,
+{
+    let chunks: Vec<&[T]> = values.chunks(PAR_CHUNK_LEN).collect();
+    let threads = threads.clamp(1, chunks.len().max(1));
+    let per_worker = chunks.len().div_ceil(threads).max(1);
+
+    let partials = thread::scope(|scope| {
+        let workers: Vec<_> = chunks
+            .chunks(per_worker)
+            .map(|group| {
+                scope.spawn(move || {
+                    group
+                        .iter()
+                        .map(|chunk| accumulate(chunk))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("summation worker panicked"))
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+
+    Ok(merge_tree(partials)?.total())
+}
+
+fn accumulate<T: Summable>(chunk: &[T]) -> Result<T::Accumulator, AddError> {
+    let mut total = T::Accumulator::default();
+    for value in chunk {
+        total.push(value.clone())?;
+    }
+    Ok(total)
+}
+
+/// Merges neighbours level by level: ((0 1) (2 3)) ((4 5) 6) ...
+fn merge_tree<A: Accumulator<T>, T>(mut level: Vec<A>) -> Result<A, AddError> {
+    while level.len() > 1 {
+        let mut next = Vec::with_capacity(level.len().div_ceil(2));
+        let mut items = level.into_iter();
+        while let Some(mut left) = items.next() {
+            if let Some(right) = items.next() {
+                left.merge(right)?;
+            }
+            next.push(left);
+        }
+        level = next;
+    }
+    Ok(level.pop().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sum;
+    use crate::test_util::Rng;
+
+    #[test]
+    fn same_result_for_any_thread_count() {
+        let mut rng = Rng(23);
+        let len = PAR_CHUNK_LEN * 5 + 123;
+        let floats: Vec<f64> = (0..len)
+            .map(|_| {
+                rng.range(-1_000_000, 1_000_000) as f64 * 1e-3 * 10f64.powi(rng.range(-8, 8) as i32)
+            })
+            .collect();
+        let expected = par_sum_with_threads(&floats, 1).unwrap();
+        for threads in 2..=8 {
+            assert_eq!(
+                par_sum_with_threads(&floats, threads).unwrap().to_bits(),
+                expected.to_bits()
+            );
+        }
+        assert_eq!(par_sum(&floats).unwrap().to_bits(), expected.to_bits());
+        let sequential = sum(floats.iter().copied()).unwrap();
+        assert!((expected - sequential).abs() <= sequential.abs() * 1e-12);
+    }
+
+    #[test]
+    fn integers_match_sequential() {
+        let counters: Vec<usize> = (0..PAR_CHUNK_LEN * 3 + 7).collect();
+        let expected = sum(counters.iter().copied());
+        for threads in [1, 2, 3, 4, 16] {
+            assert_eq!(par_sum_with_threads(&counters, threads), expected);
+        }
+    }
+
+    #[test]
+    fn overflow_is_reported() {
+        let mut values = vec![0u32; PAR_CHUNK_LEN * 2];
+        values[0] = u32::MAX;
+        values[PAR_CHUNK_LEN] = 1;
+        assert!(par_sum_with_threads(&values, 2).is_err());
+        assert!(par_sum_with_threads(&values, 1).is_err());
+    }
+
+    #[test]
+    fn overflow_is_checked_per_chunk() {
+        // 127 + 1 overflows a running i8 total, but the second chunk's own
+        // total is -1.
+        let mut values = vec![0i8; PAR_CHUNK_LEN * 2];
+        values[0] = i8::MAX;
+        values[PAR_CHUNK_LEN] = 1;
+        values[PAR_CHUNK_LEN + 1] = -2;
+        assert!(sum(values.iter().copied()).is_err());
+        assert_eq!(par_sum_with_threads(&values, 2), Ok(126));
+        // And the other way round: the second chunk reaches 128 on its own.
+        values[0] = -1;
+        values[PAR_CHUNK_LEN] = i8::MAX;
+        values[PAR_CHUNK_LEN + 1] = 1;
+        assert_eq!(sum(values.iter().copied()), Ok(i8::MAX));
+        assert!(par_sum_with_threads(&values, 2).is_err());
+    }
+
+    #[test]
+    fn small_inputs() {
+        assert_eq!(par_sum::<i32>(&[]), Ok(0));
+        assert_eq!(par_sum(&[1e16, 1.0, -1e16]), Ok(1.0));
+        assert_eq!(par_sum_with_threads(&[1u8, 2, 3], 0), Ok(6));
+    }
+}
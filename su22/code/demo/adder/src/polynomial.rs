@@ -0,0 +1,430 @@
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+use crate::{add, Num};
+
+/// A polynomial in one variable, stored as coefficients from the constant
+/// term upwards with no trailing zeros.
+///
+/// Ring operations work for any `Num`. Division, `gcd` and `monic` divide
+/// coefficients, so they are exact only over a field such as `Rational` or
+/// `ModInt` with a prime modulus; with floats they are subject to rounding.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Polynomial<T> {
+    coeffs: Vec<T>,
+}
+
+impl<T: Num> Polynomial<T> {
+    /// Builds `coeffs[0] + coeffs[1]·x + coeffs[2]·x² + …`.
+    pub fn new(mut coeffs: Vec<T>) -> Self {
+        trim(&mut coeffs);
+        Polynomial { coeffs }
+    }
+
+    pub fn zero() -> Self {
+        Polynomial { coeffs: Vec::new() }
+    }
+
+    pub fn constant(value: T) -> Self {
+        Polynomial::new(vec![value])
+    }
+
+    /// The polynomial `x`.
+    pub fn x() -> Self {
+        Polynomial::monomial(T::one(), 1)
+    }
+
+    /// `coeff·x^degree`.
+    pub fn monomial(coeff: T, degree: usize) -> Self {
+        let mut coeffs = vec![T::zero(); degree];
+        coeffs.push(coeff);
+        Polynomial::new(coeffs)
+    }
+
+    /// The coefficients from the constant term upwards; empty for zero.
+    pub fn coeffs(&self) -> &[T] {
+        &self.coeffs
+    }
+
+    /// The degree, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    pub fn leading_coefficient(&self) -> Option<&T> {
+        self.coeffs.last()
+    }
+
+    /// Evaluates at `x` with Horner's scheme: one multiplication and one
+    /// addition per coefficient.
+    pub fn eval(&self, x: T) -> T {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(T::zero(), |acc, c| add(acc.mul(x.clone()), c.clone()))
+    }
+
+    pub fn derivative(&self) -> Self {
+        let mut factor = T::zero();
+        let coeffs = self.coeffs[1.min(self.coeffs.len())..]
+            .iter()
+            .map(|c| {
+                factor = add(factor.clone(), T::one());
+                c.clone().mul(factor.clone())
+            })
+            .collect();
+        Polynomial::new(coeffs)
+    }
+
+    /// Scales so the leading coefficient is one; zero stays zero.
+    pub fn monic(&self) -> Self {
+        match self.leading_coefficient() {
+            Some(lead) => {
+                let coeffs = self.coeffs.iter();
+                Polynomial::new(coeffs.map(|c| c.clone().div(lead.clone())).collect())
+            }
+            None => Polynomial::zero(),
+        }
+    }
+
+    /// Long division: `(q, r)` with `self = q * divisor + r` and `r` of lower
+    /// degree than `divisor`, or `None` if `divisor` is zero.
+    pub fn checked_div_rem(&self, divisor: &Polynomial<T>) -> Option<(Self, Self)> {
+        let lead = divisor.leading_coefficient()?;
+        let m = divisor.coeffs.len();
+        if self.coeffs.len() < m {
+            return Some((Polynomial::zero(), self.clone()));
+        }
+        let mut rem = self.coeffs.clone();
+        let mut quot = vec![T::zero(); rem.len() - m + 1];
+        for i in (0..quot.len()).rev() {
+            let coeff = rem[i + m - 1].clone().div(lead.clone());
+            for (j, d) in divisor.coeffs[..m - 1].iter().enumerate() {
+                rem[i + j] = rem[i + j].clone().sub(coeff.clone().mul(d.clone()));
+            }
+            quot[i] = coeff;
+        }
+        // The leading terms cancel by construction; dropping them rather
+        // than relying on the subtraction keeps float remainders honest.
+        rem.truncate(m - 1);
+        Some((Polynomial::new(quot), Polynomial::new(rem)))
+    }
+
+    pub fn div_rem(&self, divisor: &Polynomial<T>) -> (Self, Self) {
+        self.checked_div_rem(divisor)
+            .expect("attempt to divide by zero")
+    }
+
+    /// The monic greatest common divisor by Euclid's algorithm; the gcd of
+    /// two zero polynomials is zero.
+    pub fn gcd(&self, other: &Polynomial<T>) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = a.div_rem(&b).1;
+            (a, b) = (b, r);
+        }
+        a.monic()
+    }
+
+    fn zip_with(&self, rhs: &Polynomial<T>, f: impl Fn(T, T) -> T) -> Self {
+        let len = self.coeffs.len().max(rhs.coeffs.len());
+        let coeff = |p: &Polynomial<T>, i| p.coeffs.get(i).cloned().unwrap_or_else(T::zero);
+        Polynomial::new((0..len).map(|i| f(coeff(self, i), coeff(rhs, i))).collect())
+    }
+}
+
+/// Drops zero coefficients from the top so the degree is well defined.
+fn trim<T: Num>(coeffs: &mut Vec<T>) {
+    while coeffs.last() == Some(&T::zero()) {
+        coeffs.pop();
+    }
+}
+
+impl<T: Num> Add for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn add(self, rhs: Self) -> Polynomial<T> {
+        self.zip_with(rhs, add)
+    }
+}
+
+impl<T: Num> Sub for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn sub(self, rhs: Self) -> Polynomial<T> {
+        self.zip_with(rhs, T::sub)
+    }
+}
+
+impl<T: Num> Mul for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn mul(self, rhs: Self) -> Polynomial<T> {
+        if self.is_zero() || rhs.is_zero() {
+            return Polynomial::zero();
+        }
+        let mut coeffs = vec![T::zero(); self.coeffs.len() + rhs.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in rhs.coeffs.iter().enumerate() {
+                coeffs[i + j] = add(coeffs[i + j].clone(), a.clone().mul(b.clone()));
+            }
+        }
+        Polynomial::new(coeffs)
+    }
+}
+
+/// The quotient of long division; panics if `rhs` is zero.
+impl<T: Num> Div for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn div(self, rhs: Self) -> Polynomial<T> {
+        self.div_rem(rhs).0
+    }
+}
+
+/// The remainder of long division; panics if `rhs` is zero.
+impl<T: Num> Rem for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn rem(self, rhs: Self) -> Polynomial<T> {
+        self.div_rem(rhs).1
+    }
+}
+
+macro_rules! forward_owned {
+    ($($imp:ident $method:ident)*) => {
+        $(
+            impl<T: Num> $imp for Polynomial<T> {
+                type Output = Polynomial<T>;
+
+                fn $method(self, rhs: Self) -> Polynomial<T> {
+                    $imp::$method(&self, &rhs)
+                }
+            }
+        )*
+    };
+}
+
+forward_owned!(Add add Sub sub Mul mul Div div Rem rem);
+
+impl<T: Num> Neg for Polynomial<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        &Polynomial::zero() - &self
+    }
+}
+
+impl<T: Num> From<T> for Polynomial<T> {
+    fn from(value: T) -> Self {
+        Polynomial::constant(value)
+    }
+}
+
+/// Formats from the highest power down, e.g. `3x^2 + 2x - 1`. Unit
+/// coefficients are left out and a precision applies to each coefficient.
+impl<T: Num + PartialOrd + Display> Display for Polynomial<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let mut first = true;
+        for (degree, coeff) in self.coeffs.iter().enumerate().rev() {
+            if *coeff == T::zero() {
+                continue;
+            }
+            let negative = *coeff < T::zero();
+            match (first, negative) {
+                (true, true) => write!(f, "-")?,
+                (true, false) => {}
+                (false, true) => write!(f, " - ")?,
+                (false, false) => write!(f, " + ")?,
+            }
+            first = false;
+            let magnitude = if negative {
+                T::zero().sub(coeff.clone())
+            } else {
+                coeff.clone()
+            };
+            if degree == 0 || magnitude != T::one() {
+                Display::fmt(&magnitude, f)?;
+            }
+            match degree {
+                0 => {}
+                1 => write!(f, "x")?,
+                _ => write!(f, "x^{degree}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Num> Num for Polynomial<T> {
+    fn zero() -> Self {
+        Polynomial::zero()
+    }
+
+    fn one() -> Self {
+        Polynomial::constant(T::one())
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        self + rhs
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        self - rhs
+    }
+
+    fn mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+
+    fn div(self, rhs: Self) -> Self {
+        self / rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+    use crate::Rational;
+
+    type P = Polynomial<Rational>;
+
+    fn p(coeffs: &[i64]) -> P {
+        Polynomial::new(coeffs.iter().map(|&c| Rational::from(c)).collect())
+    }
+
+    fn random(rng: &mut Rng, max_degree: i64) -> P {
+        let len = rng.range(0, max_degree + 2) as usize;
+        let coeffs = (0..len)
+            .map(|_| Rational::new(rng.range(-20, 20), rng.range(1, 6)))
+            .collect();
+        Polynomial::new(coeffs)
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = p(&[-1, 2, 3]);
+        let b = p(&[1, 1]);
+        assert_eq!(&a + &b, p(&[0, 3, 3]));
+        assert_eq!(&a - &a, P::zero());
+        assert_eq!(&a * &b, p(&[-1, 1, 5, 3]));
+        assert_eq!(-b.clone(), p(&[-1, -1]));
+        assert_eq!(p(&[1, 2, 0, 0]).degree(), Some(1));
+        assert_eq!(P::zero().degree(), None);
+        assert_eq!(P::monomial(Rational::from(4), 3), p(&[0, 0, 0, 4]));
+        assert_eq!(add(P::x(), P::one()), b);
+        // Integer coefficients are fine for the ring operations.
+        let square = Polynomial::new(vec![1, 1]) * Polynomial::new(vec![1, 1]);
+        assert_eq!(square.coeffs(), [1, 2, 1]);
+    }
+
+    #[test]
+    fn division_identity() {
+        let mut rng = Rng(17);
+        for _ in 0..300 {
+            let (a, b) = (random(&mut rng, 8), random(&mut rng, 4));
+            let Some((q, r)) = a.checked_div_rem(&b) else {
+                assert!(b.is_zero());
+                continue;
+            };
+            assert_eq!(&(&q * &b) + &r, a);
+            assert!(r.degree() < b.degree(), "{r} has degree >= {b}");
+            assert_eq!(&a / &b, q);
+            assert_eq!(&a % &b, r);
+        }
+        assert_eq!(P::x().checked_div_rem(&P::zero()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn divide_by_zero() {
+        let _ = P::x() / P::zero();
+    }
+
+    #[test]
+    fn gcd() {
+        // (x - 1)(x - 2) and (x - 1)(x + 3) share exactly x - 1.
+        let a = p(&[2, -3, 1]);
+        let b = p(&[-3, 2, 1]);
+        assert_eq!(a.gcd(&b), p(&[-1, 1]));
+        assert_eq!((&a * &p(&[3])).gcd(&b), p(&[-1, 1]));
+        assert_eq!(a.gcd(&P::zero()), a);
+        assert_eq!(P::zero().gcd(&P::zero()), P::zero());
+        assert_eq!(p(&[1, 1]).gcd(&p(&[-1, 1])), P::one());
+
+        let mut rng = Rng(5);
+        for _ in 0..100 {
+            let (a, b, c) = (
+                random(&mut rng, 3),
+                random(&mut rng, 3),
+                random(&mut rng, 2),
+            );
+            if c.is_zero() {
+                continue;
+            }
+            let g = (&a * &c).gcd(&(&b * &c));
+            assert!((&g % &c.monic()).is_zero(), "{c} does not divide {g}");
+        }
+    }
+
+    #[test]
+    fn horner_and_derivative() {
+        let a = Polynomial::new(vec![-1.0, 2.0, 3.0]);
+        assert_eq!(a.eval(2.0), 15.0);
+        assert_eq!(a.eval(0.0), -1.0);
+        assert_eq!(a.derivative(), Polynomial::new(vec![2.0, 6.0]));
+        assert_eq!(Polynomial::constant(5.0).derivative(), Polynomial::zero());
+        assert_eq!(Polynomial::<f64>::zero().derivative(), Polynomial::zero());
+        assert_eq!(Polynomial::<f64>::zero().eval(3.0), 0.0);
+
+        let mut rng = Rng(9);
+        for _ in 0..100 {
+            let a = random(&mut rng, 6);
+            let x = Rational::new(rng.range(-9, 9), rng.range(1, 4));
+            // The sum of c_i * x^i, each power multiplied out on its own.
+            let naive = a
+                .coeffs()
+                .iter()
+                .enumerate()
+                .fold(Rational::zero(), |acc, (i, c)| {
+                    let power = (0..i).fold(Rational::one(), |power, _| &power * &x);
+                    &acc + &(c * &power)
+                });
+            assert_eq!(a.eval(x), naive);
+            // Product rule.
+            let b = random(&mut rng, 4);
+            let lhs = (&a * &b).derivative();
+            let rhs = &(&a.derivative() * &b) + &(&a * &b.derivative());
+            assert_eq!(lhs, rhs);
+        }
+    }
+
+    #[test]
+    fn display() {
+        let cases: [(&[i64], &str); 7] = [
+            (&[-1, 2, 3], "3x^2 + 2x - 1"),
+            (&[0, -1], "-x"),
+            (&[1, 0, 0, -1], "-x^3 + 1"),
+            (&[0, 0, 1], "x^2"),
+            (&[-5], "-5"),
+            (&[], "0"),
+            (&[1, 1], "x + 1"),
+        ];
+        for (coeffs, expected) in cases {
+            assert_eq!(Polynomial::new(coeffs.to_vec()).to_string(), expected);
+        }
+        assert_eq!(p(&[1, 2]).monic().to_string(), "x + 1/2");
+        assert_eq!(
+            format!("{:.1}", Polynomial::new(vec![0.3, -1.0, 1.5])),
+            "1.5x^2 - x + 0.3"
+        );
+    }
+}
@@ -0,0 +1,480 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use crate::{add, BigInt, BigUint, Num, ParseBigIntError};
+
+/// An exact fraction of two big integers.
+///
+/// Always kept in lowest terms with a positive denominator, so two equal
+/// values have the same representation and `==`/`Hash` can be derived.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: BigInt,
+    denom: BigInt,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRationalError {
+    Numerator(ParseBigIntError),
+    Denominator(ParseBigIntError),
+    ZeroDenominator,
+}
+
+impl Display for ParseRationalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRationalError::Numerator(error) => write!(f, "invalid numerator: {error}"),
+            ParseRationalError::Denominator(error) => write!(f, "invalid denominator: {error}"),
+            ParseRationalError::ZeroDenominator => write!(f, "denominator is zero"),
+        }
+    }
+}
+
+impl Error for ParseRationalError {}
+
+impl Rational {
+    /// Builds `numer / denom` in lowest terms, panicking if `denom` is zero.
+    pub fn new(numer: impl Into<BigInt>, denom: impl Into<BigInt>) -> Self {
+        Rational::checked_new(numer, denom).expect("denominator is zero")
+    }
+
+    pub fn checked_new(numer: impl Into<BigInt>, denom: impl Into<BigInt>) -> Option<Self> {
+        let (numer, denom) = (numer.into(), denom.into());
+        if denom.is_zero() {
+            return None;
+        }
+        Some(Rational::reduce(numer, denom))
+    }
+
+    pub fn from_integer(value: impl Into<BigInt>) -> Self {
+        Rational {
+            numer: value.into(),
+            denom: BigInt::one(),
+        }
+    }
+
+    pub fn zero() -> Self {
+        Rational::from_integer(0)
+    }
+
+    pub fn one() -> Self {
+        Rational::from_integer(1)
+    }
+
+    pub fn numer(&self) -> &BigInt {
+        &self.numer
+    }
+
+    /// The denominator, always positive.
+    pub fn denom(&self) -> &BigInt {
+        &self.denom
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numer.is_zero()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == BigInt::one()
+    }
+
+    pub fn signum(&self) -> i32 {
+        self.numer.signum()
+    }
+
+    pub fn abs(&self) -> Self {
+        Rational {
+            numer: self.numer.abs(),
+            denom: self.denom.clone(),
+        }
+    }
+
+    pub fn checked_recip(&self) -> Option<Self> {
+        Rational::checked_new(self.denom.clone(), self.numer.clone())
+    }
+
+    pub fn recip(&self) -> Self {
+        self.checked_recip().expect("attempt to divide by zero")
+    }
+
+    pub fn checked_div(&self, rhs: &Rational) -> Option<Self> {
+        Some(self * &rhs.checked_recip()?)
+    }
+
+    /// The exact value of a finite float; `None` for NaN and infinities.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        let bits = value.to_bits();
+        let negative = bits >> 63 == 1;
+        let exponent = ((bits >> 52) & 0x7ff) as i64;
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, exponent) = if exponent == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | 1 << 52, exponent - 1075)
+        };
+        let mantissa = BigUint::from(mantissa);
+        let (numer, denom) = if exponent >= 0 {
+            (mantissa.shl(exponent as u64), BigUint::one())
+        } else {
+            (mantissa, BigUint::one().shl(exponent.unsigned_abs()))
+        };
+        Some(Rational::reduce(
+            BigInt::from_parts(negative, numer),
+            BigInt::from(denom),
+        ))
+    }
+
+    /// Approximates `value` by the closest fraction whose denominator is at
+    /// most `max_denominator`, e.g. `from_f64_approx(PI, 1000)` is 355/113.
+    pub fn from_f64_approx(value: f64, max_denominator: u64) -> Option<Self> {
+        Some(Rational::from_f64(value)?.limit_denominator(max_denominator))
+    }
+
+    /// The closest fraction to `self` with a denominator of at most
+    /// `max_denominator`, found from the continued fraction expansion.
+    pub fn limit_denominator(&self, max_denominator: u64) -> Self {
+        assert!(max_denominator > 0, "max_denominator must be positive");
+        let max = BigInt::from(max_denominator);
+        if self.denom <= max {
+            return self.clone();
+        }
+
+        let negative = self.numer.is_negative();
+        let (mut n, mut d) = (self.numer.abs(), self.denom.clone());
+        let (mut p0, mut q0, mut p1, mut q1) =
+            (BigInt::zero(), BigInt::one(), BigInt::one(), BigInt::zero());
+        loop {
+            let (a, r) = n.div_rem(&d);
+            let q2 = add(q0.clone(), &a * &q1);
+            if q2 > max {
+                break;
+            }
+            let p2 = add(p0, &a * &p1);
+            (p0, q0, p1, q1) = (p1, q1, p2, q2);
+            (n, d) = (d, r);
+        }
+
+        // The best approximation is either the last convergent or the
+        // largest semiconvergent that still fits under the bound.
+        let k = (&max - &q0) / &q1;
+        let semi = Rational::new(add(p0, &k * &p1), add(q0, &k * &q1));
+        let convergent = Rational::new(p1, q1);
+        let target = self.abs();
+        let best = if (&convergent - &target).abs() <= (&semi - &target).abs() {
+            convergent
+        } else {
+            semi
+        };
+        if negative {
+            -best
+        } else {
+            best
+        }
+    }
+
+    /// The nearest `f64`, correct to within one unit in the last place.
+    pub fn to_f64(&self) -> f64 {
+        if self.is_zero() {
+            return 0.0;
+        }
+        let (n, d) = (self.numer.magnitude(), self.denom.magnitude());
+        // Scale so the integer quotient carries 64-65 significant bits.
+        let shift = 65 - (n.bits() as i64 - d.bits() as i64);
+        let quotient = if shift >= 0 {
+            n.shl(shift as u64) / d
+        } else {
+            n / d.shl(shift.unsigned_abs())
+        };
+        let magnitude = scale(quotient.to_f64(), -shift);
+        if self.numer.is_negative() {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    fn reduce(numer: BigInt, denom: BigInt) -> Self {
+        let gcd = BigInt::from(numer.magnitude().gcd(denom.magnitude()));
+        let (numer, denom) = (&numer / &gcd, &denom / &gcd);
+        if denom.is_negative() {
+            Rational {
+                numer: -numer,
+                denom: -denom,
+            }
+        } else {
+            Rational { numer, denom }
+        }
+    }
+}
+
+/// `value * 2^exp` without overflowing the intermediate power of two.
+fn scale(mut value: f64, mut exp: i64) -> f64 {
+    while exp > 1000 {
+        value *= 2f64.powi(1000);
+        exp -= 1000;
+    }
+    while exp < -1000 {
+        value *= 2f64.powi(-1000);
+        exp += 1000;
+    }
+    value * 2f64.powi(exp as i32)
+}
+
+forward_binop!(Rational, Add, add, |a, b| Rational::reduce(
+    add(&a.numer * &b.denom, &b.numer * &a.denom),
+    &a.denom * &b.denom
+));
+forward_binop!(Rational, Sub, sub, |a, b| Rational::reduce(
+    add(&a.numer * &b.denom, -(&b.numer * &a.denom)),
+    &a.denom * &b.denom
+));
+forward_binop!(Rational, Mul, mul, |a, b| Rational::reduce(
+    &a.numer * &b.numer,
+    &a.denom * &b.denom
+));
+forward_binop!(Rational, Div, div, |a, b| a
+    .checked_div(b)
+    .expect("attempt to divide by zero"));
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        -self.clone()
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.numer * &other.denom).cmp(&(&other.numer * &self.denom))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty)*) => {
+        $(
+            impl From<$t> for Rational {
+                fn from(value: $t) -> Self {
+                    Rational::from_integer(value)
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize BigUint BigInt);
+
+/// Formats as `a/b`, or just `a` when the value is an integer.
+impl Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            f.pad(&self.numer.to_string())
+        } else {
+            f.pad(&format!("{}/{}", self.numer, self.denom))
+        }
+    }
+}
+
+/// Parses `a/b` or a bare integer `a`; the result is reduced.
+impl FromStr for Rational {
+    type Err = ParseRationalError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let (numer, denom) = src.split_once('/').unwrap_or((src, "1"));
+        let numer: BigInt = numer.parse().map_err(ParseRationalError::Numerator)?;
+        let denom: BigInt = denom.parse().map_err(ParseRationalError::Denominator)?;
+        Rational::checked_new(numer, denom).ok_or(ParseRationalError::ZeroDenominator)
+    }
+}
+
+impl Num for Rational {
+    fn zero() -> Self {
+        Rational::zero()
+    }
+
+    fn one() -> Self {
+        Rational::one()
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        self + rhs
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        self - rhs
+    }
+
+    fn mul(self, rhs: Self) -> Self {
+        self * rhs
+    }
+
+    fn div(self, rhs: Self) -> Self {
+        self / rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+
+    fn r(numer: i64, denom: i64) -> Rational {
+        Rational::new(numer, denom)
+    }
+
+    fn random(rng: &mut Rng) -> Rational {
+        let denom = match rng.range(-1000, 1000) {
+            0 => 1,
+            denom => denom,
+        };
+        r(rng.range(-1000, 1000), denom)
+    }
+
+    #[test]
+    fn billing_split() {
+        assert_eq!(r(1, 3) + r(1, 6), r(1, 2));
+        assert_eq!(add(r(1, 3), r(1, 6)).to_string(), "1/2");
+        assert_eq!(r(1, 3) * Rational::from(3), Rational::one());
+    }
+
+    #[test]
+    fn reduction_is_canonical() {
+        let mut rng = Rng(0x5eed);
+        for _ in 0..2000 {
+            let numer = rng.range(-10_000, 10_000);
+            let denom = match rng.range(-10_000, 10_000) {
+                0 => 1,
+                denom => denom,
+            };
+            let k = match rng.range(-500, 500) {
+                0 => 7,
+                k => k,
+            };
+            let value = r(numer, denom);
+            assert_eq!(r(numer * k, denom * k), value);
+            assert!(!value.denom().is_negative() && !value.denom().is_zero());
+            assert_eq!(
+                value.numer().magnitude().gcd(value.denom().magnitude()),
+                BigUint::one()
+            );
+            assert_eq!(value.to_string().parse::<Rational>(), Ok(value));
+        }
+        assert_eq!(r(0, -5), Rational::zero());
+        assert_eq!(r(0, -5).denom(), &BigInt::one());
+    }
+
+    #[test]
+    fn field_laws() {
+        let mut rng = Rng(99);
+        for _ in 0..500 {
+            let (a, b, c) = (random(&mut rng), random(&mut rng), random(&mut rng));
+            assert_eq!(&a + &b, &b + &a);
+            assert_eq!(&(&a + &b) + &c, &a + &(&b + &c));
+            assert_eq!(&a * &(&b + &c), &(&a * &b) + &(&a * &c));
+            assert_eq!(&a - &a, Rational::zero());
+            if !b.is_zero() {
+                assert_eq!(&(&a / &b) * &b, a);
+            }
+            let reduced = [&a + &b, &a - &b, &a * &b];
+            for value in reduced {
+                assert_eq!(
+                    value.numer().magnitude().gcd(value.denom().magnitude()),
+                    BigUint::one()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ordering() {
+        let mut rng = Rng(5);
+        for _ in 0..500 {
+            let (a, b, c, d) = (
+                rng.range(-100, 100),
+                rng.range(1, 100),
+                rng.range(-100, 100),
+                rng.range(1, 100),
+            );
+            assert_eq!(r(a, b).cmp(&r(c, d)), (a * d).cmp(&(c * b)));
+        }
+        assert!(r(-1, 2) < r(1, 3));
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!("6/-4".parse(), Ok(r(-3, 2)));
+        assert_eq!("+7".parse(), Ok(Rational::from(7)));
+        assert_eq!(
+            "1/0".parse::<Rational>(),
+            Err(ParseRationalError::ZeroDenominator)
+        );
+        assert_eq!(
+            "1/x".parse::<Rational>(),
+            Err(ParseRationalError::Denominator(
+                ParseBigIntError::InvalidDigit {
+                    index: 0,
+                    found: 'x'
+                }
+            ))
+        );
+        assert!(matches!(
+            "/2".parse::<Rational>(),
+            Err(ParseRationalError::Numerator(ParseBigIntError::Empty))
+        ));
+        assert_eq!(format!("{:>6}", r(-1, 2)), "  -1/2");
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(
+            Rational::from_f64(0.1),
+            Some(Rational::new(3602879701896397u64, 36028797018963968u64))
+        );
+        assert_eq!(Rational::from_f64(-0.75), Some(r(-3, 4)));
+        assert_eq!(Rational::from_f64(f64::NAN), None);
+        assert_eq!(
+            Rational::from_f64_approx(std::f64::consts::PI, 1000),
+            Some(r(355, 113))
+        );
+        assert_eq!(Rational::from_f64_approx(-0.1, 100), Some(r(-1, 10)));
+        assert_eq!(r(1, 3).limit_denominator(2), r(1, 2));
+        assert_eq!(r(1, 3).to_f64(), 1.0 / 3.0);
+
+        let mut rng = Rng(11);
+        for _ in 0..1000 {
+            let value = f64::from_bits(rng.next());
+            if value.is_finite() {
+                assert_eq!(Rational::from_f64(value).unwrap().to_f64(), value);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn divide_by_zero() {
+        let _ = r(1, 2) / Rational::zero();
+    }
+}
@@ -0,0 +1,271 @@
+use crate::{wrapping_add, Num};
+
+/// An element type that `add_slices` can add lane by lane.
+///
+/// Integer lanes wrap on overflow, like `wrapping_add`, so every code path
+/// gives the same bits in debug and release builds. Float lanes are plain
+/// IEEE addition, exactly like `add`.
+pub trait Lane: Num + Copy + Send + Sync {
+    fn lane_add(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_lane {
+    (wrapping: $($t:ty)*) => {
+        $(
+            impl Lane for $t {
+                #[inline(always)]
+                fn lane_add(self, rhs: Self) -> Self {
+                    wrapping_add(self, rhs)
+                }
+            }
+        )*
+    };
+    (float: $($t:ty)*) => {
+        $(
+            impl Lane for $t {
+                #[inline(always)]
+                fn lane_add(self, rhs: Self) -> Self {
+                    self + rhs
+                }
+            }
+        )*
+    };
+}
+
+impl_lane!(wrapping: u8 u16 u32 u64);
+impl_lane!(float: f32 f64);
+
+/// The instruction set a slice kernel is compiled for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Backend {
+    Scalar,
+    Sse2,
+    Avx2,
+}
+
+impl Backend {
+    /// The fastest backend the running CPU supports.
+    pub fn detect() -> Backend {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx2") {
+                return Backend::Avx2;
+            }
+            if is_x86_feature_detected!("sse2") {
+                return Backend::Sse2;
+            }
+        }
+        Backend::Scalar
+    }
+
+    pub fn is_supported(self) -> bool {
+        match self {
+            Backend::Scalar => true,
+            #[cfg(target_arch = "x86_64")]
+            Backend::Sse2 => is_x86_feature_detected!("sse2"),
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 => is_x86_feature_detected!("avx2"),
+            #[cfg(not(target_arch = "x86_64"))]
+            _ => false,
+        }
+    }
+}
+
+/// Writes `a[i] + b[i]` to `out[i]` using the best backend for this CPU.
+///
+/// Panics if the three slices differ in length.
+pub fn add_slices<T: Lane>(a: &[T], b: &[T], out: &mut [T]) {
+    add_slices_with(Backend::detect(), a, b, out)
+}
+
+/// Adds `other[i]` to `acc[i]` in place using the best backend for this CPU.
+///
+/// Panics if the slices differ in length.
+pub fn add_assign_slices<T: Lane>(acc: &mut [T], other: &[T]) {
+    add_assign_slices_with(Backend::detect(), acc, other)
+}
+
+/// `add_slices` on an explicit backend; panics if the CPU lacks it.
+pub fn add_slices_with<T: Lane>(backend: Backend, a: &[T], b: &[T], out: &mut [T]) {
+    assert!(
+        a.len() == b.len() && a.len() == out.len(),
+        "slice lengths differ: {} + {} -> {}",
+        a.len(),
+        b.len(),
+        out.len()
+    );
+    assert!(
+        backend.is_supported(),
+        "{backend:?} is not supported by this CPU"
+    );
+    match backend {
+        Backend::Scalar => add_kernel(a, b, out),
+        // SAFETY: support for the target feature was checked above.
+        #[cfg(target_arch = "x86_64")]
+        Backend::Sse2 => unsafe { x86::add_sse2(a, b, out) },
+        #[cfg(target_arch = "x86_64")]
+        Backend::Avx2 => unsafe { x86::add_avx2(a, b, out) },
+        #[cfg(not(target_arch = "x86_64"))]
+        _ => unreachable!(),
+    }
+}
+
+/// `add_assign_slices` on an explicit backend; panics if the CPU lacks it.
+pub fn add_assign_slices_with<T: Lane>(backend: Backend, acc: &mut [T], other: &[T]) {
+    assert!(
+        acc.len() == other.len(),
+        "slice lengths differ: {} += {}",
+        acc.len(),
+        other.len()
+    );
+    assert!(
+        backend.is_supported(),
+        "{backend:?} is not supported by this CPU"
+    );
+    match backend {
+        Backend::Scalar => add_assign_kernel(acc, other),
+        // SAFETY: support for the target feature was checked above.
+        #[cfg(target_arch = "x86_64")]
+        Backend::Sse2 => unsafe { x86::add_assign_sse2(acc, other) },
+        #[cfg(target_arch = "x86_64")]
+        Backend::Avx2 => unsafe { x86::add_assign_avx2(acc, other) },
+        #[cfg(not(target_arch = "x86_64"))]
+        _ => unreachable!(),
+    }
+}
+
+// The kernels are plain zipped loops with no bounds checks or early exits,
+// which LLVM vectorizes to whatever vector width the caller is compiled for.
+// They are always inlined so each `target_feature` wrapper gets its own copy.
+
+#[inline(always)]
+fn add_kernel<T: Lane>(a: &[T], b: &[T], out: &mut [T]) {
+    for ((out, &a), &b) in out.iter_mut().zip(a).zip(b) {
+        *out = a.lane_add(b);
+    }
+}
+
+#[inline(always)]
+fn add_assign_kernel<T: Lane>(acc: &mut [T], other: &[T]) {
+    for (acc, &other) in acc.iter_mut().zip(other) {
+        *acc = acc.lane_add(other);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use super::{add_assign_kernel, add_kernel, Lane};
+
+    #[target_feature(enable = "sse2")]
+    pub(super) unsafe fn add_sse2<T: Lane>(a: &[T], b: &[T], out: &mut [T]) {
+        add_kernel(a, b, out)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn add_avx2<T: Lane>(a: &[T], b: &[T], out: &mut [T]) {
+        add_kernel(a, b, out)
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(super) unsafe fn add_assign_sse2<T: Lane>(acc: &mut [T], other: &[T]) {
+        add_assign_kernel(acc, other)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn add_assign_avx2<T: Lane>(acc: &mut [T], other: &[T]) {
+        add_assign_kernel(acc, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::add;
+    use crate::test_util::Rng;
+
+    const BACKENDS: [Backend; 3] = [Backend::Scalar, Backend::Sse2, Backend::Avx2];
+
+    /// Runs both slice functions on every supported backend and checks each
+    /// lane against `expected`, compared bit for bit via `bits`.
+    fn check<T: Lane>(a: &[T], b: &[T], expected: impl Fn(T, T) -> T, bits: impl Fn(T) -> u64) {
+        for backend in BACKENDS.into_iter().filter(|b| b.is_supported()) {
+            let mut out = vec![T::zero(); a.len()];
+            add_slices_with(backend, a, b, &mut out);
+            let mut acc = a.to_vec();
+            add_assign_slices_with(backend, &mut acc, b);
+            for i in 0..a.len() {
+                let want = bits(expected(a[i], b[i]));
+                assert_eq!(bits(out[i]), want, "{backend:?} lane {i}");
+                assert_eq!(bits(acc[i]), want, "{backend:?} lane {i} in place");
+            }
+        }
+    }
+
+    macro_rules! check_ints {
+        ($rng:ident; $($t:ty)*) => {
+            $(
+                // Odd lengths leave a remainder after any vector width.
+                for len in [0, 1, 7, 31, 64, 1001] {
+                    let a: Vec<$t> = (0..len).map(|_| $rng.next() as $t).collect();
+                    let b: Vec<$t> = (0..len).map(|_| $rng.next() as $t).collect();
+                    check(&a, &b, wrapping_add, |x| x as u64);
+
+                    let small: Vec<$t> = a.iter().map(|x| x / 2).collect();
+                    let half: Vec<$t> = b.iter().map(|x| x / 2).collect();
+                    check(&small, &half, add, |x| x as u64);
+                }
+            )*
+        };
+    }
+
+    #[test]
+    fn integers_match_scalar_add() {
+        let mut rng = Rng(8);
+        check_ints!(rng; u8 u16 u32 u64);
+    }
+
+    #[test]
+    fn floats_match_scalar_add() {
+        let mut rng = Rng(9);
+        for len in [0, 1, 7, 31, 64, 1001] {
+            let a: Vec<f64> = (0..len).map(|_| f64::from_bits(rng.next())).collect();
+            let b: Vec<f64> = (0..len).map(|_| f64::from_bits(rng.next())).collect();
+            check(&a, &b, add, f64::to_bits);
+
+            let a: Vec<f32> = (0..len)
+                .map(|_| f32::from_bits(rng.next() as u32))
+                .collect();
+            let b: Vec<f32> = (0..len)
+                .map(|_| f32::from_bits(rng.next() as u32))
+                .collect();
+            check(&a, &b, add, |x| x.to_bits() as u64);
+        }
+        let specials = [
+            0.0,
+            -0.0,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::MIN_POSITIVE / 2.0,
+        ];
+        check(
+            &specials,
+            &[-0.0, -0.0, f64::NEG_INFINITY, 1.0, f64::MIN_POSITIVE / 2.0],
+            add,
+            f64::to_bits,
+        );
+    }
+
+    #[test]
+    fn detected_backend_is_supported() {
+        assert!(Backend::detect().is_supported());
+        let mut out = [0u8; 3];
+        add_slices(&[1, 2, 255], &[1, 2, 2], &mut out);
+        assert_eq!(out, [2, 4, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "slice lengths differ: 2 + 3 -> 2")]
+    fn length_mismatch() {
+        add_slices(&[1.0, 2.0], &[1.0, 2.0, 3.0], &mut [0.0; 2]);
+    }
+}
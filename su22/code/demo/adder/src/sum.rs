@@ -0,0 +1,323 @@
+use crate::{checked_add, AddError, Decimal, Int, Num, Rational};
+
+/// The order in which `sum_with` adds values up.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SumMode {
+    /// Left to right through the type's `Accumulator`: compensated for
+    /// floats, overflow-checked for integers.
+    #[default]
+    Sequential,
+    /// Balanced binary tree of `Summable::combine` calls, which keeps the
+    /// rounding error of plain float addition to O(log n).
+    Pairwise,
+}
+
+/// A running total that values are pushed into one at a time.
+pub trait Accumulator<T>: Default {
+    fn push(&mut self, value: T) -> Result<(), AddError>;
+    /// Folds in another partial total as if its values had been pushed here.
+    fn merge(&mut self, other: Self) -> Result<(), AddError>;
+    fn total(self) -> T;
+}
+
+/// A number that can be summed over an iterator.
+pub trait Summable: Num {
+    type Accumulator: Accumulator<Self>;
+
+    /// Adds two partial sums.
+    fn combine(left: Self, right: Self) -> Result<Self, AddError>;
+}
+
+/// Integer total that fails as soon as a partial sum overflows.
+#[derive(Clone, Debug)]
+pub struct CheckedSum<T>(T);
+
+impl<T: Num> Default for CheckedSum<T> {
+    fn default() -> Self {
+        CheckedSum(T::zero())
+    }
+}
+
+impl<T: Int> Accumulator<T> for CheckedSum<T> {
+    fn push(&mut self, value: T) -> Result<(), AddError> {
+        self.0 = checked_add(self.0.clone(), value)?;
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Self) -> Result<(), AddError> {
+        self.push(other.0)
+    }
+
+    fn total(self) -> T {
+        self.0
+    }
+}
+
+/// Float total with Neumaier's improved Kahan compensation: the low-order
+/// bits lost by each addition are collected separately and added back at
+/// the end.
+#[derive(Clone, Debug, Default)]
+pub struct CompensatedSum<T> {
+    sum: T,
+    compensation: T,
+}
+
+macro_rules! impl_float_sum {
+    ($($t:ty)*) => {
+        $(
+            impl Accumulator<$t> for CompensatedSum<$t> {
+                fn push(&mut self, value: $t) -> Result<(), AddError> {
+                    let sum = self.sum + value;
+                    if self.sum.abs() >= value.abs() {
+                        self.compensation += (self.sum - sum) + value;
+                    } else {
+                        self.compensation += (value - sum) + self.sum;
+                    }
+                    self.sum = sum;
+                    Ok(())
+                }
+
+                fn merge(&mut self, other: Self) -> Result<(), AddError> {
+                    self.push(other.sum)?;
+                    self.compensation += other.compensation;
+                    Ok(())
+                }
+
+                fn total(self) -> $t {
+                    // Once the sum is infinite or NaN the compensation is
+                    // meaningless (and usually NaN itself).
+                    if self.sum.is_finite() {
+                        self.sum + self.compensation
+                    } else {
+                        self.sum
+                    }
+                }
+            }
+
+            impl Summable for $t {
+                type Accumulator = CompensatedSum<$t>;
+
+                fn combine(left: Self, right: Self) -> Result<Self, AddError> {
+                    Ok(left + right)
+                }
+            }
+        )*
+    };
+}
+
+impl_float_sum!(f32 f64);
+
+/// Total for types whose addition is already exact.
+#[derive(Clone, Debug)]
+pub struct ExactSum<T>(T);
+
+impl<T: Num> Default for ExactSum<T> {
+    fn default() -> Self {
+        ExactSum(T::zero())
+    }
+}
+
+impl<T: Num> Accumulator<T> for ExactSum<T> {
+    fn push(&mut self, value: T) -> Result<(), AddError> {
+        self.0 = self.0.clone().add(value);
+        Ok(())
+    }
+
+    fn merge(&mut self, other: Self) -> Result<(), AddError> {
+        self.push(other.0)
+    }
+
+    fn total(self) -> T {
+        self.0
+    }
+}
+
+macro_rules! impl_summable {
+    ($accumulator:ident; $($t:ty)*) => {
+        $(
+            impl Summable for $t {
+                type Accumulator = $accumulator<$t>;
+
+                fn combine(left: Self, right: Self) -> Result<Self, AddError> {
+                    let mut total = $accumulator(left);
+                    total.push(right)?;
+                    Ok(total.total())
+                }
+            }
+        )*
+    };
+}
+
+impl_summable!(CheckedSum; u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+impl_summable!(CheckedSum; crate::BigUint crate::BigInt);
+impl_summable!(ExactSum; Rational Decimal);
+
+/// Sums `values` sequentially; see `SumMode::Sequential`.
+///
+/// Integer sums fail with `AddError::Overflow` if any partial sum overflows,
+/// even when the final total would fit.
+pub fn sum<T: Summable, I: IntoIterator<Item = T>>(values: I) -> Result<T, AddError> {
+    sum_with(values, SumMode::Sequential)
+}
+
+pub fn sum_with<T: Summable, I: IntoIterator<Item = T>>(
+    values: I,
+    mode: SumMode,
+) -> Result<T, AddError> {
+    match mode {
+        SumMode::Sequential => {
+            let mut total = T::Accumulator::default();
+            for value in values {
+                total.push(value)?;
+            }
+            Ok(total.total())
+        }
+        SumMode::Pairwise => pairwise(values),
+    }
+}
+
+/// Streams values into a balanced reduction tree, keeping one pending
+/// partial sum per level: the n-th value merges like a binary counter.
+fn pairwise<T: Summable, I: IntoIterator<Item = T>>(values: I) -> Result<T, AddError> {
+    let mut stack: Vec<(u32, T)> = Vec::new();
+    for value in values {
+        let (mut level, mut value) = (0, value);
+        while stack.last().is_some_and(|(top, _)| *top == level) {
+            let (_, left) = stack.pop().unwrap();
+            value = T::combine(left, value)?;
+            level += 1;
+        }
+        stack.push((level, value));
+    }
+    let mut total = match stack.pop() {
+        Some((_, value)) => value,
+        None => return Ok(T::zero()),
+    };
+    while let Some((_, left)) = stack.pop() {
+        total = T::combine(left, total)?;
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+
+    fn naive(values: &[f64]) -> f64 {
+        values.iter().fold(0.0, |total, &value| total + value)
+    }
+
+    #[test]
+    fn cancellation() {
+        let values = [1e16, 1.0, -1e16];
+        assert_eq!(naive(&values), 0.0);
+        assert_eq!(sum(values), Ok(1.0));
+
+        let values = [1.0, 1e100, 1.0, -1e100];
+        assert_eq!(naive(&values), 0.0);
+        assert_eq!(sum(values), Ok(2.0));
+    }
+
+    #[test]
+    fn repeated_tenths() {
+        let values = [0.1; 10];
+        assert_eq!(naive(&values), 0.9999999999999999);
+        assert_eq!(sum(values), Ok(1.0));
+        assert_eq!(sum_with(values, SumMode::Pairwise), Ok(1.0));
+    }
+
+    #[test]
+    fn pairwise_beats_naive() {
+        let values = vec![0.1f32; 1 << 20];
+        let exact = 0.1f32 as f64 * (1 << 20) as f64;
+        let naive = values.iter().fold(0.0f32, |total, &value| total + value);
+        let pairwise = sum_with(values.iter().copied(), SumMode::Pairwise).unwrap();
+        let compensated = sum(values).unwrap();
+        let error = |value: f32| (value as f64 - exact).abs();
+        assert!(
+            error(pairwise) * 1000.0 < error(naive),
+            "{pairwise} vs {naive}"
+        );
+        assert!(
+            error(compensated) * 100.0 < error(naive),
+            "{compensated} vs {naive}"
+        );
+    }
+
+    #[test]
+    fn random_magnitudes() {
+        let mut rng = Rng(17);
+        let values: Vec<f64> = (0..10_000)
+            .map(|_| {
+                let mantissa = rng.range(-1_000_000, 1_000_000) as f64;
+                mantissa * 2f64.powi(rng.range(-40, 40) as i32)
+            })
+            .collect();
+        // Every value is a multiple of 2^-40 well inside 2^100, so the exact
+        // sum fits in an i128 count of 2^-40 units.
+        let exact: i128 = values.iter().map(|v| (v * 2f64.powi(40)) as i128).sum();
+        let exact = exact as f64 / 2f64.powi(40);
+        let error = |value: f64| (value - exact).abs();
+        let compensated = sum(values.iter().copied()).unwrap();
+        assert!(error(compensated) <= exact.abs() * f64::EPSILON);
+        assert!(error(compensated) <= error(naive(&values)));
+    }
+
+    #[test]
+    fn non_finite() {
+        assert_eq!(sum([f64::MAX, f64::MAX]), Ok(f64::INFINITY));
+        assert!(sum([f64::INFINITY, f64::NEG_INFINITY]).unwrap().is_nan());
+        assert!(sum([1.0, f64::NAN]).unwrap().is_nan());
+    }
+
+    #[test]
+    fn checked_integers() {
+        assert_eq!(sum([1u8, 2, 3]), Ok(6));
+        assert_eq!(sum(Vec::<i32>::new()), Ok(0));
+        assert!(sum([u8::MAX, 1]).is_err());
+        assert!(sum([100i8, 100, -100]).is_err());
+        assert_eq!(sum([100i8, -100, 100]), Ok(100));
+        assert_eq!(
+            sum_with([u64::MAX, 0, 0, 0, 0], SumMode::Pairwise),
+            Ok(u64::MAX)
+        );
+        assert!(sum_with([u64::MAX, 0, 0, 1], SumMode::Pairwise).is_err());
+    }
+
+    #[test]
+    fn pairwise_matches_sequential_for_exact_types() {
+        for len in 0..40i64 {
+            let values: Vec<Rational> = (1..=len).map(|n| Rational::new(1, n)).collect();
+            assert_eq!(
+                sum_with(values.clone(), SumMode::Pairwise),
+                sum_with(values, SumMode::Sequential)
+            );
+            let ints: Vec<i64> = (0..len).collect();
+            assert_eq!(
+                sum_with(ints.iter().copied(), SumMode::Pairwise),
+                Ok(len * (len - 1) / 2)
+            );
+        }
+        let cents: Vec<Decimal> = ["0.10", "0.20", "0.30"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        assert_eq!(sum(cents).unwrap().to_string(), "0.60");
+    }
+
+    #[test]
+    fn merge_matches_push() {
+        let values = [1e16, 1.0, -1e16, 3.5, 0.25];
+        let mut left = CompensatedSum::default();
+        let mut right = CompensatedSum::default();
+        for &value in &values[..2] {
+            left.push(value).unwrap();
+        }
+        for &value in &values[2..] {
+            right.push(value).unwrap();
+        }
+        left.merge(right).unwrap();
+        assert_eq!(left.total(), sum(values).unwrap());
+    }
+}
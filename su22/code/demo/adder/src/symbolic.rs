@@ -0,0 +1,540 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use crate::{add, BinOp, Expr, Func};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiffError {
+    /// The function has no derivative we can write as a formula.
+    NotDifferentiable(Func),
+}
+
+impl Display for DiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffError::NotDifferentiable(func) => {
+                write!(f, "`{}` is not differentiable", func.name())
+            }
+        }
+    }
+}
+
+impl Error for DiffError {}
+
+/// Simplification repeats until nothing changes; this bounds the passes in
+/// case two rewrites ever undo each other.
+const MAX_PASSES: usize = 16;
+
+impl Expr {
+    /// The simplified derivative with respect to `var`.
+    pub fn derivative(&self, var: &str) -> Result<Expr, DiffError> {
+        Ok(self.diff(var)?.simplify())
+    }
+
+    /// Rewrites into an equivalent, usually shorter form: folds constants,
+    /// drops `+ 0`, `* 1` and `^ 1`, and collects like terms and powers,
+    /// so `x * x + 2 * x - x` becomes `x^2 + x`.
+    ///
+    /// Like the textbook rules it assumes `x / x` is 1 and `0 * x` is 0, so
+    /// the result can be defined where the input was not.
+    pub fn simplify(&self) -> Expr {
+        let mut current = self.clone();
+        for _ in 0..MAX_PASSES {
+            let next = current.simplify_once();
+            if next == current {
+                break;
+            }
+            current = next;
+        }
+        current
+    }
+
+    /// Whether `var` occurs anywhere in the expression.
+    pub fn contains_var(&self, var: &str) -> bool {
+        match self {
+            Expr::Number(_) => false,
+            Expr::Var(name) => name == var,
+            Expr::Neg(operand) => operand.contains_var(var),
+            Expr::Binary(_, a, b) => a.contains_var(var) || b.contains_var(var),
+            Expr::Call(_, args) => args.iter().any(|arg| arg.contains_var(var)),
+        }
+    }
+
+    fn diff(&self, var: &str) -> Result<Expr, DiffError> {
+        if !self.contains_var(var) {
+            return Ok(num(0.0));
+        }
+        Ok(match self {
+            Expr::Number(_) => num(0.0),
+            Expr::Var(_) => num(1.0),
+            Expr::Neg(u) => neg(u.diff(var)?),
+            Expr::Binary(op, u, v) => {
+                let (u, v) = (&**u, &**v);
+                match op {
+                    BinOp::Add => bin(BinOp::Add, u.diff(var)?, v.diff(var)?),
+                    BinOp::Sub => bin(BinOp::Sub, u.diff(var)?, v.diff(var)?),
+                    BinOp::Mul => bin(
+                        BinOp::Add,
+                        bin(BinOp::Mul, u.diff(var)?, v.clone()),
+                        bin(BinOp::Mul, u.clone(), v.diff(var)?),
+                    ),
+                    BinOp::Div => bin(
+                        BinOp::Div,
+                        bin(
+                            BinOp::Sub,
+                            bin(BinOp::Mul, u.diff(var)?, v.clone()),
+                            bin(BinOp::Mul, u.clone(), v.diff(var)?),
+                        ),
+                        bin(BinOp::Pow, v.clone(), num(2.0)),
+                    ),
+                    BinOp::Pow if !v.contains_var(var) => bin(
+                        BinOp::Mul,
+                        bin(
+                            BinOp::Mul,
+                            v.clone(),
+                            bin(BinOp::Pow, u.clone(), bin(BinOp::Sub, v.clone(), num(1.0))),
+                        ),
+                        u.diff(var)?,
+                    ),
+                    // d(u^v) = u^v * (v' ln u + v u' / u)
+                    BinOp::Pow => bin(
+                        BinOp::Mul,
+                        self.clone(),
+                        bin(
+                            BinOp::Add,
+                            bin(BinOp::Mul, v.diff(var)?, call(Func::Ln, u.clone())),
+                            bin(
+                                BinOp::Div,
+                                bin(BinOp::Mul, v.clone(), u.diff(var)?),
+                                u.clone(),
+                            ),
+                        ),
+                    ),
+                }
+            }
+            Expr::Call(func, args) => {
+                let u = &args[0];
+                let outer = match func {
+                    Func::Sin => call(Func::Cos, u.clone()),
+                    Func::Cos => neg(call(Func::Sin, u.clone())),
+                    Func::Exp => self.clone(),
+                    Func::Ln => bin(BinOp::Div, num(1.0), u.clone()),
+                    Func::Sqrt => bin(
+                        BinOp::Div,
+                        num(1.0),
+                        bin(BinOp::Mul, num(2.0), self.clone()),
+                    ),
+                    Func::Abs => bin(BinOp::Div, u.clone(), self.clone()),
+                    Func::Min | Func::Max => return Err(DiffError::NotDifferentiable(*func)),
+                };
+                bin(BinOp::Mul, outer, u.diff(var)?)
+            }
+        })
+    }
+
+    /// One bottom-up pass: children first, then the rules for this node.
+    fn simplify_once(&self) -> Expr {
+        match self {
+            Expr::Number(_) | Expr::Var(_) => self.clone(),
+            Expr::Neg(_) | Expr::Binary(BinOp::Add | BinOp::Sub, ..) => {
+                let mut sum = Sum::default();
+                sum.collect(self, 1.0);
+                sum.into_expr()
+            }
+            Expr::Binary(BinOp::Mul | BinOp::Div, ..) => {
+                let mut product = Product::new();
+                product.collect(self, 1.0);
+                product.into_expr()
+            }
+            Expr::Binary(BinOp::Pow, base, exponent) => {
+                power(base.simplify_once(), exponent.simplify_once())
+            }
+            Expr::Call(func, args) => {
+                let args: Vec<Expr> = args.iter().map(Expr::simplify_once).collect();
+                let values: Option<Vec<f64>> = args.iter().map(Expr::as_number).collect();
+                match values.map(|values| func.apply(&values)) {
+                    Some(value) if value.is_finite() && value.fract() == 0.0 => num(value),
+                    _ => Expr::Call(*func, args),
+                }
+            }
+        }
+    }
+
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Expr::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+fn num(value: f64) -> Expr {
+    Expr::Number(value)
+}
+
+fn neg(operand: Expr) -> Expr {
+    Expr::Neg(Box::new(operand))
+}
+
+fn bin(op: BinOp, a: Expr, b: Expr) -> Expr {
+    Expr::Binary(op, Box::new(a), Box::new(b))
+}
+
+fn call(func: Func, arg: Expr) -> Expr {
+    Expr::Call(func, vec![arg])
+}
+
+/// Whether a folded constant is short enough to print in place of the
+/// expression that produced it; `1 / 4` folds, `1 / 3` stays a fraction.
+fn prints_short(value: f64) -> bool {
+    value.is_finite() && (value * 1e6).fract() == 0.0
+}
+
+fn power(base: Expr, exponent: Expr) -> Expr {
+    match (base, exponent) {
+        (Expr::Number(a), Expr::Number(b)) if prints_short(a.powf(b)) => num(a.powf(b)),
+        (_, Expr::Number(0.0)) => num(1.0),
+        (base, Expr::Number(1.0)) => base,
+        (Expr::Number(1.0), _) => num(1.0),
+        (Expr::Binary(BinOp::Pow, inner, a), Expr::Number(b)) if a.as_number().is_some() => {
+            let a = a.as_number().unwrap();
+            // An even power drops the sign and a fractional one cannot bring
+            // it back: `(x^2)^0.5` is `abs(x)`, not `x`.
+            let inner = if a % 2.0 == 0.0 && b.fract() != 0.0 {
+                call(Func::Abs, *inner)
+            } else {
+                *inner
+            };
+            power(inner, num(a * b))
+        }
+        (base, exponent) => bin(BinOp::Pow, base, exponent),
+    }
+}
+
+/// A product `coeff * base1^exp1 * base2^exp2 ...` with each base listed
+/// once, so repeated factors combine into one power.
+struct Product {
+    coeff: f64,
+    factors: Vec<(Expr, f64)>,
+}
+
+impl Product {
+    fn new() -> Self {
+        Product {
+            coeff: 1.0,
+            factors: Vec::new(),
+        }
+    }
+
+    /// Multiplies in `expr^exponent`, where `exponent` is 1 or -1 for the
+    /// two sides of a division.
+    fn collect(&mut self, expr: &Expr, exponent: f64) {
+        match expr {
+            Expr::Binary(BinOp::Mul, a, b) => {
+                self.collect(a, exponent);
+                self.collect(b, exponent);
+            }
+            Expr::Binary(BinOp::Div, a, b) => {
+                self.collect(a, exponent);
+                self.collect(b, -exponent);
+            }
+            Expr::Neg(operand) => {
+                self.coeff = -self.coeff;
+                self.collect(operand, exponent);
+            }
+            _ => match expr.simplify_once() {
+                Expr::Number(value) if exponent > 0.0 => self.coeff *= value,
+                Expr::Number(value) if value != 0.0 && prints_short(self.coeff / value) => {
+                    self.coeff /= value
+                }
+                Expr::Binary(BinOp::Pow, base, power) if matches!(*power, Expr::Number(_)) => {
+                    let power = power.as_number().unwrap();
+                    self.push(*base, power * exponent);
+                }
+                // A nested sum or product that folded away still needs
+                // splitting into its own factors.
+                simplified @ (Expr::Neg(_) | Expr::Binary(BinOp::Mul | BinOp::Div, ..)) => {
+                    self.collect(&simplified, exponent)
+                }
+                simplified => self.push(simplified, exponent),
+            },
+        }
+    }
+
+    fn push(&mut self, base: Expr, exponent: f64) {
+        match self
+            .factors
+            .iter_mut()
+            .find(|(existing, _)| *existing == base)
+        {
+            Some((_, total)) => *total = add(*total, exponent),
+            None => self.factors.push((base, exponent)),
+        }
+    }
+
+    /// The same factors, ignoring order, so `x * y` and `y * x` are alike.
+    fn same_factors(&self, other: &Product) -> bool {
+        self.factors.len() == other.factors.len()
+            && self
+                .factors
+                .iter()
+                .all(|factor| other.factors.contains(factor))
+    }
+
+    fn into_expr(mut self) -> Expr {
+        self.factors.retain(|(_, exponent)| *exponent != 0.0);
+        if self.coeff == 0.0 {
+            return num(0.0);
+        }
+        let mut numerator: Option<Expr> = None;
+        let mut denominator: Option<Expr> = None;
+        // A lone -1 stays a coefficient when there is nothing else on top,
+        // giving `-1 / x` rather than `-(1 / x)`.
+        let has_numerator = self.factors.iter().any(|(_, exponent)| *exponent > 0.0);
+        let negate = self.coeff == -1.0 && has_numerator;
+        if self.coeff.abs() != 1.0 || !has_numerator {
+            numerator = Some(num(self.coeff));
+        }
+        for (base, exponent) in self.factors {
+            let (side, exponent) = if exponent > 0.0 {
+                (&mut numerator, exponent)
+            } else {
+                (&mut denominator, -exponent)
+            };
+            let factor = power(base, num(exponent));
+            *side = Some(match side.take() {
+                Some(acc) => bin(BinOp::Mul, acc, factor),
+                None => factor,
+            });
+        }
+        // The numerator always holds the coefficient or a factor by now.
+        let top = numerator.unwrap();
+        let expr = match denominator {
+            Some(bottom) => bin(BinOp::Div, top, bottom),
+            None => top,
+        };
+        if negate {
+            neg(expr)
+        } else {
+            expr
+        }
+    }
+}
+
+/// A sum of products plus a constant, with like terms merged.
+#[derive(Default)]
+struct Sum {
+    constant: f64,
+    terms: Vec<Product>,
+}
+
+impl Sum {
+    fn collect(&mut self, expr: &Expr, sign: f64) {
+        match expr {
+            Expr::Binary(BinOp::Add, a, b) => {
+                self.collect(a, sign);
+                self.collect(b, sign);
+            }
+            Expr::Binary(BinOp::Sub, a, b) => {
+                self.collect(a, sign);
+                self.collect(b, -sign);
+            }
+            Expr::Neg(operand) => self.collect(operand, -sign),
+            Expr::Number(value) => self.constant = add(self.constant, sign * value),
+            _ => {
+                let mut term = Product::new();
+                term.collect(expr, 1.0);
+                term.coeff *= sign;
+                self.push(term);
+            }
+        }
+    }
+
+    fn push(&mut self, term: Product) {
+        if term.coeff == 0.0 {
+            return;
+        }
+        let folded = term.factors.iter().all(|(_, exponent)| *exponent == 0.0);
+        if term.factors.is_empty() || folded {
+            self.constant = add(self.constant, term.coeff);
+            return;
+        }
+        // A term that simplified back into a sum is spliced in.
+        if let [(inner @ Expr::Binary(BinOp::Add | BinOp::Sub, ..), exponent)] = &term.factors[..] {
+            if *exponent == 1.0 {
+                let inner = inner.clone();
+                return self.collect(&inner, term.coeff);
+            }
+        }
+        match self
+            .terms
+            .iter_mut()
+            .find(|existing| existing.same_factors(&term))
+        {
+            Some(existing) => existing.coeff = add(existing.coeff, term.coeff),
+            None => self.terms.push(term),
+        }
+    }
+
+    fn into_expr(self) -> Expr {
+        let mut expr: Option<Expr> = None;
+        for mut term in self.terms {
+            if term.coeff == 0.0 {
+                continue;
+            }
+            expr = Some(match expr {
+                Some(acc) if term.coeff < 0.0 => {
+                    term.coeff = -term.coeff;
+                    bin(BinOp::Sub, acc, term.into_expr())
+                }
+                Some(acc) => bin(BinOp::Add, acc, term.into_expr()),
+                None => term.into_expr(),
+            });
+        }
+        match expr {
+            Some(acc) if self.constant < 0.0 => bin(BinOp::Sub, acc, num(-self.constant)),
+            Some(acc) if self.constant > 0.0 => bin(BinOp::Add, acc, num(self.constant)),
+            Some(acc) => acc,
+            None => num(self.constant),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn parse(src: &str) -> Expr {
+        Expr::parse(src).unwrap()
+    }
+
+    fn derivative(src: &str) -> String {
+        parse(src).derivative("x").unwrap().to_string()
+    }
+
+    fn simplified(src: &str) -> String {
+        parse(src).simplify().to_string()
+    }
+
+    #[test]
+    fn simplifier_rules() {
+        for (src, expected) in [
+            ("2 + 3 * 4", "14"),
+            ("x + 0", "x"),
+            ("0 + x", "x"),
+            ("x * 1", "x"),
+            ("1 * x - 0", "x"),
+            ("x * 0 + y", "y"),
+            ("x / 1", "x"),
+            ("x ^ 1", "x"),
+            ("x ^ 0", "1"),
+            ("--x", "x"),
+            ("x - x", "0"),
+            ("x + x", "2 * x"),
+            ("2 * x + 3 * x - x", "4 * x"),
+            ("x * y + y * x", "2 * x * y"),
+            ("x * x * x", "x^3"),
+            ("x^2 * x / x^3", "1"),
+            ("x * x + 2 * x - x", "x^2 + x"),
+            ("3 - x + 2", "-x + 5"),
+            ("a - (b - a)", "2 * a - b"),
+            ("1 / 4", "0.25"),
+            ("1 / 3", "1 / 3"),
+            ("x / 3", "x / 3"),
+            ("x / 2", "0.5 * x"),
+            ("2 * (x + 1) - 2", "2 * x"),
+            ("x * (x + 1)", "x * (x + 1)"),
+            ("(x^2)^3", "x^6"),
+            ("(x^2)^0.5", "abs(x)"),
+            ("(x^-4)^0.25", "abs(x)^(-1)"),
+            ("(x^3)^0.5", "x^1.5"),
+            ("sqrt(16) + sqrt(2)", "sqrt(2) + 4"),
+            ("ln(1) + exp(0) * y", "y"),
+            ("-(2 * x)", "-2 * x"),
+            ("-(x * y)", "-(x * y)"),
+        ] {
+            assert_eq!(simplified(src), expected, "{src}");
+        }
+    }
+
+    #[test]
+    fn derivatives() {
+        for (src, expected) in [
+            ("7", "0"),
+            ("y", "0"),
+            ("x", "1"),
+            ("x^2", "2 * x"),
+            ("3 * x^2 + 2 * x - 1", "6 * x + 2"),
+            ("x^3 / 3", "x^2"),
+            ("1 / x", "-1 / x^2"),
+            ("sin(x) * x", "cos(x) * x + sin(x)"),
+            ("cos(x)", "-sin(x)"),
+            ("exp(2 * x)", "2 * exp(2 * x)"),
+            ("ln(x)", "1 / x"),
+            ("ln(x^2)", "2 / x"),
+            ("sqrt(x)", "0.5 / sqrt(x)"),
+            ("2^x", "2^x * ln(2)"),
+            ("x^x", "x^x * (ln(x) + 1)"),
+            ("y * x + max(y, 2)", "y"),
+        ] {
+            assert_eq!(derivative(src), expected, "{src}");
+        }
+        assert_eq!(
+            parse("min(x, 1)").derivative("x"),
+            Err(DiffError::NotDifferentiable(Func::Min))
+        );
+        assert_eq!(
+            DiffError::NotDifferentiable(Func::Max).to_string(),
+            "`max` is not differentiable"
+        );
+    }
+
+    #[test]
+    fn derivatives_match_finite_differences() {
+        let h = 1e-6;
+        for src in [
+            "3 * x^4 - 2 * x^2 + x - 5",
+            "sin(x) * cos(x)",
+            "exp(sin(x)) / (1 + x^2)",
+            "ln(x^2 + 1) * sqrt(x)",
+            "x^x",
+            "abs(x - 2) * x",
+            "(x + 1) / (x - 3)",
+            "2^(x * x) - cos(x)^3",
+        ] {
+            let f = parse(src);
+            let df = f.derivative("x").unwrap();
+            for x in [0.3, 0.9, 1.7, 2.6] {
+                let at = |x: f64| HashMap::from([("x".to_string(), x)]);
+                let exact = df.eval(&at(x)).unwrap();
+                let approx =
+                    (f.eval(&at(x + h)).unwrap() - f.eval(&at(x - h)).unwrap()) / (2.0 * h);
+                let tolerance = 1e-5 * exact.abs().max(1.0);
+                assert!(
+                    (exact - approx).abs() < tolerance,
+                    "{src} at {x}: {df} = {exact}, {approx}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn simplify_preserves_values() {
+        let vars = HashMap::from([("x".to_string(), 1.3), ("y".to_string(), -0.7)]);
+        for src in [
+            "(x + y) * (x - y) - x * x + y^2",
+            "x / y / x * y * 3 - (2 - x) * 4",
+            "-(x - y) - -(y * 2 * x) + x * y * 0.5",
+            "exp(x)^2 * exp(x) + x^2 / x^-1",
+            "(x * y)^2 / (y * x) + 1 / 3 - 2 / 6",
+            "(y^2)^0.5 + x * (y^4)^0.75",
+        ] {
+            let expr = parse(src);
+            let simple = expr.simplify();
+            let (a, b) = (expr.eval(&vars).unwrap(), simple.eval(&vars).unwrap());
+            assert!((a - b).abs() < 1e-9 * a.abs().max(1.0), "{src} -> {simple}");
+            assert_eq!(simple.simplify(), simple, "{src} -> {simple}");
+        }
+    }
+}
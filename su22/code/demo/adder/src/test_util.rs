@@ -0,0 +1,23 @@
+/// xorshift64*, enough to drive randomized tests without a dependency.
+pub struct Rng(pub u64);
+
+impl Rng {
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    pub fn u128(&mut self) -> u128 {
+        // Vary the width so short and long operands both show up.
+        let value = ((self.next() as u128) << 64) | self.next() as u128;
+        value >> (self.next() % 128)
+    }
+
+    /// Uniform-ish integer in `low..=high`.
+    pub fn range(&mut self, low: i64, high: i64) -> i64 {
+        let span = (high as i128 - low as i128 + 1) as u128;
+        (low as i128 + (self.next() as u128 % span) as i128) as i64
+    }
+}
@@ -0,0 +1,765 @@
+//! Physical quantities whose dimensions are checked before they are added.
+//!
+//! `Quantity<D>` carries its dimension in the type, so adding a length to a
+//! time does not compile:
+//!
+//! ```compile_fail
+//! use adder::{Quantity, HOUR, METRE};
+//!
+//! let _ = Quantity::new(1.0, METRE) + Quantity::new(1.0, HOUR);
+//! ```
+//!
+//! `DynQuantity` carries it at runtime instead, for values parsed from text
+//! such as `"3.5 km"`; its additions return a `DimensionError` on mismatch.
+
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use crate::add;
+
+/// Exponents of the base dimensions, e.g. velocity is length¹·time⁻¹.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Dim {
+    pub length: i8,
+    pub mass: i8,
+    pub time: i8,
+}
+
+impl Dim {
+    pub const NONE: Dim = Dim::new(0, 0, 0);
+    pub const LENGTH: Dim = Dim::new(1, 0, 0);
+    pub const MASS: Dim = Dim::new(0, 1, 0);
+    pub const TIME: Dim = Dim::new(0, 0, 1);
+
+    pub const fn new(length: i8, mass: i8, time: i8) -> Self {
+        Dim { length, mass, time }
+    }
+
+    /// Panics if an exponent leaves the `i8` range; see `checked_pow`.
+    pub fn pow(self, exp: i8) -> Self {
+        self.checked_pow(exp).expect("dimension exponent overflow")
+    }
+
+    /// Raises every exponent by `exp`, or `None` if one leaves `i8`.
+    pub fn checked_pow(self, exp: i8) -> Option<Self> {
+        Some(Dim::new(
+            self.length.checked_mul(exp)?,
+            self.mass.checked_mul(exp)?,
+            self.time.checked_mul(exp)?,
+        ))
+    }
+
+    /// The dimension of a product, or `None` if an exponent leaves `i8`.
+    pub fn checked_mul(self, rhs: Dim) -> Option<Self> {
+        Some(Dim::new(
+            self.length.checked_add(rhs.length)?,
+            self.mass.checked_add(rhs.mass)?,
+            self.time.checked_add(rhs.time)?,
+        ))
+    }
+}
+
+/// Panics if an exponent leaves the `i8` range; see `Dim::checked_mul`.
+impl Mul for Dim {
+    type Output = Dim;
+
+    fn mul(self, rhs: Dim) -> Dim {
+        self.checked_mul(rhs).expect("dimension exponent overflow")
+    }
+}
+
+impl Div for Dim {
+    type Output = Dim;
+
+    fn div(self, rhs: Dim) -> Dim {
+        self * rhs.pow(-1)
+    }
+}
+
+/// Formats in SI base units, e.g. `m·s^-2`, or `1` when dimensionless.
+impl Display for Dim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == Dim::NONE {
+            return write!(f, "1");
+        }
+        let parts = [("m", self.length), ("kg", self.mass), ("s", self.time)];
+        let mut first = true;
+        for (symbol, exp) in parts {
+            if exp == 0 {
+                continue;
+            }
+            if !first {
+                write!(f, "·")?;
+            }
+            first = false;
+            match exp {
+                1 => write!(f, "{symbol}")?,
+                _ => write!(f, "{symbol}^{exp}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A type-level dimension for `Quantity`.
+pub trait Dimension {
+    const DIM: Dim;
+}
+
+macro_rules! dimensions {
+    ($($(#[$doc:meta])* $name:ident = $dim:expr;)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+            pub struct $name;
+
+            impl Dimension for $name {
+                const DIM: Dim = $dim;
+            }
+        )*
+    };
+}
+
+dimensions! {
+    Dimensionless = Dim::NONE;
+    Length = Dim::LENGTH;
+    Mass = Dim::MASS;
+    Time = Dim::TIME;
+    Area = Dim::new(2, 0, 0);
+    Velocity = Dim::new(1, 0, -1);
+    Acceleration = Dim::new(1, 0, -2);
+}
+
+/// A unit of dimension `D`, as a factor from the SI base unit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Unit<D> {
+    symbol: &'static str,
+    factor: f64,
+    dim: PhantomData<D>,
+}
+
+impl<D: Dimension> Unit<D> {
+    /// A unit worth `factor` SI base units, e.g. `Unit::new("km", 1000.0)`.
+    pub const fn new(symbol: &'static str, factor: f64) -> Self {
+        Unit {
+            symbol,
+            factor,
+            dim: PhantomData,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        self.symbol
+    }
+
+    pub fn factor(&self) -> f64 {
+        self.factor
+    }
+}
+
+pub const METRE: Unit<Length> = Unit::new("m", 1.0);
+pub const KILOMETRE: Unit<Length> = Unit::new("km", 1e3);
+pub const CENTIMETRE: Unit<Length> = Unit::new("cm", 1e-2);
+pub const MILLIMETRE: Unit<Length> = Unit::new("mm", 1e-3);
+pub const KILOGRAM: Unit<Mass> = Unit::new("kg", 1.0);
+pub const GRAM: Unit<Mass> = Unit::new("g", 1e-3);
+pub const SECOND: Unit<Time> = Unit::new("s", 1.0);
+pub const MILLISECOND: Unit<Time> = Unit::new("ms", 1e-3);
+pub const MINUTE: Unit<Time> = Unit::new("min", 60.0);
+pub const HOUR: Unit<Time> = Unit::new("h", 3600.0);
+pub const SQUARE_METRE: Unit<Area> = Unit::new("m^2", 1.0);
+pub const METRE_PER_SECOND: Unit<Velocity> = Unit::new("m/s", 1.0);
+pub const KILOMETRE_PER_HOUR: Unit<Velocity> = Unit::new("km/h", 1e3 / 3600.0);
+
+/// A value of dimension `D`, stored in SI base units.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Quantity<D> {
+    value: f64,
+    dim: PhantomData<D>,
+}
+
+impl<D: Dimension> Quantity<D> {
+    pub fn new(value: f64, unit: Unit<D>) -> Self {
+        Quantity::from_si(value * unit.factor)
+    }
+
+    pub fn from_si(value: f64) -> Self {
+        Quantity {
+            value,
+            dim: PhantomData,
+        }
+    }
+
+    /// The value in SI base units.
+    pub fn si(&self) -> f64 {
+        self.value
+    }
+
+    /// The value expressed in `unit`, e.g. `3.5 km` in `METRE` is 3500.
+    pub fn to(&self, unit: Unit<D>) -> f64 {
+        self.value / unit.factor
+    }
+
+    pub fn dim(&self) -> Dim {
+        D::DIM
+    }
+}
+
+/// Dimension mismatch between two runtime quantities.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DimensionError {
+    pub expected: Dim,
+    pub found: Dim,
+}
+
+impl Display for DimensionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected dimension {}, found {}",
+            self.expected, self.found
+        )
+    }
+}
+
+impl Error for DimensionError {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseQuantityError {
+    Empty,
+    InvalidNumber(String),
+    UnknownUnit(String),
+    /// A term such as `m^100` pushed an exponent out of the `i8` range.
+    ExponentOverflow(String),
+    Dimension(DimensionError),
+}
+
+impl Display for ParseQuantityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseQuantityError::Empty => write!(f, "cannot parse quantity from empty string"),
+            ParseQuantityError::InvalidNumber(number) => write!(f, "invalid number {number:?}"),
+            ParseQuantityError::UnknownUnit(unit) => write!(f, "unknown unit {unit:?}"),
+            ParseQuantityError::ExponentOverflow(term) => {
+                write!(f, "exponent out of range at {term:?}")
+            }
+            ParseQuantityError::Dimension(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for ParseQuantityError {}
+
+impl From<DimensionError> for ParseQuantityError {
+    fn from(error: DimensionError) -> Self {
+        ParseQuantityError::Dimension(error)
+    }
+}
+
+/// A unit whose dimension is only known at runtime, e.g. parsed `km/h`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DynUnit {
+    pub factor: f64,
+    pub dim: Dim,
+}
+
+const PREFIXES: [(&str, f64); 8] = [
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("µ", 1e-6),
+    ("u", 1e-6),
+    ("n", 1e-9),
+];
+
+/// Symbols that take SI prefixes, with their factor from the base unit.
+const BASE_UNITS: [(&str, f64, Dim); 3] = [
+    ("m", 1.0, Dim::LENGTH),
+    ("g", 1e-3, Dim::MASS),
+    ("s", 1.0, Dim::TIME),
+];
+
+const OTHER_UNITS: [(&str, f64, Dim); 3] = [
+    ("min", 60.0, Dim::TIME),
+    ("h", 3600.0, Dim::TIME),
+    ("d", 86400.0, Dim::TIME),
+];
+
+impl DynUnit {
+    fn symbol(symbol: &str) -> Option<DynUnit> {
+        let unit = |(_, factor, dim): (&str, f64, Dim)| DynUnit { factor, dim };
+        let exact = |name: &str| {
+            OTHER_UNITS
+                .into_iter()
+                .chain(BASE_UNITS)
+                .find(|u| u.0 == name)
+        };
+        if let Some(found) = exact(symbol) {
+            return Some(unit(found));
+        }
+        PREFIXES.iter().find_map(|&(prefix, scale)| {
+            let base = BASE_UNITS
+                .into_iter()
+                .find(|u| symbol.strip_prefix(prefix) == Some(u.0))?;
+            let DynUnit { factor, dim } = unit(base);
+            Some(DynUnit {
+                factor: factor * scale,
+                dim,
+            })
+        })
+    }
+}
+
+/// Parses products and quotients of prefixed symbols with integer powers,
+/// e.g. `km`, `m/s^2` or `kg*m/s^2`. Each `/` divides by the next symbol only.
+impl FromStr for DynUnit {
+    type Err = ParseQuantityError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let mut unit = DynUnit {
+            factor: 1.0,
+            dim: Dim::NONE,
+        };
+        let (mut op, mut start) = ('*', 0);
+        for (i, c) in src.char_indices().chain([(src.len(), '*')]) {
+            if !matches!(c, '*' | '/' | '·') {
+                continue;
+            }
+            let term = &src[start..i];
+            let unknown = || ParseQuantityError::UnknownUnit(term.to_string());
+            let (symbol, exp) = match term.split_once('^') {
+                Some((symbol, exp)) => (symbol, exp.parse::<i8>().map_err(|_| unknown())?),
+                None => (term, 1),
+            };
+            let base = DynUnit::symbol(symbol).ok_or_else(unknown)?;
+            let overflow = || ParseQuantityError::ExponentOverflow(term.to_string());
+            let exp = match op {
+                '/' => exp.checked_neg().ok_or_else(overflow)?,
+                _ => exp,
+            };
+            let dim = base.dim.checked_pow(exp).ok_or_else(overflow)?;
+            unit = DynUnit {
+                factor: unit.factor * base.factor.powi(exp as i32),
+                dim: unit.dim.checked_mul(dim).ok_or_else(overflow)?,
+            };
+            (op, start) = (c, i + c.len_utf8());
+        }
+        Ok(unit)
+    }
+}
+
+impl<D: Dimension> From<Unit<D>> for DynUnit {
+    fn from(unit: Unit<D>) -> Self {
+        DynUnit {
+            factor: unit.factor,
+            dim: D::DIM,
+        }
+    }
+}
+
+/// A value with a runtime dimension, stored in SI base units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DynQuantity {
+    value: f64,
+    dim: Dim,
+}
+
+impl DynQuantity {
+    pub fn new(value: f64, unit: DynUnit) -> Self {
+        DynQuantity {
+            value: value * unit.factor,
+            dim: unit.dim,
+        }
+    }
+
+    pub fn si(&self) -> f64 {
+        self.value
+    }
+
+    pub fn dim(&self) -> Dim {
+        self.dim
+    }
+
+    pub fn checked_add(&self, rhs: &DynQuantity) -> Result<Self, DimensionError> {
+        self.expect(rhs.dim)?;
+        Ok(DynQuantity {
+            value: add(self.value, rhs.value),
+            dim: self.dim,
+        })
+    }
+
+    pub fn checked_sub(&self, rhs: &DynQuantity) -> Result<Self, DimensionError> {
+        self.checked_add(&-*rhs)
+    }
+
+    /// The value expressed in `unit`, if the dimensions agree.
+    pub fn to(&self, unit: impl Into<DynUnit>) -> Result<f64, DimensionError> {
+        let unit = unit.into();
+        self.expect(unit.dim)?;
+        Ok(self.value / unit.factor)
+    }
+
+    fn expect(&self, dim: Dim) -> Result<(), DimensionError> {
+        if self.dim == dim {
+            Ok(())
+        } else {
+            Err(DimensionError {
+                expected: self.dim,
+                found: dim,
+            })
+        }
+    }
+}
+
+/// Parses a number, whitespace and a unit, e.g. `3.5 km` or `9.81 m/s^2`.
+/// A bare number is dimensionless.
+impl FromStr for DynQuantity {
+    type Err = ParseQuantityError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let src = src.trim();
+        if src.is_empty() {
+            return Err(ParseQuantityError::Empty);
+        }
+        let (number, unit) = match src.split_once(char::is_whitespace) {
+            Some((number, unit)) => (number, unit.trim_start().parse()?),
+            None => (src, DynUnit::from(Unit::<Dimensionless>::new("", 1.0))),
+        };
+        let value = number
+            .parse::<f64>()
+            .map_err(|_| ParseQuantityError::InvalidNumber(number.to_string()))?;
+        Ok(DynQuantity::new(value, unit))
+    }
+}
+
+impl<D: Dimension> From<Quantity<D>> for DynQuantity {
+    fn from(quantity: Quantity<D>) -> Self {
+        DynQuantity {
+            value: quantity.value,
+            dim: D::DIM,
+        }
+    }
+}
+
+impl<D: Dimension> TryFrom<DynQuantity> for Quantity<D> {
+    type Error = DimensionError;
+
+    fn try_from(quantity: DynQuantity) -> Result<Self, Self::Error> {
+        if quantity.dim == D::DIM {
+            Ok(Quantity::from_si(quantity.value))
+        } else {
+            Err(DimensionError {
+                expected: D::DIM,
+                found: quantity.dim,
+            })
+        }
+    }
+}
+
+impl<D: Dimension> FromStr for Quantity<D> {
+    type Err = ParseQuantityError;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Ok(Quantity::try_from(src.parse::<DynQuantity>()?)?)
+    }
+}
+
+impl<D: Dimension> Add for Quantity<D> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Quantity::from_si(add(self.value, rhs.value))
+    }
+}
+
+impl<D: Dimension> Sub for Quantity<D> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Quantity::from_si(self.value - rhs.value)
+    }
+}
+
+impl<D: Dimension> Neg for Quantity<D> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Quantity::from_si(-self.value)
+    }
+}
+
+impl<D: Dimension> Mul<f64> for Quantity<D> {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Quantity::from_si(self.value * rhs)
+    }
+}
+
+impl<D: Dimension> Div<f64> for Quantity<D> {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self {
+        Quantity::from_si(self.value / rhs)
+    }
+}
+
+/// The ratio of two quantities of the same dimension is a plain number.
+impl<D: Dimension> Div for Quantity<D> {
+    type Output = f64;
+
+    fn div(self, rhs: Self) -> f64 {
+        self.value / rhs.value
+    }
+}
+
+/// Products and quotients between the named dimensions; anything else goes
+/// through `DynQuantity`.
+macro_rules! combine {
+    ($($a:ident $op:tt $b:ident = $out:ident;)*) => {
+        $(
+            combine!(@impl $a $op $b = $out);
+        )*
+    };
+    (@impl $a:ident * $b:ident = $out:ident) => {
+        impl Mul<Quantity<$b>> for Quantity<$a> {
+            type Output = Quantity<$out>;
+
+            fn mul(self, rhs: Quantity<$b>) -> Quantity<$out> {
+                Quantity::from_si(self.value * rhs.value)
+            }
+        }
+    };
+    (@impl $a:ident / $b:ident = $out:ident) => {
+        impl Div<Quantity<$b>> for Quantity<$a> {
+            type Output = Quantity<$out>;
+
+            fn div(self, rhs: Quantity<$b>) -> Quantity<$out> {
+                Quantity::from_si(self.value / rhs.value)
+            }
+        }
+    };
+}
+
+combine! {
+    Length * Length = Area;
+    Area / Length = Length;
+    Length / Time = Velocity;
+    Velocity * Time = Length;
+    Time * Velocity = Length;
+    Velocity / Time = Acceleration;
+    Acceleration * Time = Velocity;
+}
+
+impl Neg for DynQuantity {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        DynQuantity {
+            value: -self.value,
+            dim: self.dim,
+        }
+    }
+}
+
+/// Panics if an exponent of the product's dimension leaves `i8`.
+impl Mul for DynQuantity {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        DynQuantity {
+            value: self.value * rhs.value,
+            dim: self.dim * rhs.dim,
+        }
+    }
+}
+
+/// Panics if an exponent of the quotient's dimension leaves `i8`.
+impl Div for DynQuantity {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        DynQuantity {
+            value: self.value / rhs.value,
+            dim: self.dim / rhs.dim,
+        }
+    }
+}
+
+/// Formats in SI base units, e.g. `3500 m`; a precision applies to the value.
+impl<D: Dimension> Display for Quantity<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let quantity = DynQuantity {
+            value: self.value,
+            dim: D::DIM,
+        };
+        Display::fmt(&quantity, f)
+    }
+}
+
+impl Display for DynQuantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.value, f)?;
+        if self.dim != Dim::NONE {
+            write!(f, " {}", self.dim)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+            "{actual} is not close to {expected}"
+        );
+    }
+
+    #[test]
+    fn typed_arithmetic() {
+        let leg = Quantity::new(3.5, KILOMETRE) + Quantity::new(250.0, METRE);
+        assert_eq!(leg.to(METRE), 3750.0);
+        assert_eq!(leg.to(KILOMETRE), 3.75);
+        let time = Quantity::new(0.5, HOUR) - Quantity::new(15.0, MINUTE);
+        assert_eq!(time.to(SECOND), 900.0);
+
+        let speed: Quantity<Velocity> = leg / time;
+        assert_close(speed.to(KILOMETRE_PER_HOUR), 15.0);
+        let area = Quantity::new(2.0, METRE) * Quantity::new(50.0, CENTIMETRE);
+        assert_eq!(area.to(SQUARE_METRE), 1.0);
+        assert_eq!(area / Quantity::new(1.0, METRE), Quantity::new(1.0, METRE));
+        assert_eq!(leg / Quantity::new(1.0, KILOMETRE), 3.75);
+        assert_eq!((leg * 2.0).to(KILOMETRE), 7.5);
+        let accel: Quantity<Acceleration> = speed / time;
+        assert_close((accel * time * time).si(), leg.si());
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Quantity::new(2.0, HOUR).to(SECOND), 7200.0);
+        assert_eq!(Quantity::new(7200.0, SECOND).to(HOUR), 2.0);
+        assert_eq!(Quantity::new(1500.0, GRAM).to(KILOGRAM), 1.5);
+        assert_eq!(Quantity::new(12.0, MILLIMETRE).to(CENTIMETRE), 1.2);
+        assert_close(
+            Quantity::new(36.0, KILOMETRE_PER_HOUR).to(METRE_PER_SECOND),
+            10.0,
+        );
+    }
+
+    #[test]
+    fn parsing() {
+        let q: DynQuantity = "3.5 km".parse().unwrap();
+        assert_eq!(q.dim(), Dim::LENGTH);
+        assert_eq!(q.to(METRE), Ok(3500.0));
+        let cases = [
+            ("2 h", 7200.0, Dim::TIME),
+            ("90 min", 5400.0, Dim::TIME),
+            ("250 ms", 0.25, Dim::TIME),
+            ("12 mm", 0.012, Dim::LENGTH),
+            ("3 kg", 3.0, Dim::MASS),
+            ("9.81 m/s^2", 9.81, Dim::new(1, 0, -2)),
+            ("2 kg*m/s^2", 2.0, Dim::new(1, 1, -2)),
+            ("1 m^2", 1.0, Dim::new(2, 0, 0)),
+            ("4 s^-1", 4.0, Dim::new(0, 0, -1)),
+            ("  7   µm ", 7e-6, Dim::LENGTH),
+            ("42", 42.0, Dim::NONE),
+        ];
+        for (src, si, dim) in cases {
+            let q: DynQuantity = src.parse().unwrap();
+            assert_close(q.si(), si);
+            assert_eq!(q.dim(), dim, "{src}");
+        }
+        let speed: Quantity<Velocity> = "72 km/h".parse().unwrap();
+        assert_close(speed.to(METRE_PER_SECOND), 20.0);
+
+        assert_eq!("".parse::<DynQuantity>(), Err(ParseQuantityError::Empty));
+        assert_eq!(
+            "x km".parse::<DynQuantity>(),
+            Err(ParseQuantityError::InvalidNumber("x".into()))
+        );
+        assert_eq!(
+            "3 furlong".parse::<DynQuantity>(),
+            Err(ParseQuantityError::UnknownUnit("furlong".into()))
+        );
+        assert_eq!(
+            "3 m/".parse::<DynQuantity>(),
+            Err(ParseQuantityError::UnknownUnit("".into()))
+        );
+        assert_eq!(
+            "1 s/m^-128".parse::<DynQuantity>(),
+            Err(ParseQuantityError::ExponentOverflow("m^-128".into()))
+        );
+        assert_eq!(
+            "1 m^100*m^100".parse::<DynQuantity>(),
+            Err(ParseQuantityError::ExponentOverflow("m^100".into()))
+        );
+        assert_eq!(
+            "1 kg^127".parse::<DynQuantity>().map(|q| q.dim()),
+            Ok(Dim::new(0, 127, 0))
+        );
+        assert_eq!(
+            "1 g^-128/s^127*s^-2".parse::<DynQuantity>(),
+            Err(ParseQuantityError::ExponentOverflow("s^-2".into()))
+        );
+        assert_eq!(
+            ParseQuantityError::ExponentOverflow("m^100".into()).to_string(),
+            "exponent out of range at \"m^100\""
+        );
+        assert_eq!(Dim::LENGTH.checked_pow(-128), Some(Dim::new(-128, 0, 0)));
+        assert_eq!(Dim::new(2, 0, 0).checked_pow(64), None);
+        assert_eq!(Dim::new(100, 0, 0).checked_mul(Dim::new(28, 0, 0)), None);
+        assert_eq!(
+            "3 s".parse::<Quantity<Length>>(),
+            Err(ParseQuantityError::Dimension(DimensionError {
+                expected: Dim::LENGTH,
+                found: Dim::TIME,
+            }))
+        );
+    }
+
+    #[test]
+    fn runtime_mismatch() {
+        let length: DynQuantity = "3.5 km".parse().unwrap();
+        let time: DynQuantity = "2 h".parse().unwrap();
+        let error = length.checked_add(&time).unwrap_err();
+        assert_eq!(
+            error,
+            DimensionError {
+                expected: Dim::LENGTH,
+                found: Dim::TIME
+            }
+        );
+        assert_eq!(error.to_string(), "expected dimension m, found s");
+        assert!(length.checked_sub(&time).is_err());
+        assert!(time.to(METRE).is_err());
+        assert_eq!(length.checked_add(&length).unwrap().to(KILOMETRE), Ok(7.0));
+
+        let speed = length / time;
+        assert_eq!(speed.dim(), Dim::new(1, 0, -1));
+        assert_close(speed.to(KILOMETRE_PER_HOUR).unwrap(), 1.75);
+        assert!(Quantity::<Velocity>::try_from(speed).is_ok());
+        assert!(Quantity::<Length>::try_from(speed).is_err());
+        assert_eq!(
+            DynQuantity::from(Quantity::new(1.0, KILOMETRE)).to(METRE),
+            Ok(1000.0)
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Quantity::new(3.5, KILOMETRE).to_string(), "3500 m");
+        assert_eq!(format!("{:.1}", Quantity::new(1.0, HOUR)), "3600.0 s");
+        let accel: DynQuantity = "9.81 kg*m/s^2".parse().unwrap();
+        assert_eq!(accel.to_string(), "9.81 m·kg·s^-2");
+        assert_eq!("5".parse::<DynQuantity>().unwrap().to_string(), "5");
+        assert_eq!(Dim::NONE.to_string(), "1");
+    }
+}
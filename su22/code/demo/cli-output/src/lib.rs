@@ -0,0 +1,29 @@
+//! Output formats shared by the `plus` and `add` command-line tools, and
+//! the snapshot checks their tests use.
+//!
+//! Both tools print a result in one of four [`OutputFormat`]s. Plain and
+//! table are for people and send diagnostics to stderr. The two JSON
+//! formats keep everything on stdout and share one schema:
+//!
+//! - `json` prints one document once the input is done:
+//!   `{"ok": bool, ..., "diagnostics": [diagnostic, ...]}`, where the
+//!   members in between describe the tool's result and are null on error.
+//! - `ndjson` prints one object per line, each with a `"type"`: a
+//!   `"diagnostic"` as soon as it happens, any tool-specific records, and
+//!   last a `"result"` unless the run failed.
+//! - A diagnostic is `{"severity": "warning" | "error", "position": n |
+//!   null, "input": string | null, "message": string}`. `position` counts
+//!   the inputs from 1, lines for `add` and numbers for `plus`, and is null
+//!   when the failure is not about one input. `input` is that input as
+//!   given, when the tool keeps it.
+//!
+//! Decimals are written as strings so that no digits are lost.
+
+mod output;
+mod snapshot;
+
+pub use output::{
+    json_document, json_or_null, json_string, ndjson_record, text_table, Diagnostic, OutputFormat,
+    ParseFormatError, Severity,
+};
+pub use snapshot::{snapshot, snapshots, FORMATS};
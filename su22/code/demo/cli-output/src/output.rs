@@ -0,0 +1,255 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// How a tool writes its result and diagnostics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The result as text, with diagnostics on stderr.
+    Plain,
+    /// Aligned columns, with diagnostics on stderr.
+    Table,
+    /// One JSON document on stdout.
+    Json,
+    /// One JSON object per line on stdout.
+    Ndjson,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseFormatError {
+    pub found: String,
+}
+
+impl Display for ParseFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid format {:?} (expected plain, table, json or ndjson)",
+            self.found
+        )
+    }
+}
+
+impl Error for ParseFormatError {}
+
+impl FromStr for OutputFormat {
+    type Err = ParseFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(OutputFormat::Plain),
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            _ => Err(ParseFormatError {
+                found: s.to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The input was skipped and the run carried on.
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A warning or error as the JSON formats report it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Which input, counting from 1.
+    pub position: Option<u64>,
+    /// The input as given.
+    pub input: Option<String>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn members(&self) -> String {
+        format!(
+            "\"severity\":{},\"position\":{},\"input\":{},\"message\":{}",
+            json_string(&self.severity.to_string()),
+            json_or_null(self.position.map(|position| position.to_string())),
+            json_or_null(self.input.as_deref().map(json_string)),
+            json_string(&self.message)
+        )
+    }
+
+    pub fn to_json(&self) -> String {
+        format!("{{{}}}", self.members())
+    }
+
+    /// The diagnostic as an ndjson line, newline included.
+    pub fn to_ndjson(&self) -> String {
+        ndjson_record("diagnostic", &self.members())
+    }
+}
+
+/// `text` as a JSON string literal.
+pub fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// An already encoded JSON value, or `null`.
+pub fn json_or_null(value: Option<String>) -> String {
+    value.unwrap_or_else(|| "null".to_string())
+}
+
+/// The `json` document: `ok`, then the encoded `members`, then the
+/// diagnostics, newline included.
+pub fn json_document(ok: bool, members: &str, diagnostics: &[Diagnostic]) -> String {
+    let diagnostics: Vec<String> = diagnostics.iter().map(Diagnostic::to_json).collect();
+    let separator = if members.is_empty() { "" } else { "," };
+    format!(
+        "{{\"ok\":{ok}{separator}{members},\"diagnostics\":[{}]}}\n",
+        diagnostics.join(",")
+    )
+}
+
+/// One `ndjson` line of the given type, newline included.
+pub fn ndjson_record(kind: &str, members: &str) -> String {
+    let separator = if members.is_empty() { "" } else { "," };
+    format!("{{\"type\":{}{separator}{members}}}\n", json_string(kind))
+}
+
+/// Lines `rows` up under `headers` and a rule. The first `left` columns
+/// are left-aligned; the rest hold numbers and are right-aligned.
+pub fn text_table(headers: &[&str], rows: &[Vec<String>], left: usize) -> String {
+    let mut widths: Vec<usize> = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let rule: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+    let headers: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+    let mut out = String::new();
+    for row in [&headers, &rule].into_iter().chain(rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| {
+                if i < left {
+                    format!("{cell:<width$}")
+                } else {
+                    format!("{cell:>width$}")
+                }
+            })
+            .collect();
+        out.push_str(cells.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_strings() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(
+            json_string("say \"hi\"\\\n\tnow\u{1}é"),
+            "\"say \\\"hi\\\"\\\\\\n\\tnow\\u0001é\""
+        );
+        assert_eq!(json_or_null(None), "null");
+        assert_eq!(json_or_null(Some(json_string("x"))), "\"x\"");
+    }
+
+    #[test]
+    fn diagnostics() {
+        let warning = Diagnostic {
+            severity: Severity::Warning,
+            position: Some(3),
+            input: Some("x".into()),
+            message: "invalid number".into(),
+        };
+        assert_eq!(
+            warning.to_json(),
+            "{\"severity\":\"warning\",\"position\":3,\"input\":\"x\",\"message\":\"invalid number\"}"
+        );
+        let error = Diagnostic {
+            severity: Severity::Error,
+            position: None,
+            input: None,
+            message: "overflow".into(),
+        };
+        assert_eq!(
+            error.to_ndjson(),
+            "{\"type\":\"diagnostic\",\"severity\":\"error\",\"position\":null,\"input\":null,\"message\":\"overflow\"}\n"
+        );
+        assert_eq!(
+            json_document(false, "\"sum\":null", &[warning, error]),
+            "{\"ok\":false,\"sum\":null,\"diagnostics\":[\
+             {\"severity\":\"warning\",\"position\":3,\"input\":\"x\",\"message\":\"invalid number\"},\
+             {\"severity\":\"error\",\"position\":null,\"input\":null,\"message\":\"overflow\"}]}\n"
+        );
+        assert_eq!(
+            json_document(true, "", &[]),
+            "{\"ok\":true,\"diagnostics\":[]}\n"
+        );
+        assert_eq!(ndjson_record("result", ""), "{\"type\":\"result\"}\n");
+    }
+
+    #[test]
+    fn tables() {
+        let rows = vec![
+            vec!["east".to_string(), "7.25".to_string(), "2".to_string()],
+            vec![
+                "north-west".to_string(),
+                "-10".to_string(),
+                "12".to_string(),
+            ],
+        ];
+        assert_eq!(
+            text_table(&["group", "sum", "count"], &rows, 1),
+            "\
+group        sum  count
+----------  ----  -----
+east        7.25      2
+north-west   -10     12
+"
+        );
+        assert_eq!(text_table(&["sum"], &[], 0), "sum\n---\n");
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!("ndjson".parse(), Ok(OutputFormat::Ndjson));
+        let error = "yaml".parse::<OutputFormat>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid format \"yaml\" (expected plain, table, json or ndjson)"
+        );
+    }
+}
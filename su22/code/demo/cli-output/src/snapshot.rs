@@ -0,0 +1,62 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// The output formats every snapshot scenario runs through.
+pub const FORMATS: [&str; 4] = ["plain", "table", "json", "ndjson"];
+
+/// Compares `actual` with the file `name` in `dir`, usually the calling
+/// crate's `tests/snapshots`. Run the tests with `UPDATE_SNAPSHOTS=1` to
+/// write the files instead, then review the diff.
+pub fn snapshot(dir: &Path, name: &str, actual: &str) {
+    let path = dir.join(name);
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}; run with UPDATE_SNAPSHOTS=1",
+            path.display()
+        )
+    });
+    assert_eq!(actual, expected, "snapshot {name} is out of date");
+}
+
+/// Pins every output format for one scenario, stdout and stderr alike.
+/// `run` gets the format name and returns the exit code, stdout and stderr.
+pub fn snapshots(dir: &Path, scenario: &str, run: impl Fn(&str) -> (i32, String, String)) {
+    for format in FORMATS {
+        let (code, stdout, stderr) = run(format);
+        snapshot(
+            dir,
+            &format!("{scenario}.{format}.txt"),
+            &format!("exit: {code}\n--- stdout\n{stdout}--- stderr\n{stderr}"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_and_writes_files() {
+        let dir = env::temp_dir().join(format!("cli-output-snapshots-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("one.plain.txt"),
+            "exit: 0\n--- stdout\n1\n--- stderr\n",
+        )
+        .unwrap();
+        snapshot(
+            &dir,
+            "one.plain.txt",
+            "exit: 0\n--- stdout\n1\n--- stderr\n",
+        );
+        let mismatch = std::panic::catch_unwind(|| snapshot(&dir, "one.plain.txt", "other"));
+        assert!(mismatch.is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}